@@ -1,7 +1,7 @@
 //! VTT-CLI - Voice-to-Text Command Line Tool
 //!
 //! This tool provides end-to-end speech-to-text functionality:
-//! - Record audio from microphone
+//! - Record audio from microphone, or read it from an audio/video file
 //! - Transcribe using Whisper
 //! - Save transcription to file
 
@@ -9,8 +9,11 @@ use clap::Parser;
 use std::path::PathBuf;
 use std::thread;
 use std::time::Duration;
-use vtt_core::audio::{write_wav, AudioCapture, AudioFormat};
-use vtt_core::whisper::{WhisperConfig, WhisperContext};
+use vtt_core::audio::{decode_file, write_wav, AudioCapture, AudioFormat};
+use vtt_core::config::schema::AudioConfig;
+use vtt_core::config::ConfigLoader;
+use vtt_core::export::{export, ExportFormat, ExportOptions};
+use vtt_core::whisper::{display_name, DecodingOverrides, DecodingPreset, Task, WhisperConfig, WhisperContext};
 
 /// VTT-CLI: Voice-to-Text Command Line Tool
 #[derive(Parser, Debug)]
@@ -23,7 +26,11 @@ struct Args {
     #[arg(short, long, default_value = "5")]
     duration: u64,
 
-    /// Path to Whisper model file (default: models/ggml-base.bin)
+    /// Transcribe this audio or video file instead of recording
+    #[arg(short, long)]
+    input: Option<PathBuf>,
+
+    /// Path to Whisper model file (default: from config, models/ggml-base.bin)
     #[arg(short, long)]
     model: Option<String>,
 
@@ -31,10 +38,50 @@ struct Args {
     #[arg(short, long)]
     output: Option<String>,
 
-    /// Number of threads for transcription (default: auto)
+    /// Format of the --output file: txt, srt, vtt, json or md
+    #[arg(short, long, default_value = "txt")]
+    format: ExportFormat,
+
+    /// Maximum characters per subtitle line (srt/vtt)
+    #[arg(long, default_value = "42")]
+    max_line_chars: usize,
+
+    /// Maximum subtitle duration in milliseconds (srt/vtt)
+    #[arg(long, default_value = "7000")]
+    max_caption_ms: u64,
+
+    /// Number of threads for transcription (default: from config)
     #[arg(short, long)]
     threads: Option<i32>,
 
+    /// transcribe, or translate to English
+    #[arg(long, default_value = "transcribe")]
+    task: Task,
+
+    /// With --task translate, also print the transcript in the spoken language
+    #[arg(long)]
+    include_original: bool,
+
+    /// Text to prime Whisper with, e.g. the topic or expected spelling (default: from config)
+    #[arg(long)]
+    prompt: Option<String>,
+
+    /// Comma-separated names and jargon Whisper should spell this way (default: from config)
+    #[arg(long, value_delimiter = ',')]
+    vocabulary: Vec<String>,
+
+    /// Decoding preset: fast, balanced or accurate (default: from config)
+    #[arg(long)]
+    decoding: Option<DecodingPreset>,
+
+    /// Beams for beam search, 1 (greedy) to 8 (default: from the preset)
+    #[arg(long)]
+    beam_size: Option<u32>,
+
+    /// Config file layered over the system and user config
+    #[arg(short, long)]
+    config: Option<PathBuf>,
+
     /// List available audio devices and exit
     #[arg(long)]
     list_devices: bool,
@@ -53,55 +100,102 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
         return Ok(());
     }
 
+    // Flags win over config files and VTT_* environment variables
+    let config = ConfigLoader::load_with(args.config.as_deref())?;
+    let model_path = args
+        .model
+        .clone()
+        .unwrap_or_else(|| config.whisper.resolved_model_path());
+    let threads = args.threads.unwrap_or(config.whisper.resolved_threads() as i32);
+    let mut decoding = match args.decoding {
+        // A preset on the command line replaces the configured settings
+        Some(preset) => DecodingOverrides { preset: Some(preset), ..Default::default() },
+        None => config.whisper.decoding.clone(),
+    };
+    decoding.beam_size = args.beam_size.or(decoding.beam_size);
+    let decoding = decoding.resolve();
+    decoding.validate()?;
+
     println!("🎤 VTT-CLI - Voice to Text\n");
     println!("Configuration:");
-    println!("  Duration: {}s", args.duration);
-    println!(
-        "  Model: {}",
-        args.model.as_deref().unwrap_or("models/ggml-base.bin")
-    );
-    if let Some(threads) = args.threads {
-        println!("  Threads: {}", threads);
+    match args.input {
+        Some(ref input) => println!("  Input: {}", input.display()),
+        None => println!("  Duration: {}s", args.duration),
+    }
+    println!("  Model: {}", model_path);
+    println!("  Threads: {}", threads);
+    println!("  Task: {}", args.task);
+    if decoding.is_beam_search() {
+        println!("  Decoding: beam search, {} beams", decoding.beam_size);
+    } else {
+        println!("  Decoding: greedy");
     }
     if let Some(ref audio_path) = args.save_audio {
         println!("  Save audio: {}", audio_path.display());
     }
     println!();
 
-    // Step 1: Record audio
-    println!("📻 Recording audio...");
-    let audio_data = record_audio(args.duration)?;
-    let sample_rate = 48000; // PipeWire default
-    println!(
-        "✓ Captured {} samples ({}s)",
-        audio_data.len(),
-        args.duration
-    );
+    // Step 1: Record or decode audio
+    let (audio_data, format) = match args.input {
+        Some(ref input) => {
+            println!("📂 Decoding {}...", input.display());
+            let audio = decode_file(input)?;
+            println!(
+                "✓ Decoded {} Hz, {} channel(s), {:.1}s",
+                audio.format.sample_rate,
+                audio.format.channels,
+                audio.duration_ms() as f64 / 1000.0
+            );
+            (audio.samples, audio.format)
+        }
+        None => {
+            println!("📻 Recording audio...");
+            let recorded = record_audio(args.duration, &config.audio)?;
+            println!(
+                "✓ Captured {} samples ({}s)",
+                recorded.0.len(),
+                args.duration
+            );
+            recorded
+        }
+    };
 
     // Save audio if requested
     if let Some(ref audio_path) = args.save_audio {
-        let format = AudioFormat::DEFAULT;
         write_wav(audio_path.to_str().unwrap(), &audio_data, &format)?;
         println!("✓ Saved audio to: {}", audio_path.display());
     }
 
     // Step 2: Load Whisper model
     println!("\n🧠 Loading Whisper model...");
-    let model_path = args
-        .model
-        .unwrap_or_else(|| "models/ggml-base.bin".to_string());
-
-    let mut config = WhisperConfig::default().with_model_path(&model_path);
-    if let Some(threads) = args.threads {
-        config = config.with_threads(threads);
-    }
+    let whisper_config = WhisperConfig::default()
+        .with_model_path(&model_path)
+        .with_threads(threads)
+        .with_gpu(config.whisper.enable_gpu)
+        .with_decoding(decoding)
+        .with_task(args.task)
+        .with_initial_prompt(args.prompt.clone().or(config.whisper.initial_prompt.clone()))
+        .with_vocabulary(if args.vocabulary.is_empty() {
+            config.whisper.vocabulary.clone()
+        } else {
+            args.vocabulary.clone()
+        });
 
-    let ctx = WhisperContext::new(config)?;
+    let ctx = WhisperContext::new(whisper_config)?;
     println!("✓ Model loaded successfully");
 
     // Step 3: Transcribe
     println!("\n🔊 Transcribing...");
-    let result = ctx.transcribe(&audio_data, sample_rate)?;
+    let samples = format.to_mono(&audio_data);
+    let result = ctx.transcribe(&samples, format.sample_rate)?;
+    let original = if args.include_original && args.task == Task::Translate {
+        println!("🔊 Transcribing in the spoken language...");
+        let mut config = ctx.config().clone().with_task(Task::Transcribe);
+        config.language = result.language.clone().or(config.language);
+        Some(ctx.with_config(config).transcribe(&samples, format.sample_rate)?)
+    } else {
+        None
+    };
 
     println!("✓ Transcription complete!");
     println!("\n─────────────────────────────────────");
@@ -109,13 +203,39 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
     println!("─────────────────────────────────────");
     println!("{}", result.text);
     println!("─────────────────────────────────────");
+    if let Some(ref original) = original {
+        println!("ORIGINAL:");
+        println!("─────────────────────────────────────");
+        println!("{}", original.text);
+        println!("─────────────────────────────────────");
+    }
     println!("Duration: {}ms", result.duration_ms());
+    if let Some(ref language) = result.language {
+        println!("Language: {}", display_name(language));
+    }
     println!("─────────────────────────────────────");
+    if result.segments.len() > 1 {
+        for segment in &result.segments {
+            println!(
+                "[{:.2}s - {:.2}s] {}",
+                segment.start_ms as f64 / 1000.0,
+                segment.end_ms as f64 / 1000.0,
+                segment.text
+            );
+        }
+        println!("─────────────────────────────────────");
+    }
 
     // Save to file if requested
     if let Some(output_path) = args.output {
-        std::fs::write(&output_path, &result.text)?;
-        println!("✓ Saved transcription to: {}", output_path);
+        let options = ExportOptions {
+            max_line_chars: args.max_line_chars,
+            max_caption_ms: args.max_caption_ms,
+            ..Default::default()
+        };
+        let content = export(&result.segments, args.format, &options)?;
+        std::fs::write(&output_path, content)?;
+        println!("✓ Saved {} transcription to: {}", args.format, output_path);
     }
 
     Ok(())
@@ -133,15 +253,23 @@ fn list_devices() -> Result<(), Box<dyn std::error::Error>> {
     println!("Available audio input devices:");
     for dev in &devices {
         let marker = if dev.is_default { " (default)" } else { "" };
-        println!("  {}{}", dev.name, marker);
+        if dev.id == dev.name {
+            println!("  {}{}", dev.name, marker);
+        } else {
+            println!("  {} [{}]{}", dev.name, dev.id, marker);
+        }
     }
 
     Ok(())
 }
 
 /// Record audio for the specified duration
-fn record_audio(duration_secs: u64) -> Result<Vec<f32>, Box<dyn std::error::Error>> {
-    let mut capture = AudioCapture::with_format(AudioFormat::DEFAULT)?;
+///
+/// Captures in the `[audio]` format and channel policy. Returns the captured
+/// samples together with the format the backend delivered.
+fn record_audio(duration_secs: u64, audio: &AudioConfig) -> Result<(Vec<f32>, AudioFormat), Box<dyn std::error::Error>> {
+    let mut capture = AudioCapture::with_format(audio.capture_format())?
+        .with_channel_policy(audio.channel_policy);
 
     capture.start()?;
     println!("  Recording... (speak now!)");
@@ -151,7 +279,7 @@ fn record_audio(duration_secs: u64) -> Result<Vec<f32>, Box<dyn std::error::Erro
     capture.stop()?;
     let samples = capture.take_buffer();
 
-    Ok(samples)
+    Ok((samples, capture.format()))
 }
 
 pub const VERSION: &str = env!("CARGO_PKG_VERSION");
@@ -6,12 +6,12 @@ use super::pipewire_capture::PipeWireCapture as Impl;
 #[cfg(not(target_os = "linux"))]
 use super::cpal_capture::CpalCapture as Impl;
 
-use super::{AudioFormat, AudioResult};
+use super::{AudioDevice, AudioFormat, AudioResult, ChannelPolicy};
 
 /// Audio capture device abstraction
 /// 
 /// This struct provides a cross-platform interface for capturing audio
-/// from the default input device (microphone).
+/// from the default input device (microphone) or a chosen device.
 #[derive(Debug, Clone)]
 pub struct AudioCapture {
     inner: Impl,
@@ -32,7 +32,33 @@ impl AudioCapture {
         })
     }
     
+    /// Create a new audio capture instance for a specific input device
+    ///
+    /// `device` should come from [`list_devices`](super::list_devices).
+    pub fn with_device(device: &AudioDevice, fmt: AudioFormat) -> AudioResult<Self> {
+        Ok(Self {
+            inner: Impl::with_device(&device.id, fmt)?,
+        })
+    }
+    
+    /// Choose how the device's channels become the captured samples
+    ///
+    /// Defaults to [`ChannelPolicy::Downmix`]. Takes effect on the next
+    /// [`start`](Self::start).
+    pub fn with_channel_policy(mut self, policy: ChannelPolicy) -> Self {
+        self.inner.set_channel_policy(policy);
+        self
+    }
+
+    /// How the device's channels become the captured samples
+    pub fn channel_policy(&self) -> ChannelPolicy {
+        self.inner.channel_policy()
+    }
+
     /// Start capturing audio
+    ///
+    /// Returns once the device format has been negotiated; see
+    /// [`format`](Self::format).
     pub fn start(&mut self) -> AudioResult<()> {
         self.inner.start()
     }
@@ -60,8 +86,19 @@ impl AudioCapture {
         self.inner.is_active()
     }
     
-    /// Get the audio format being used
-    pub fn format(&self) -> &AudioFormat {
+    /// Format of the samples returned by [`take_buffer`](Self::take_buffer)
+    ///
+    /// Before [`start`](Self::start) this is the requested format with the
+    /// channel policy applied. Once started, `channels` is what the policy
+    /// produced from the channels actually negotiated, `sample_format` is
+    /// what the device delivers (samples are always handed out as `f32`),
+    /// and `sample_rate` is the requested rate, which the backend resamples
+    /// to if the device runs at another one.
+    ///
+    /// The device may renegotiate while capturing, so read this again for
+    /// each [`take_buffer`](Self::take_buffer) rather than keeping a copy.
+    /// Only `channels` can change, and only with [`ChannelPolicy::All`].
+    pub fn format(&self) -> AudioFormat {
         self.inner.format()
     }
 }
@@ -1,8 +1,9 @@
 //! cpal-based audio capture
 use super::error::{AudioError, AudioResult};
-use super::format::AudioFormat;
+use super::format::{AudioFormat, AudioSampleFormat, ChannelPolicy};
+use super::resample::Resampler;
 use cpal::traits::{DeviceTrait, StreamTrait};
-use cpal::{Device, Stream, StreamConfig};
+use cpal::{Device, FromSample, SizedSample, Stream, StreamConfig};
 use std::sync::{Arc, Mutex};
 
 type AudioBuffer = Arc<Mutex<Vec<f32>>>;
@@ -12,20 +13,37 @@ type AudioBuffer = Arc<Mutex<Vec<f32>>>;
 /// Provides cross-platform audio capture using the cpal library.
 /// Clone creates a new capture instance that shares the same buffer
 /// but without the active stream (if any).
-#[derive(Debug)]
 pub struct CpalCapture {
     device: Device,
+    /// Format asked for; the device may deliver something else
+    requested: AudioFormat,
+    /// Format of the samples in `buffer`
     format: AudioFormat,
+    policy: ChannelPolicy,
     stream: Option<Stream>,
     buffer: AudioBuffer,
 }
 
+// cpal's Device and Stream don't implement Debug
+impl std::fmt::Debug for CpalCapture {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("CpalCapture")
+            .field("device", &self.device.name().ok())
+            .field("format", &self.format)
+            .field("policy", &self.policy)
+            .field("active", &self.stream.is_some())
+            .finish()
+    }
+}
+
 // Manual Clone implementation - shares buffer but not stream
 impl Clone for CpalCapture {
     fn clone(&self) -> Self {
         Self {
             device: self.device.clone(),
-            format: self.format.clone(),
+            requested: self.requested,
+            format: self.format,
+            policy: self.policy,
             stream: None, // Don't clone the stream
             buffer: Arc::clone(&self.buffer),
         }
@@ -34,32 +52,72 @@ impl Clone for CpalCapture {
 
 impl CpalCapture {
     pub fn new() -> AudioResult<Self> {
-        let device = super::device::default_device()?;
         Self::with_format(AudioFormat::DEFAULT)
     }
 
     pub fn with_format(format: AudioFormat) -> AudioResult<Self> {
         let device = super::device::default_device()?;
-        Ok(Self { device, format, stream: None, buffer: Arc::new(Mutex::new(Vec::new())) })
+        Ok(Self::for_device(device, format))
+    }
+
+    /// Capture from the input device with the given CPAL name
+    pub fn with_device(name: &str, format: AudioFormat) -> AudioResult<Self> {
+        let device = super::device::device_by_name(name)?;
+        Ok(Self::for_device(device, format))
+    }
+
+    fn for_device(device: Device, requested: AudioFormat) -> Self {
+        let policy = ChannelPolicy::default();
+        Self {
+            device,
+            requested,
+            format: AudioFormat { channels: policy.output_channels(requested.channels), ..requested },
+            policy,
+            stream: None,
+            buffer: Arc::new(Mutex::new(Vec::new())),
+        }
+    }
+
+    pub fn set_channel_policy(&mut self, policy: ChannelPolicy) {
+        self.policy = policy;
+        self.format.channels = policy.output_channels(self.requested.channels);
+    }
+
+    pub fn channel_policy(&self) -> ChannelPolicy {
+        self.policy
     }
 
     pub fn start(&mut self) -> AudioResult<()> {
         if self.stream.is_some() {
             return Err(AudioError::StreamError("active".to_string()));
         }
-        let config = self.get_supported_config()?;
+        let (config, sample_format) = self.negotiate()?;
+        self.policy.validate(config.channels)?;
+
+        let channels = config.channels;
+        let out_channels = self.policy.output_channels(channels);
+        let policy = self.policy;
+        // Devices that can't run at the requested rate are converted as we go
+        let mut resampler = Resampler::new(config.sample_rate.0, self.requested.sample_rate, out_channels);
         self.buffer.lock().unwrap().clear();
         let buffer = Arc::clone(&self.buffer);
-        let stream = self.device.build_input_stream(
-            &config,
-            move |data: &[f32], _: &cpal::InputCallbackInfo| {
-                buffer.lock().unwrap().extend_from_slice(data);
-            },
-            |err| eprintln!("error: {}", err),
-            None,
-        )?;
+        let push = move |data: &[f32]| {
+            let data = resampler.process(&policy.apply(data, channels));
+            buffer.lock().unwrap().extend_from_slice(&data);
+        };
+
+        let stream = match sample_format {
+            AudioSampleFormat::F32 => build_stream::<f32>(&self.device, &config, push)?,
+            AudioSampleFormat::I16 => build_stream::<i16>(&self.device, &config, push)?,
+            AudioSampleFormat::I24 | AudioSampleFormat::I32 => build_stream::<i32>(&self.device, &config, push)?,
+        };
         stream.play()?;
         self.stream = Some(stream);
+        self.format = AudioFormat {
+            sample_rate: self.requested.sample_rate,
+            channels: out_channels,
+            sample_format,
+        };
         Ok(())
     }
 
@@ -81,35 +139,62 @@ impl CpalCapture {
         self.stream.is_some()
     }
 
-    pub fn format(&self) -> &AudioFormat {
-        &self.format
+    pub fn format(&self) -> AudioFormat {
+        self.format
     }
 
-    fn get_supported_config(&self) -> AudioResult<StreamConfig> {
-        let supported_configs = self.device.supported_input_configs()
-            .map_err(|e| AudioError::DeviceError(e.to_string()))?;
-        let desired_sample_rate = self.format.to_cpal_sample_rate();
-        for config_range in supported_configs {
-            if config_range.sample_format() == self.format.to_cpal_sample_format()
-                && config_range.channels() == self.format.channels
-                && config_range.min_sample_rate() <= desired_sample_rate
-                && config_range.max_sample_rate() >= desired_sample_rate
-            {
-                return Ok(StreamConfig {
-                    channels: config_range.channels(),
-                    sample_rate: desired_sample_rate,
+    /// Pick the device configuration closest to the requested format
+    ///
+    /// Prefers, in order: the requested channel count, the rate nearest the
+    /// requested one (resampled in `start` if it differs), and the requested
+    /// sample format.
+    fn negotiate(&self) -> AudioResult<(StreamConfig, AudioSampleFormat)> {
+        let desired_rate = self.requested.to_cpal_sample_rate();
+        self.device.supported_input_configs()
+            .map_err(|e| AudioError::DeviceError(e.to_string()))?
+            .filter_map(|range| {
+                let sample_format = AudioSampleFormat::from_cpal(range.sample_format())?;
+                let sample_rate = desired_rate.clamp(range.min_sample_rate(), range.max_sample_rate());
+                let rank = (
+                    range.channels() != self.requested.channels,
+                    sample_rate.0.abs_diff(desired_rate.0),
+                    sample_format != self.requested.sample_format,
+                );
+                let config = StreamConfig {
+                    channels: range.channels(),
+                    sample_rate,
                     buffer_size: cpal::BufferSize::Default,
-                });
-            }
-        }
-        Ok(StreamConfig {
-            channels: self.format.channels,
-            sample_rate: self.format.to_cpal_sample_rate(),
-            buffer_size: cpal::BufferSize::Default,
-        })
+                };
+                Some((rank, config, sample_format))
+            })
+            .min_by_key(|(rank, ..)| *rank)
+            .map(|(_, config, sample_format)| (config, sample_format))
+            .ok_or_else(|| AudioError::DeviceError("No F32, I16 or I32 input configuration available".to_string()))
     }
 }
 
+/// Open an input stream delivering `T` samples, converted to `f32` for `push`
+fn build_stream<T>(
+    device: &Device,
+    config: &StreamConfig,
+    mut push: impl FnMut(&[f32]) + Send + 'static,
+) -> AudioResult<Stream>
+where
+    T: SizedSample,
+    f32: FromSample<T>,
+{
+    let stream = device.build_input_stream(
+        config,
+        move |data: &[T], _: &cpal::InputCallbackInfo| {
+            let samples: Vec<f32> = data.iter().map(|s| s.to_sample::<f32>()).collect();
+            push(&samples);
+        },
+        |err| eprintln!("error: {}", err),
+        None,
+    )?;
+    Ok(stream)
+}
+
 impl Drop for CpalCapture {
     fn drop(&mut self) {
         let _ = self.stop();
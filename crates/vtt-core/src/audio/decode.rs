@@ -0,0 +1,207 @@
+//! Audio file decoding
+//!
+//! Reads WAV (integer or float), FLAC, Ogg Vorbis, MP3, AAC/ALAC and the
+//! audio track of MP4/MKV/WebM files with Symphonia, plus Ogg Opus with the
+//! `opus` feature, and converts them into the 16 kHz mono `f32` samples
+//! Whisper expects.
+
+use super::error::{AudioError, AudioResult};
+use super::format::{AudioFormat, AudioSampleFormat};
+use super::resample::resample_mono;
+use std::fs::File;
+use std::path::Path;
+use std::sync::OnceLock;
+use symphonia::core::audio::SampleBuffer;
+use symphonia::core::codecs::{CodecRegistry, DecoderOptions, CODEC_TYPE_NULL};
+use symphonia::core::errors::Error as SymphoniaError;
+use symphonia::core::formats::FormatOptions;
+use symphonia::core::io::MediaSourceStream;
+use symphonia::core::meta::MetadataOptions;
+use symphonia::core::probe::Hint;
+
+/// Sample rate Whisper models are trained on
+pub const WHISPER_SAMPLE_RATE: u32 = 16000;
+
+/// Decoded audio in the file's own sample rate and channel layout
+#[derive(Debug, Clone)]
+pub struct DecodedAudio {
+    /// Interleaved samples in [-1.0, 1.0]
+    pub samples: Vec<f32>,
+    /// Sample rate and channel count of `samples`
+    pub format: AudioFormat,
+}
+
+impl DecodedAudio {
+    /// Duration of the audio in milliseconds
+    pub fn duration_ms(&self) -> u64 {
+        self.format.duration_ms(self.samples.len())
+    }
+
+    /// Downmix to mono and resample to [`WHISPER_SAMPLE_RATE`]
+    pub fn to_whisper_input(&self) -> Vec<f32> {
+        let mono = self.format.to_mono(&self.samples);
+        resample_mono(&mono, self.format.sample_rate, WHISPER_SAMPLE_RATE)
+    }
+}
+
+/// Decode the first playable audio track of a file
+///
+/// The container is detected from the file contents, with the extension as
+/// a hint. Packets that fail to decode are skipped.
+///
+/// # Errors
+///
+/// Returns an error if the file can't be opened, its format isn't
+/// recognised, or it has no audio track with a supported codec.
+pub fn decode_file<P: AsRef<Path>>(path: P) -> AudioResult<DecodedAudio> {
+    let path = path.as_ref();
+    let file = File::open(path)?;
+    let stream = MediaSourceStream::new(Box::new(file), Default::default());
+
+    let mut hint = Hint::new();
+    if let Some(ext) = path.extension().and_then(|e| e.to_str()) {
+        hint.with_extension(ext);
+    }
+
+    let probed = symphonia::default::get_probe()
+        .format(&hint, stream, &FormatOptions::default(), &MetadataOptions::default())
+        .map_err(|e| AudioError::DecodeError(format!("{}: {}", path.display(), e)))?;
+    let mut reader = probed.format;
+
+    // Video containers list their picture tracks too; take the first track
+    // we can actually decode.
+    let track = reader
+        .tracks()
+        .iter()
+        .find(|t| t.codec_params.codec != CODEC_TYPE_NULL && codecs().get_codec(t.codec_params.codec).is_some())
+        .ok_or_else(|| AudioError::DecodeError(format!("{}: no supported audio track", path.display())))?;
+    let track_id = track.id;
+    let mut decoder = codecs().make(&track.codec_params, &DecoderOptions::default())?;
+
+    let mut samples = Vec::new();
+    let mut format = None;
+    loop {
+        let packet = match reader.next_packet() {
+            Ok(packet) => packet,
+            Err(SymphoniaError::IoError(e)) if e.kind() == std::io::ErrorKind::UnexpectedEof => break,
+            Err(SymphoniaError::ResetRequired) => break,
+            Err(e) => return Err(e.into()),
+        };
+        if packet.track_id() != track_id {
+            continue;
+        }
+
+        let decoded = match decoder.decode(&packet) {
+            Ok(decoded) => decoded,
+            Err(SymphoniaError::DecodeError(e)) => {
+                log::warn!("Skipping undecodable packet in {}: {}", path.display(), e);
+                continue;
+            }
+            Err(e) => return Err(e.into()),
+        };
+
+        let spec = *decoded.spec();
+        format.get_or_insert(AudioFormat::new(
+            spec.rate,
+            spec.channels.count() as u16,
+            AudioSampleFormat::F32,
+        ));
+        let mut buffer = SampleBuffer::<f32>::new(decoded.capacity() as u64, spec);
+        buffer.copy_interleaved_ref(decoded);
+        samples.extend_from_slice(buffer.samples());
+    }
+
+    let format = format.ok_or_else(|| AudioError::DecodeError(format!("{}: no audio decoded", path.display())))?;
+    Ok(DecodedAudio { samples, format })
+}
+
+/// Decode a file straight to Whisper input: 16 kHz mono `f32`
+///
+/// # Errors
+///
+/// See [`decode_file`].
+pub fn decode_for_whisper<P: AsRef<Path>>(path: P) -> AudioResult<Vec<f32>> {
+    Ok(decode_file(path)?.to_whisper_input())
+}
+
+// Symphonia's codecs plus libopus, which Symphonia doesn't ship
+fn codecs() -> &'static CodecRegistry {
+    static CODECS: OnceLock<CodecRegistry> = OnceLock::new();
+    CODECS.get_or_init(|| {
+        let mut registry = CodecRegistry::new();
+        symphonia::default::register_enabled_codecs(&mut registry);
+        #[cfg(feature = "opus")]
+        registry.register_all::<symphonia_adapter_libopus::OpusDecoder>();
+        registry
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use hound::{SampleFormat, WavSpec, WavWriter};
+
+    fn write_wav(name: &str, spec: WavSpec, write: impl FnOnce(&mut WavWriter<std::io::BufWriter<File>>)) -> std::path::PathBuf {
+        let path = std::env::temp_dir().join(format!("vtt-decode-{}-{}", std::process::id(), name));
+        let mut writer = WavWriter::create(&path, spec).unwrap();
+        write(&mut writer);
+        writer.finalize().unwrap();
+        path
+    }
+
+    #[test]
+    fn test_decode_stereo_i16_wav() {
+        let spec = WavSpec { channels: 2, sample_rate: 48000, bits_per_sample: 16, sample_format: SampleFormat::Int };
+        let path = write_wav("i16.wav", spec, |w| {
+            for _ in 0..4800 {
+                w.write_sample(i16::MAX / 2).unwrap();
+                w.write_sample(0i16).unwrap();
+            }
+        });
+
+        let audio = decode_file(&path).unwrap();
+        assert_eq!(audio.format.sample_rate, 48000);
+        assert_eq!(audio.format.channels, 2);
+        assert_eq!(audio.samples.len(), 9600);
+        assert_eq!(audio.duration_ms(), 100);
+
+        let input = audio.to_whisper_input();
+        assert_eq!(input.len(), 1600);
+        assert!((input[800] - 0.25).abs() < 0.01);
+        let _ = std::fs::remove_file(path);
+    }
+
+    #[test]
+    fn test_decode_float_and_24_bit_wav() {
+        let spec = WavSpec { channels: 1, sample_rate: 16000, bits_per_sample: 32, sample_format: SampleFormat::Float };
+        let path = write_wav("f32.wav", spec, |w| {
+            for _ in 0..1600 {
+                w.write_sample(-0.5f32).unwrap();
+            }
+        });
+        let input = decode_for_whisper(&path).unwrap();
+        assert_eq!(input.len(), 1600);
+        assert!((input[0] + 0.5).abs() < 1e-6);
+        let _ = std::fs::remove_file(path);
+
+        let spec = WavSpec { channels: 1, sample_rate: 16000, bits_per_sample: 24, sample_format: SampleFormat::Int };
+        let path = write_wav("i24.wav", spec, |w| {
+            for _ in 0..1600 {
+                w.write_sample(1 << 22).unwrap();
+            }
+        });
+        let input = decode_for_whisper(&path).unwrap();
+        assert!((input[0] - 0.5).abs() < 1e-3);
+        let _ = std::fs::remove_file(path);
+    }
+
+    #[test]
+    fn test_decode_missing_and_garbage_files() {
+        assert!(matches!(decode_file("/nonexistent/clip.wav"), Err(AudioError::IoError(_))));
+
+        let path = std::env::temp_dir().join(format!("vtt-decode-{}-garbage.bin", std::process::id()));
+        std::fs::write(&path, b"definitely not audio").unwrap();
+        assert!(matches!(decode_file(&path), Err(AudioError::DecodeError(_))));
+        let _ = std::fs::remove_file(path);
+    }
+}
@@ -6,8 +6,13 @@ use cpal::traits::{DeviceTrait, HostTrait};
 /// Audio device information
 #[derive(Debug, Clone)]
 pub struct AudioDevice {
-    /// Device name
+    /// Human-readable device name
     pub name: String,
+    /// Backend identifier used to open the device
+    ///
+    /// This is the PipeWire node name on Linux and the CPAL device name
+    /// elsewhere.
+    pub id: String,
     /// Whether this is the default device
     pub is_default: bool,
 }
@@ -17,6 +22,17 @@ pub struct AudioDevice {
 /// # Errors
 ///
 /// Returns an error if device enumeration fails or if no host is available.
+#[cfg(target_os = "linux")]
+pub fn list_devices() -> AudioResult<Vec<AudioDevice>> {
+    super::pipewire_capture::list_sources()
+}
+
+/// List available audio input devices
+///
+/// # Errors
+///
+/// Returns an error if device enumeration fails or if no host is available.
+#[cfg(not(target_os = "linux"))]
 pub fn list_devices() -> AudioResult<Vec<AudioDevice>> {
     let host = cpal::default_host();
 
@@ -29,13 +45,29 @@ pub fn list_devices() -> AudioResult<Vec<AudioDevice>> {
     for device in devices {
         if let Ok(name) = device.name() {
             let is_default = default_name.as_ref() == Some(&name);
-            result.push(AudioDevice { name, is_default });
+            result.push(AudioDevice {
+                id: name.clone(),
+                name,
+                is_default,
+            });
         }
     }
 
     Ok(result)
 }
 
+/// Find an input device by its name or backend identifier
+///
+/// # Errors
+///
+/// Returns an error if no device matches or enumeration fails.
+pub fn find_device(name_or_id: &str) -> AudioResult<AudioDevice> {
+    list_devices()?
+        .into_iter()
+        .find(|d| d.name == name_or_id || d.id == name_or_id)
+        .ok_or_else(|| AudioError::DeviceError(format!("Device not found: {}", name_or_id)))
+}
+
 /// Get the default audio input device
 ///
 /// # Errors
@@ -81,6 +113,12 @@ mod tests {
         assert!(result.is_ok());
     }
 
+    #[test]
+    fn test_find_missing_device() {
+        let result = find_device("vtt-no-such-device");
+        assert!(matches!(result, Err(AudioError::DeviceError(_))));
+    }
+
     #[test]
     fn test_default_device() {
         let result = default_device();
@@ -20,6 +20,10 @@ pub enum AudioError {
     #[error("Audio capture error: {0}")]
     CaptureError(String),
 
+    /// Audio file could not be decoded
+    #[error("Audio decode error: {0}")]
+    DecodeError(String),
+
     /// I/O error (file operations)
     #[error("I/O error: {0}")]
     IoError(#[from] std::io::Error),
@@ -47,6 +51,15 @@ impl From<cpal::PlayStreamError> for AudioError {
     }
 }
 
+impl From<symphonia::core::errors::Error> for AudioError {
+    fn from(err: symphonia::core::errors::Error) -> Self {
+        match err {
+            symphonia::core::errors::Error::IoError(e) => AudioError::IoError(e),
+            other => AudioError::DecodeError(other.to_string()),
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
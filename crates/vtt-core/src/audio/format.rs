@@ -1,6 +1,8 @@
 //! Audio format configuration
 
+use super::error::{AudioError, AudioResult};
 use cpal::{SampleFormat, SampleRate, SupportedStreamConfig};
+use serde::{Deserialize, Serialize};
 
 /// Audio format specification for capture
 #[derive(Debug, Clone, Copy, PartialEq)]
@@ -20,6 +22,120 @@ pub enum AudioSampleFormat {
     F32,
     /// 16-bit signed integer
     I16,
+    /// 24-bit signed integer, packed in 3 bytes
+    I24,
+    /// 32-bit signed integer
+    I32,
+}
+
+impl AudioSampleFormat {
+    /// Size of one sample in bytes
+    pub fn bytes_per_sample(&self) -> usize {
+        match self {
+            Self::I16 => 2,
+            Self::I24 => 3,
+            Self::F32 | Self::I32 => 4,
+        }
+    }
+
+    /// Convert little-endian samples to `f32` in [-1.0, 1.0]
+    ///
+    /// A trailing partial sample is ignored.
+    pub fn decode_le(&self, bytes: &[u8]) -> Vec<f32> {
+        let chunks = bytes.chunks_exact(self.bytes_per_sample());
+        match self {
+            Self::F32 => chunks.map(|b| f32::from_le_bytes([b[0], b[1], b[2], b[3]])).collect(),
+            Self::I16 => chunks
+                .map(|b| i16::from_le_bytes([b[0], b[1]]) as f32 / 32768.0)
+                .collect(),
+            // Shift into the top of an i32 so the sign bit lands in place
+            Self::I24 => chunks
+                .map(|b| (i32::from_le_bytes([0, b[0], b[1], b[2]]) >> 8) as f32 / 8_388_608.0)
+                .collect(),
+            Self::I32 => chunks
+                .map(|b| i32::from_le_bytes([b[0], b[1], b[2], b[3]]) as f32 / 2_147_483_648.0)
+                .collect(),
+        }
+    }
+
+    /// Matching format for a cpal sample format, if we can capture it
+    pub fn from_cpal(format: SampleFormat) -> Option<Self> {
+        match format {
+            SampleFormat::F32 => Some(Self::F32),
+            SampleFormat::I16 => Some(Self::I16),
+            SampleFormat::I32 => Some(Self::I32),
+            _ => None,
+        }
+    }
+}
+
+/// How a multichannel input becomes the captured samples
+///
+/// In TOML: `channel_policy = "downmix"`, `"all"` or `{ select = 1 }`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ChannelPolicy {
+    /// Average every channel into one
+    #[default]
+    Downmix,
+    /// Keep only the given channel, counting from 0
+    Select(u16),
+    /// Keep every channel, interleaved
+    All,
+}
+
+impl ChannelPolicy {
+    /// Channels to ask a device for so the policy can be applied, given
+    /// the `configured` count
+    ///
+    /// Selecting a channel needs at least that many channels, so a device
+    /// with more than `configured` isn't downmixed before the pick.
+    pub fn input_channels(&self, configured: u16) -> u16 {
+        match self {
+            Self::Select(channel) => configured.max(channel.saturating_add(1)),
+            Self::Downmix | Self::All => configured.max(1),
+        }
+    }
+
+    /// Channels left after applying the policy to `input_channels`
+    pub fn output_channels(&self, input_channels: u16) -> u16 {
+        match self {
+            Self::All => input_channels,
+            Self::Downmix | Self::Select(_) => 1,
+        }
+    }
+
+    /// Check the policy can be applied to `input_channels`
+    ///
+    /// # Errors
+    ///
+    /// Returns `CaptureError` if a selected channel doesn't exist.
+    pub fn validate(&self, input_channels: u16) -> AudioResult<()> {
+        match self {
+            Self::Select(channel) if *channel >= input_channels => Err(AudioError::CaptureError(format!(
+                "Channel {} selected but the input only has {} (counting from 0)",
+                channel, input_channels
+            ))),
+            _ => Ok(()),
+        }
+    }
+
+    /// Apply the policy to interleaved samples with `channels` channels
+    pub fn apply(&self, samples: &[f32], channels: u16) -> Vec<f32> {
+        let channels = channels.max(1) as usize;
+        match self {
+            Self::All => samples.to_vec(),
+            Self::Downmix if channels == 1 => samples.to_vec(),
+            Self::Downmix => samples
+                .chunks_exact(channels)
+                .map(|frame| frame.iter().sum::<f32>() / channels as f32)
+                .collect(),
+            Self::Select(channel) => samples
+                .chunks_exact(channels)
+                .filter_map(|frame| frame.get(*channel as usize).copied())
+                .collect(),
+        }
+    }
 }
 
 impl AudioFormat {
@@ -58,6 +174,8 @@ impl AudioFormat {
         match self.sample_format {
             AudioSampleFormat::F32 => SampleFormat::F32,
             AudioSampleFormat::I16 => SampleFormat::I16,
+            // cpal has no packed 24-bit format; such devices report I32
+            AudioSampleFormat::I24 | AudioSampleFormat::I32 => SampleFormat::I32,
         }
     }
 
@@ -67,6 +185,19 @@ impl AudioFormat {
             && config.channels() == self.channels
             && config.sample_rate() == self.to_cpal_sample_rate()
     }
+
+    /// Downmix interleaved samples in this format to mono
+    ///
+    /// Each frame is averaged across channels. Mono input is returned as-is.
+    pub fn to_mono(&self, samples: &[f32]) -> Vec<f32> {
+        ChannelPolicy::Downmix.apply(samples, self.channels)
+    }
+
+    /// Duration in milliseconds of an interleaved buffer in this format
+    pub fn duration_ms(&self, n_samples: usize) -> u64 {
+        let frames = n_samples / self.channels.max(1) as usize;
+        (frames as u64 * 1000) / self.sample_rate.max(1) as u64
+    }
 }
 
 #[cfg(test)]
@@ -95,4 +226,61 @@ mod tests {
         assert_eq!(fmt.to_cpal_sample_rate().0, 48000);
         assert_eq!(fmt.to_cpal_sample_format(), SampleFormat::F32);
     }
+
+    #[test]
+    fn test_to_mono() {
+        let stereo = AudioFormat::new(48000, 2, AudioSampleFormat::F32);
+        let mono = stereo.to_mono(&[0.2, 0.4, -1.0, 1.0]);
+        assert_eq!(mono.len(), 2);
+        assert!((mono[0] - 0.3).abs() < 1e-6);
+        assert_eq!(mono[1], 0.0);
+
+        let samples = [0.1, 0.2, 0.3];
+        assert_eq!(AudioFormat::STT_DEFAULT.to_mono(&samples), samples.to_vec());
+    }
+
+    #[test]
+    fn test_decode_le() {
+        let bytes: Vec<u8> = [-16384i16, 16384].iter().flat_map(|s| s.to_le_bytes()).collect();
+        assert_eq!(AudioSampleFormat::I16.decode_le(&bytes), vec![-0.5, 0.5]);
+
+        // -0.5 and 0.25 as packed 24-bit, plus a stray byte
+        let bytes = [0x00, 0x00, 0xC0, 0x00, 0x00, 0x20, 0x7F];
+        assert_eq!(AudioSampleFormat::I24.decode_le(&bytes), vec![-0.5, 0.25]);
+
+        let bytes = (i32::MIN).to_le_bytes();
+        assert_eq!(AudioSampleFormat::I32.decode_le(&bytes), vec![-1.0]);
+
+        let bytes = 0.75f32.to_le_bytes();
+        assert_eq!(AudioSampleFormat::F32.decode_le(&bytes), vec![0.75]);
+    }
+
+    #[test]
+    fn test_channel_policy() {
+        let stereo = [0.2, 0.4, -1.0, 1.0, 0.5];
+        assert_eq!(ChannelPolicy::Downmix.apply(&stereo, 2).len(), 2);
+        assert_eq!(ChannelPolicy::Select(1).apply(&stereo, 2), vec![0.4, 1.0]);
+        assert_eq!(ChannelPolicy::All.apply(&stereo[..4], 2), stereo[..4].to_vec());
+
+        assert_eq!(ChannelPolicy::All.output_channels(4), 4);
+        assert_eq!(ChannelPolicy::Select(3).output_channels(4), 1);
+        assert!(ChannelPolicy::Select(3).validate(4).is_ok());
+        assert!(ChannelPolicy::Select(2).validate(2).is_err());
+
+        // The channels requested for a policy are enough to apply it
+        for channel in 0..8 {
+            let policy = ChannelPolicy::Select(channel);
+            assert!(policy.validate(policy.input_channels(1)).is_ok(), "{:?}", policy);
+            assert!(policy.validate(policy.input_channels(2)).is_ok(), "{:?}", policy);
+        }
+        assert_eq!(ChannelPolicy::Select(3).input_channels(8), 8);
+        assert_eq!(ChannelPolicy::Downmix.input_channels(2), 2);
+        assert_eq!(ChannelPolicy::All.input_channels(0), 1);
+    }
+
+    #[test]
+    fn test_duration_ms() {
+        assert_eq!(AudioFormat::STT_DEFAULT.duration_ms(16000), 1000);
+        assert_eq!(AudioFormat::DEFAULT.duration_ms(48000), 500);
+    }
 }
@@ -1,19 +1,26 @@
 //! Audio processing
 
 pub mod capture;
+pub mod decode;
 pub mod device;
 pub mod error;
 pub mod format;
+pub mod resample;
 pub mod writer;
 
 #[cfg(target_os = "linux")]
 pub mod pipewire_capture;
 
+#[cfg(not(target_os = "linux"))]
+pub mod cpal_capture;
+
 pub use capture::AudioCapture;
-pub use device::{default_device, device_by_name, list_devices, AudioDevice};
+pub use decode::{decode_file, decode_for_whisper, DecodedAudio};
+pub use device::{default_device, device_by_name, find_device, list_devices, AudioDevice};
 pub use error::{AudioError, AudioResult};
-pub use format::{AudioFormat, AudioSampleFormat};
-pub use writer::write_wav;
+pub use format::{AudioFormat, AudioSampleFormat, ChannelPolicy};
+pub use resample::{resample, resample_mono, Resampler};
+pub use writer::{encode_wav, write_wav};
 
 pub const VERSION: &str = env!("CARGO_PKG_VERSION");
 
@@ -1,14 +1,36 @@
 //! PipeWire native audio capture
-use super::{AudioFormat, AudioResult};
-use std::mem;
+use super::resample::Resampler;
+use super::{AudioDevice, AudioError, AudioFormat, AudioResult, AudioSampleFormat, ChannelPolicy};
+use std::cell::{Cell, RefCell};
+use std::rc::Rc;
+use std::sync::mpsc;
 use std::sync::{Arc, Mutex};
 use std::thread::{self, JoinHandle};
+use std::time::Duration;
+
+/// How long `start` waits for PipeWire to settle on a stream format
+const NEGOTIATION_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// How long `list_sources` waits for PipeWire to announce the sources
+const LIST_TIMEOUT: Duration = Duration::from_secs(2);
 
 // User data passed to PipeWire callbacks
 struct CaptureData {
     buffer: Arc<Mutex<Vec<f32>>>,
     format: pipewire::spa::param::audio::AudioInfoRaw,
     active: Arc<Mutex<bool>>,
+    /// Sample rate the caller asked for
+    rate: u32,
+    policy: ChannelPolicy,
+    /// Sample format of the negotiated stream; `None` until negotiated
+    sample_format: Option<AudioSampleFormat>,
+    /// Set when the graph runs at a different rate than `rate`
+    resampler: Option<Resampler>,
+    /// Format of the samples pushed to `buffer`, updated on every
+    /// negotiation
+    output: Arc<Mutex<AudioFormat>>,
+    /// Reports the outcome of the first negotiation back to `start`
+    negotiated: Option<mpsc::Sender<AudioResult<()>>>,
 }
 
 /// PipeWire audio capture using native API
@@ -18,22 +40,45 @@ struct CaptureData {
 /// Note: Clone creates a new capture instance that shares the same buffer
 /// and active state, but without the thread handle. This is safe because
 /// the original instance's thread will continue running until stopped.
-#[derive(Debug)]
 pub struct PipeWireCapture {
-    format: AudioFormat,
+    /// Format asked for; the graph may deliver something else
+    requested: AudioFormat,
+    /// Format of the samples in `buffer`; the capture thread updates it
+    /// whenever the stream is renegotiated
+    format: Arc<Mutex<AudioFormat>>,
+    policy: ChannelPolicy,
+    target: Option<String>,
     buffer: Arc<Mutex<Vec<f32>>>,
     active: Arc<Mutex<bool>>,
     thread_handle: Option<JoinHandle<()>>,
+    /// Wakes the capture thread's main loop so it can exit
+    quit: Option<pipewire::channel::Sender<()>>,
+}
+
+// pipewire's channel sender doesn't implement Debug
+impl std::fmt::Debug for PipeWireCapture {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("PipeWireCapture")
+            .field("format", &self.format())
+            .field("policy", &self.policy)
+            .field("target", &self.target)
+            .field("active", &self.is_active())
+            .finish()
+    }
 }
 
 // Manual Clone implementation - shares buffer/active state but drops thread handle
 impl Clone for PipeWireCapture {
     fn clone(&self) -> Self {
         Self {
+            requested: self.requested,
             format: self.format.clone(),
+            policy: self.policy,
+            target: self.target.clone(),
             buffer: self.buffer.clone(),
             active: self.active.clone(),
             thread_handle: None, // Don't clone the thread handle
+            quit: None,
         }
     }
 }
@@ -44,14 +89,43 @@ impl PipeWireCapture {
     }
 
     pub fn with_format(format: AudioFormat) -> AudioResult<Self> {
+        Self::with_target(None, format)
+    }
+
+    /// Capture from a specific PipeWire node instead of the default source
+    ///
+    /// `node_name` is the node's `node.name` property, as reported in
+    /// [`AudioDevice::id`] by [`list_sources`].
+    pub fn with_device(node_name: &str, format: AudioFormat) -> AudioResult<Self> {
+        Self::with_target(Some(node_name.to_string()), format)
+    }
+
+    fn with_target(target: Option<String>, requested: AudioFormat) -> AudioResult<Self> {
+        let policy = ChannelPolicy::default();
         Ok(Self {
-            format,
+            requested,
+            format: Arc::new(Mutex::new(AudioFormat {
+                channels: policy.output_channels(requested.channels),
+                ..requested
+            })),
+            policy,
+            target,
             buffer: Arc::new(Mutex::new(Vec::new())),
             active: Arc::new(Mutex::new(false)),
             thread_handle: None,
+            quit: None,
         })
     }
 
+    pub fn set_channel_policy(&mut self, policy: ChannelPolicy) {
+        self.policy = policy;
+        self.format.lock().unwrap().channels = policy.output_channels(self.requested.channels);
+    }
+
+    pub fn channel_policy(&self) -> ChannelPolicy {
+        self.policy
+    }
+
     pub fn start(&mut self) -> AudioResult<()> {
         let mut active = self.active.lock().unwrap();
         if *active {
@@ -63,18 +137,33 @@ impl PipeWireCapture {
         // Clone Arcs for the new thread
         let buffer_clone = self.buffer.clone();
         let active_clone = self.active.clone();
+        let requested = self.requested;
+        let policy = self.policy;
+        let target = self.target.clone();
+        let format = self.format.clone();
+        let (quit_tx, quit_rx) = pipewire::channel::channel();
+        let (negotiated_tx, negotiated_rx) = mpsc::channel();
 
         // Spawn PipeWire event loop thread
         let handle = thread::spawn(move || {
-            Self::run_pipewire_loop(buffer_clone, active_clone);
+            Self::run_pipewire_loop(requested, policy, target, buffer_clone, active_clone, format, quit_rx, negotiated_tx);
         });
 
         self.thread_handle = Some(handle);
+        self.quit = Some(quit_tx);
 
-        // Give PipeWire a moment to initialize
-        std::thread::sleep(std::time::Duration::from_millis(100));
-
-        Ok(())
+        let negotiated = negotiated_rx.recv_timeout(NEGOTIATION_TIMEOUT).unwrap_or_else(|e| {
+            Err(AudioError::StreamError(match e {
+                mpsc::RecvTimeoutError::Timeout => {
+                    format!("PipeWire: no stream format negotiated within {:?}", NEGOTIATION_TIMEOUT)
+                }
+                mpsc::RecvTimeoutError::Disconnected => "PipeWire: capture thread exited during setup".to_string(),
+            }))
+        });
+        if negotiated.is_err() {
+            let _ = self.stop();
+        }
+        negotiated
     }
 
     pub fn stop(&mut self) -> AudioResult<()> {
@@ -85,7 +174,10 @@ impl PipeWireCapture {
         *active = false;
         drop(active);
 
-        // Wait for the thread to finish
+        // Wake the main loop so the thread can finish
+        if let Some(quit) = self.quit.take() {
+            let _ = quit.send(());
+        }
         if let Some(handle) = self.thread_handle.take() {
             let _ = handle.join();
         }
@@ -106,44 +198,57 @@ impl PipeWireCapture {
         *self.active.lock().unwrap()
     }
 
-    pub fn format(&self) -> &AudioFormat {
-        &self.format
+    pub fn format(&self) -> AudioFormat {
+        *self.format.lock().unwrap()
     }
 
     // Runs the PipeWire event loop in a separate thread
-    fn run_pipewire_loop(buffer: Arc<Mutex<Vec<f32>>>, active: Arc<Mutex<bool>>) {
+    #[allow(clippy::too_many_arguments)]
+    fn run_pipewire_loop(
+        requested: AudioFormat,
+        policy: ChannelPolicy,
+        target: Option<String>,
+        buffer: Arc<Mutex<Vec<f32>>>,
+        active: Arc<Mutex<bool>>,
+        output: Arc<Mutex<AudioFormat>>,
+        quit: pipewire::channel::Receiver<()>,
+        negotiated: mpsc::Sender<AudioResult<()>>,
+    ) {
         use pipewire as pw;
         use pw::spa;
+        use pw::spa::param::audio::AudioFormat as SpaAudioFormat;
         use pw::spa::pod::Pod;
 
+        let fail = |what: &str, e: pw::Error| {
+            eprintln!("PipeWire: {}: {}", what, e);
+            let _ = negotiated.send(Err(AudioError::StreamError(format!("PipeWire: {}: {}", what, e))));
+        };
+
         // Initialize PipeWire
         pw::init();
 
         // Create main loop
         let mainloop = match pw::main_loop::MainLoopRc::new(None) {
             Ok(ml) => ml,
-            Err(e) => {
-                eprintln!("PipeWire: Failed to create main loop: {}", e);
-                return;
-            }
+            Err(e) => return fail("failed to create main loop", e),
         };
 
+        // `stop` sends on this to end `mainloop.run()`
+        let _quit = quit.attach(mainloop.loop_(), {
+            let mainloop = mainloop.clone();
+            move |()| mainloop.quit()
+        });
+
         // Create context
         let context = match pw::context::ContextRc::new(&mainloop, None) {
             Ok(ctx) => ctx,
-            Err(e) => {
-                eprintln!("PipeWire: Failed to create context: {}", e);
-                return;
-            }
+            Err(e) => return fail("failed to create context", e),
         };
 
         // Connect to PipeWire daemon
         let core = match context.connect_rc(None) {
             Ok(c) => c,
-            Err(e) => {
-                eprintln!("PipeWire: Failed to connect: {}", e);
-                return;
-            }
+            Err(e) => return fail("failed to connect", e),
         };
 
         // Create user data
@@ -151,22 +256,29 @@ impl PipeWireCapture {
             buffer,
             format: Default::default(),
             active: active.clone(),
+            rate: requested.sample_rate,
+            policy,
+            sample_format: None,
+            resampler: None,
+            output,
+            negotiated: Some(negotiated.clone()),
         };
 
         // Create stream properties
-        let props = pw::properties::properties! {
+        let mut props = pw::properties::properties! {
             *pw::keys::MEDIA_TYPE => "Audio",
             *pw::keys::MEDIA_CATEGORY => "Capture",
             *pw::keys::MEDIA_ROLE => "Music",
         };
+        // Without a target the session manager links us to the default source
+        if let Some(node_name) = target {
+            props.insert(*pw::keys::TARGET_OBJECT, node_name);
+        }
 
         // Create stream
         let stream = match pw::stream::StreamBox::new(&core, "vtt-capture", props) {
             Ok(s) => s,
-            Err(e) => {
-                eprintln!("PipeWire: Failed to create stream: {}", e);
-                return;
-            }
+            Err(e) => return fail("failed to create stream", e),
         };
 
         // Set up callbacks
@@ -201,17 +313,54 @@ impl PipeWireCapture {
                     return;
                 }
 
-                eprintln!(
-                    "PipeWire: Capturing rate:{} channels:{}",
-                    user_data.format.rate(),
-                    user_data.format.channels()
-                );
+                let rate = user_data.format.rate();
+                let channels = user_data.format.channels() as u16;
+                eprintln!("PipeWire: Capturing rate:{} channels:{}", rate, channels);
+
+                let sample_format = match user_data.format.format() {
+                    SpaAudioFormat::F32LE => Some(AudioSampleFormat::F32),
+                    SpaAudioFormat::S32LE => Some(AudioSampleFormat::I32),
+                    SpaAudioFormat::S24LE => Some(AudioSampleFormat::I24),
+                    SpaAudioFormat::S16LE => Some(AudioSampleFormat::I16),
+                    _ => None,
+                };
+                let result = sample_format
+                    .ok_or_else(|| AudioError::StreamError(format!(
+                        "PipeWire: unsupported sample format {:?}",
+                        user_data.format.format()
+                    )))
+                    .and_then(|sample_format| {
+                        user_data.policy.validate(channels)?;
+                        let out_channels = user_data.policy.output_channels(channels);
+                        // The graph may not honour the requested rate
+                        user_data.resampler = (rate != user_data.rate)
+                            .then(|| Resampler::new(rate, user_data.rate, out_channels));
+                        Ok(AudioFormat { sample_rate: user_data.rate, channels: out_channels, sample_format })
+                    });
+
+                // The stream may be renegotiated at any time, e.g. when the
+                // source is switched; `format()` follows along. The output
+                // rate never changes, only the channels with `All`.
+                let result = result.map(|format| {
+                    user_data.sample_format = Some(format.sample_format);
+                    *user_data.output.lock().unwrap() = format;
+                });
+                if let Err(e) = &result {
+                    eprintln!("{}", e);
+                    user_data.sample_format = None;
+                }
+                if let Some(negotiated) = user_data.negotiated.take() {
+                    let _ = negotiated.send(result);
+                }
             })
             .process(|stream, user_data| {
                 // Check if we should still be active
                 if !*user_data.active.lock().unwrap() {
                     return;
                 }
+                let Some(sample_format) = user_data.sample_format else {
+                    return;
+                };
 
                 match stream.dequeue_buffer() {
                     None => {}
@@ -222,47 +371,59 @@ impl PipeWireCapture {
                         }
 
                         let data = &mut datas[0];
-                        let n_channels = user_data.format.channels();
-
-                        // Get chunk size before borrowing data
-                        let chunk_size = data.chunk().size();
-                        let n_samples = chunk_size / mem::size_of::<f32>() as u32;
-
-                        if let Some(samples) = data.data() {
-                            // Convert to f32 samples and store in buffer
-                            // Use first channel only (mono)
-                            let mut audio_samples =
-                                Vec::with_capacity((n_samples / n_channels) as usize);
-                            for n in (0..n_samples).step_by(n_channels as usize) {
-                                let start = n as usize * mem::size_of::<f32>();
-                                let end = start + mem::size_of::<f32>();
-                                if end <= samples.len() {
-                                    let chan = &samples[start..end];
-                                    // Use try_into() to convert &[u8] to [u8; 4]
-                                    if let Ok(bytes) = chan.try_into() {
-                                        let f = f32::from_le_bytes(bytes);
-                                        audio_samples.push(f);
-                                    }
-                                }
+                        let channels = user_data.format.channels() as u16;
+
+                        // Get chunk bounds before borrowing data
+                        let offset = data.chunk().offset() as usize;
+                        let size = data.chunk().size() as usize;
+
+                        if let Some(bytes) = data.data() {
+                            let end = (offset + size).min(bytes.len());
+                            let samples = sample_format.decode_le(&bytes[offset.min(end)..end]);
+                            let mut samples = user_data.policy.apply(&samples, channels);
+                            if let Some(resampler) = user_data.resampler.as_mut() {
+                                samples = resampler.process(&samples);
                             }
 
                             // Push to shared buffer
                             let mut buf = user_data.buffer.lock().unwrap();
-                            buf.extend(audio_samples);
+                            buf.extend(samples);
                         }
                     }
                 }
             })
             .register();
 
-        // Create format parameters
+        // Ask for the requested rate and channel count explicitly, letting
+        // the graph pick whichever sample format suits it; PipeWire converts
+        // anything else for us.
         let mut audio_info = spa::param::audio::AudioInfoRaw::new();
-        audio_info.set_format(spa::param::audio::AudioFormat::F32LE);
+        audio_info.set_rate(requested.sample_rate);
+        audio_info.set_channels(requested.channels as u32);
+        let mut properties: Vec<spa::pod::Property> = audio_info.into();
+        let id = |format: SpaAudioFormat| spa::utils::Id(format.as_raw());
+        properties.push(spa::pod::Property::new(
+            spa::param::format::FormatProperties::AudioFormat.as_raw(),
+            spa::pod::Value::Choice(spa::pod::ChoiceValue::Id(spa::utils::Choice(
+                spa::utils::ChoiceFlags::empty(),
+                spa::utils::ChoiceEnum::Enum {
+                    default: id(SpaAudioFormat::F32LE),
+                    alternatives: [
+                        SpaAudioFormat::F32LE,
+                        SpaAudioFormat::S32LE,
+                        SpaAudioFormat::S24LE,
+                        SpaAudioFormat::S16LE,
+                    ]
+                    .map(id)
+                    .to_vec(),
+                },
+            ))),
+        ));
 
         let obj = pw::spa::pod::Object {
             type_: pw::spa::utils::SpaTypes::ObjectParamFormat.as_raw(),
             id: pw::spa::param::ParamType::EnumFormat.as_raw(),
-            properties: audio_info.into(),
+            properties,
         };
 
         let values: Vec<u8> = pw::spa::pod::serialize::PodSerializer::serialize(
@@ -284,8 +445,7 @@ impl PipeWireCapture {
                 | pw::stream::StreamFlags::RT_PROCESS,
             &mut params,
         ) {
-            eprintln!("PipeWire: Failed to connect stream: {}", e);
-            return;
+            return fail("failed to connect stream", e);
         }
 
         // Run the main loop (blocking)
@@ -293,6 +453,130 @@ impl PipeWireCapture {
     }
 }
 
+/// List PipeWire audio source nodes
+///
+/// Each device's `id` is the node name to pass to
+/// [`PipeWireCapture::with_device`]. The source with the highest session
+/// priority is reported as the default, which is what the session manager
+/// picks when the user hasn't chosen one. Returns an empty list if no
+/// PipeWire daemon is reachable.
+///
+/// # Errors
+///
+/// Returns an error if the PipeWire main loop or registry can't be set up.
+pub fn list_sources() -> AudioResult<Vec<AudioDevice>> {
+    use pipewire as pw;
+
+    pw::init();
+
+    let pw_err = |what: &str, e: pw::Error| AudioError::DeviceError(format!("PipeWire: {}: {}", what, e));
+
+    let mainloop = pw::main_loop::MainLoopRc::new(None)
+        .map_err(|e| pw_err("failed to create main loop", e))?;
+    let context = pw::context::ContextRc::new(&mainloop, None)
+        .map_err(|e| pw_err("failed to create context", e))?;
+    let core = match context.connect_rc(None) {
+        Ok(c) => c,
+        Err(e) => {
+            log::warn!("PipeWire: failed to connect, no devices listed: {}", e);
+            return Ok(Vec::new());
+        }
+    };
+    let registry = core.get_registry().map_err(|e| pw_err("failed to get registry", e))?;
+
+    // (device, priority.session) for every source announced before the sync
+    let sources: Rc<RefCell<Vec<(AudioDevice, i64)>>> = Rc::new(RefCell::new(Vec::new()));
+    let done = Rc::new(Cell::new(false));
+
+    let pending = core.sync(0).map_err(|e| pw_err("sync failed", e))?;
+
+    let done_clone = done.clone();
+    let loop_clone = mainloop.clone();
+    let _core_listener = core
+        .add_listener_local()
+        .done(move |id, seq| {
+            if id == pw::core::PW_ID_CORE && seq == pending {
+                done_clone.set(true);
+                loop_clone.quit();
+            }
+        })
+        .register();
+
+    let sources_clone = sources.clone();
+    let _registry_listener = registry
+        .add_listener_local()
+        .global(move |global| {
+            if global.type_ != pw::types::ObjectType::Node {
+                return;
+            }
+            let Some(props) = global.props else {
+                return;
+            };
+            if props.get(*pw::keys::MEDIA_CLASS) != Some("Audio/Source") {
+                return;
+            }
+            let Some(node_name) = props.get(*pw::keys::NODE_NAME) else {
+                return;
+            };
+
+            let name = props
+                .get(*pw::keys::NODE_DESCRIPTION)
+                .or_else(|| props.get(*pw::keys::NODE_NICK))
+                .unwrap_or(node_name);
+            let priority = props
+                .get("priority.session")
+                .and_then(|p| p.parse().ok())
+                .unwrap_or(0);
+
+            sources_clone.borrow_mut().push((
+                AudioDevice {
+                    name: name.to_string(),
+                    id: node_name.to_string(),
+                    is_default: false,
+                },
+                priority,
+            ));
+        })
+        .register();
+
+    // Give up if the daemon never answers the sync
+    let timed_out = Rc::new(Cell::new(false));
+    let timer = mainloop.loop_().add_timer({
+        let timed_out = timed_out.clone();
+        let mainloop = mainloop.clone();
+        move |_| {
+            timed_out.set(true);
+            mainloop.quit();
+        }
+    });
+    timer
+        .update_timer(Some(LIST_TIMEOUT), None)
+        .into_result()
+        .map_err(|e| AudioError::DeviceError(format!("PipeWire: failed to arm timer: {}", e)))?;
+
+    while !done.get() && !timed_out.get() {
+        mainloop.run();
+    }
+    if !done.get() {
+        return Err(AudioError::DeviceError(format!(
+            "PipeWire: no reply from the daemon within {:?}",
+            LIST_TIMEOUT
+        )));
+    }
+
+    let mut sources = sources.take();
+    let default_idx = sources
+        .iter()
+        .enumerate()
+        .max_by_key(|(_, (_, priority))| *priority)
+        .map(|(i, _)| i);
+    if let Some(i) = default_idx {
+        sources[i].0.is_default = true;
+    }
+
+    Ok(sources.into_iter().map(|(device, _)| device).collect())
+}
+
 impl Drop for PipeWireCapture {
     fn drop(&mut self) {
         let _ = self.stop();
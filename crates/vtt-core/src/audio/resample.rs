@@ -0,0 +1,373 @@
+//! Band-limited sample rate conversion
+//!
+//! A windowed-sinc (Kaiser) resampler evaluated as a polyphase FIR bank.
+//! The rate ratio is reduced to `up / down`; output sample `n` sits at input
+//! position `n * down / up`, and its fractional part selects one of `up`
+//! precomputed filter phases. When downsampling, the cutoff moves below the
+//! output Nyquist frequency so content above it is removed instead of
+//! folding back into the speech band.
+//!
+//! [`Resampler`] keeps the tail of each chunk between calls, so live capture
+//! can be converted as it arrives and gives the same result as converting
+//! the whole recording at once with [`resample`].
+
+use std::f64::consts::PI;
+
+/// Zero crossings of the sinc on each side of the centre tap
+const ZERO_CROSSINGS: f64 = 16.0;
+/// Fraction of the target Nyquist frequency left in the passband
+const ROLLOFF: f64 = 0.94;
+/// Kaiser window shape; ~80 dB stopband attenuation
+const KAISER_BETA: f64 = 8.0;
+/// Largest filter bank built up front, in coefficients
+///
+/// Rate pairs with an unusually large reduced ratio compute their
+/// coefficients per output sample instead.
+const MAX_TABLE_LEN: usize = 1 << 20;
+
+/// Streaming resampler for interleaved audio
+///
+/// Feed chunks of any length to [`process`](Self::process) and call
+/// [`flush`](Self::flush) once at the end of the stream to get the samples
+/// still held back by the filter.
+#[derive(Debug, Clone)]
+pub struct Resampler {
+    from_rate: u32,
+    to_rate: u32,
+    channels: usize,
+    up: u64,
+    down: u64,
+    /// Input frames each side of an output position the filter reaches
+    half_width: usize,
+    /// Cutoff in cycles per input sample
+    cutoff: f64,
+    /// `up` phases of `2 * half_width` taps, empty if computed on the fly
+    table: Vec<f32>,
+    /// Interleaved input frames not yet fully consumed
+    pending: Vec<f32>,
+    /// Absolute input frame index of `pending[0]`
+    pending_start: u64,
+    /// Index of the next output frame
+    next_out: u64,
+    /// Input frames received so far
+    frames_in: u64,
+}
+
+impl Resampler {
+    /// Create a resampler converting `channels`-channel audio from
+    /// `from_rate` to `to_rate` Hz
+    ///
+    /// # Panics
+    ///
+    /// Panics if either rate or the channel count is zero.
+    pub fn new(from_rate: u32, to_rate: u32, channels: u16) -> Self {
+        assert!(from_rate > 0 && to_rate > 0, "sample rates must be non-zero");
+        assert!(channels > 0, "channel count must be non-zero");
+
+        let divisor = gcd(from_rate as u64, to_rate as u64);
+        let up = to_rate as u64 / divisor;
+        let down = from_rate as u64 / divisor;
+
+        let cutoff = 0.5 * ROLLOFF * (up as f64 / down as f64).min(1.0);
+        let half_width = (ZERO_CROSSINGS / (2.0 * cutoff)).ceil() as usize;
+
+        let mut resampler = Self {
+            from_rate,
+            to_rate,
+            channels: channels as usize,
+            up,
+            down,
+            half_width,
+            cutoff,
+            table: Vec::new(),
+            pending: Vec::new(),
+            pending_start: 0,
+            next_out: 0,
+            frames_in: 0,
+        };
+        let taps = resampler.taps();
+        if from_rate != to_rate && (up as usize).saturating_mul(taps) <= MAX_TABLE_LEN {
+            let mut table = Vec::with_capacity(up as usize * taps);
+            for phase in 0..up {
+                table.extend(resampler.phase_coefficients(phase as f64 / up as f64));
+            }
+            resampler.table = table;
+        }
+        resampler
+    }
+
+    /// Input sample rate in Hz
+    pub fn from_rate(&self) -> u32 {
+        self.from_rate
+    }
+
+    /// Output sample rate in Hz
+    pub fn to_rate(&self) -> u32 {
+        self.to_rate
+    }
+
+    /// Interleaved channels per frame
+    pub fn channels(&self) -> u16 {
+        self.channels as u16
+    }
+
+    /// Whether input and output rates are equal and samples pass through
+    pub fn is_passthrough(&self) -> bool {
+        self.from_rate == self.to_rate
+    }
+
+    /// Input frames held back before the first output can be produced
+    pub fn latency_frames(&self) -> usize {
+        if self.is_passthrough() { 0 } else { self.half_width }
+    }
+
+    /// Resample the next chunk of interleaved input
+    ///
+    /// A trailing partial frame is ignored. Returns every output frame whose
+    /// filter window is fully covered by the input seen so far.
+    pub fn process(&mut self, input: &[f32]) -> Vec<f32> {
+        let input = &input[..input.len() - input.len() % self.channels];
+        if self.is_passthrough() {
+            return input.to_vec();
+        }
+
+        self.pending.extend_from_slice(input);
+        self.frames_in += (input.len() / self.channels) as u64;
+
+        // Output n reaches input frames up to floor(n * down / up) + half_width
+        let mut out = Vec::new();
+        while (self.next_out * self.down) / self.up + (self.half_width as u64) < self.frames_in {
+            self.push_frame(&mut out);
+        }
+        self.discard_consumed();
+        out
+    }
+
+    /// Finish the stream, treating the input as silent past its end
+    ///
+    /// Returns the remaining output so that the total length is
+    /// `ceil(input_frames * to_rate / from_rate)` frames, then resets the
+    /// resampler for a new stream.
+    pub fn flush(&mut self) -> Vec<f32> {
+        let mut out = Vec::new();
+        if !self.is_passthrough() {
+            let total = (self.frames_in * self.up).div_ceil(self.down);
+            while self.next_out < total {
+                self.push_frame(&mut out);
+            }
+        }
+        self.reset();
+        out
+    }
+
+    /// Drop any buffered input and start a new stream
+    pub fn reset(&mut self) {
+        self.pending.clear();
+        self.pending_start = 0;
+        self.next_out = 0;
+        self.frames_in = 0;
+    }
+
+    fn taps(&self) -> usize {
+        2 * self.half_width
+    }
+
+    /// Compute output frame `next_out` and append it to `out`
+    fn push_frame(&mut self, out: &mut Vec<f32>) {
+        let position = self.next_out * self.down;
+        let centre = position / self.up;
+        let phase = position % self.up;
+
+        let taps = self.taps();
+        let computed;
+        let coefficients = if self.table.is_empty() {
+            computed = self.phase_coefficients(phase as f64 / self.up as f64);
+            &computed[..]
+        } else {
+            let start = phase as usize * taps;
+            &self.table[start..start + taps]
+        };
+
+        // Tap j covers input frame centre + j - (half_width - 1)
+        let first = centre as i64 - (self.half_width as i64 - 1);
+        for channel in 0..self.channels {
+            let mut acc = 0.0f32;
+            for (j, coefficient) in coefficients.iter().enumerate() {
+                let frame = first + j as i64;
+                if frame < self.pending_start as i64 {
+                    continue;
+                }
+                let index = (frame as u64 - self.pending_start) as usize * self.channels + channel;
+                if let Some(sample) = self.pending.get(index) {
+                    acc += sample * coefficient;
+                }
+            }
+            out.push(acc);
+        }
+        self.next_out += 1;
+    }
+
+    /// Drop buffered frames no future output frame reaches
+    fn discard_consumed(&mut self) {
+        let centre = (self.next_out * self.down) / self.up;
+        let keep_from = centre.saturating_sub(self.half_width as u64 - 1);
+        if keep_from > self.pending_start {
+            let frames = ((keep_from - self.pending_start) as usize).min(self.pending.len() / self.channels);
+            self.pending.drain(..frames * self.channels);
+            self.pending_start += frames as u64;
+        }
+    }
+
+    /// Taps for an output position `frac` input frames past its centre
+    /// frame, normalised to unit DC gain
+    fn phase_coefficients(&self, frac: f64) -> Vec<f32> {
+        let half_width = self.half_width as f64;
+        let coefficients: Vec<f64> = (0..self.taps())
+            .map(|j| {
+                // Distance from the output position to the tap's input frame
+                let x = frac - (j as f64 - (half_width - 1.0));
+                let window = kaiser(x / half_width);
+                2.0 * self.cutoff * sinc(2.0 * self.cutoff * x) * window
+            })
+            .collect();
+        let sum: f64 = coefficients.iter().sum();
+        coefficients.iter().map(|c| (c / sum) as f32).collect()
+    }
+}
+
+/// Resample a whole interleaved buffer in one go
+pub fn resample(samples: &[f32], from_rate: u32, to_rate: u32, channels: u16) -> Vec<f32> {
+    if from_rate == to_rate {
+        return samples.to_vec();
+    }
+    let mut resampler = Resampler::new(from_rate, to_rate, channels);
+    let mut out = resampler.process(samples);
+    out.extend(resampler.flush());
+    out
+}
+
+/// Resample a whole mono buffer in one go
+pub fn resample_mono(samples: &[f32], from_rate: u32, to_rate: u32) -> Vec<f32> {
+    resample(samples, from_rate, to_rate, 1)
+}
+
+fn sinc(x: f64) -> f64 {
+    if x.abs() < 1e-12 {
+        1.0
+    } else {
+        (PI * x).sin() / (PI * x)
+    }
+}
+
+/// Kaiser window over [-1, 1], zero outside
+fn kaiser(x: f64) -> f64 {
+    if x.abs() > 1.0 {
+        return 0.0;
+    }
+    bessel_i0(KAISER_BETA * (1.0 - x * x).sqrt()) / bessel_i0(KAISER_BETA)
+}
+
+/// Zeroth-order modified Bessel function of the first kind
+fn bessel_i0(x: f64) -> f64 {
+    let mut sum = 1.0;
+    let mut term = 1.0;
+    let half = x / 2.0;
+    for k in 1..50 {
+        term *= (half / k as f64).powi(2);
+        sum += term;
+        if term < sum * 1e-12 {
+            break;
+        }
+    }
+    sum
+}
+
+fn gcd(mut a: u64, mut b: u64) -> u64 {
+    while b != 0 {
+        (a, b) = (b, a % b);
+    }
+    a
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sine(freq: f64, rate: u32, frames: usize) -> Vec<f32> {
+        (0..frames)
+            .map(|i| (2.0 * PI * freq * i as f64 / rate as f64).sin() as f32)
+            .collect()
+    }
+
+    fn rms(samples: &[f32]) -> f32 {
+        (samples.iter().map(|s| s * s).sum::<f32>() / samples.len() as f32).sqrt()
+    }
+
+    #[test]
+    fn test_output_length() {
+        for (from, to) in [(48000, 16000), (44100, 16000), (22050, 16000), (8000, 16000)] {
+            let out = resample_mono(&vec![0.0; 4410], from, to);
+            let expected = (4410u64 * to as u64).div_ceil(from as u64) as usize;
+            assert_eq!(out.len(), expected, "{} -> {}", from, to);
+        }
+        let samples: Vec<f32> = (0..480).map(|i| i as f32).collect();
+        assert_eq!(resample_mono(&samples, 16000, 16000), samples);
+    }
+
+    #[test]
+    fn test_passband_is_preserved() {
+        let input = sine(1000.0, 48000, 48000);
+        let out = resample_mono(&input, 48000, 16000);
+        let expected = sine(1000.0, 16000, 16000);
+        // Ignore the edges, where the filter sees silence
+        let middle = 200..out.len() - 200;
+        let error = out[middle.clone()]
+            .iter()
+            .zip(&expected[middle])
+            .map(|(a, b)| (a - b).abs())
+            .fold(0.0f32, f32::max);
+        assert!(error < 0.01, "max error {}", error);
+    }
+
+    #[test]
+    fn test_removes_content_above_nyquist() {
+        // 12 kHz would alias to 4 kHz at 16 kHz with naive decimation
+        let input = sine(12000.0, 48000, 48000);
+        let out = resample_mono(&input, 48000, 16000);
+        assert!(rms(&out[200..out.len() - 200]) < 0.001);
+
+        // 9 kHz would come back as a 7 kHz alias
+        let out = resample_mono(&sine(9000.0, 44100, 44100), 44100, 16000);
+        assert!(rms(&out[200..out.len() - 200]) < 0.001);
+    }
+
+    #[test]
+    fn test_streaming_matches_one_shot() {
+        let input = sine(440.0, 44100, 10000);
+        let expected = resample_mono(&input, 44100, 16000);
+
+        let mut resampler = Resampler::new(44100, 16000, 1);
+        let mut out = Vec::new();
+        for chunk in input.chunks(333) {
+            out.extend(resampler.process(chunk));
+        }
+        out.extend(resampler.flush());
+        assert_eq!(out.len(), expected.len());
+        assert!(out.iter().zip(&expected).all(|(a, b)| (a - b).abs() < 1e-6));
+    }
+
+    #[test]
+    fn test_interleaved_channels() {
+        let left = sine(500.0, 48000, 4800);
+        let stereo: Vec<f32> = left.iter().flat_map(|&s| [s, -s]).collect();
+        let out = resample(&stereo, 48000, 16000, 2);
+        assert_eq!(out.len(), 3200);
+        assert!(out.chunks_exact(2).all(|frame| (frame[0] + frame[1]).abs() < 1e-6));
+    }
+
+    #[test]
+    fn test_dc_gain() {
+        let out = resample_mono(&vec![0.5; 8000], 8000, 16000);
+        assert!(out[100..out.len() - 100].iter().all(|s| (s - 0.5).abs() < 1e-3));
+    }
+}
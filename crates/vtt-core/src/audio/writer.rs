@@ -3,6 +3,7 @@
 use super::error::{AudioError, AudioResult};
 use super::format::AudioFormat;
 use hound::{WavSpec, WavWriter};
+use std::io::{Cursor, Seek, Write};
 use std::path::Path;
 
 /// Write f32 audio samples to WAV file
@@ -25,28 +26,41 @@ pub fn write_wav<P: AsRef<Path>>(
     samples: &[f32],
     format: &AudioFormat,
 ) -> AudioResult<()> {
-    let spec = WavSpec {
+    let writer = WavWriter::create(path, wav_spec(format)).map_err(wav_error)?;
+    write_samples(writer, samples)
+}
+
+/// Encode f32 audio samples as an in-memory 16-bit PCM WAV file
+///
+/// # Errors
+///
+/// Returns an error if encoding fails.
+pub fn encode_wav(samples: &[f32], format: &AudioFormat) -> AudioResult<Vec<u8>> {
+    let mut bytes = Cursor::new(Vec::with_capacity(44 + samples.len() * 2));
+    let writer = WavWriter::new(&mut bytes, wav_spec(format)).map_err(wav_error)?;
+    write_samples(writer, samples)?;
+    Ok(bytes.into_inner())
+}
+
+fn wav_spec(format: &AudioFormat) -> WavSpec {
+    WavSpec {
         channels: format.channels,
         sample_rate: format.sample_rate,
         bits_per_sample: 16,
         sample_format: hound::SampleFormat::Int,
-    };
-
-    let mut writer = WavWriter::create(path, spec)
-        .map_err(|e| AudioError::IoError(std::io::Error::new(std::io::ErrorKind::Other, e)))?;
+    }
+}
 
+fn write_samples<W: Write + Seek>(mut writer: WavWriter<W>, samples: &[f32]) -> AudioResult<()> {
     for &sample in samples {
         let sample_i16 = (sample * i16::MAX as f32) as i16;
-        writer
-            .write_sample(sample_i16)
-            .map_err(|e| AudioError::IoError(std::io::Error::new(std::io::ErrorKind::Other, e)))?;
+        writer.write_sample(sample_i16).map_err(wav_error)?;
     }
+    writer.finalize().map_err(wav_error)
+}
 
-    writer
-        .finalize()
-        .map_err(|e| AudioError::IoError(std::io::Error::new(std::io::ErrorKind::Other, e)))?;
-
-    Ok(())
+fn wav_error(e: hound::Error) -> AudioError {
+    AudioError::IoError(std::io::Error::new(std::io::ErrorKind::Other, e))
 }
 
 #[cfg(test)]
@@ -70,4 +84,19 @@ mod tests {
         // Clean up
         let _ = fs::remove_file(path);
     }
+
+    #[test]
+    fn test_encode_wav() {
+        let samples = vec![0.0, 0.5, -0.5, 1.0];
+        let format = AudioFormat::STT_DEFAULT;
+        let bytes = encode_wav(&samples, &format).unwrap();
+        assert_eq!(&bytes[..4], b"RIFF");
+        assert_eq!(bytes.len(), 44 + samples.len() * 2);
+
+        let reader = hound::WavReader::new(Cursor::new(bytes)).unwrap();
+        assert_eq!(reader.spec().sample_rate, 16000);
+        assert_eq!(reader.spec().channels, 1);
+        let decoded: Vec<i16> = reader.into_samples().map(|s| s.unwrap()).collect();
+        assert_eq!(decoded, vec![0, i16::MAX / 2, -(i16::MAX / 2), i16::MAX]);
+    }
 }
@@ -1,26 +1,103 @@
+//! Layered configuration loading
+//!
+//! Settings are resolved from, lowest to highest precedence: built-in
+//! defaults, `/etc/vtt-mcp/config.toml`, `~/.config/vtt-mcp/config.toml`, an
+//! explicit `--config` file, then `VTT_*` environment variables. Files may be
+//! partial; each one only overrides the keys it sets. Command-line flags and
+//! per-call tool parameters are applied on top by the binaries.
+
 use super::schema::Config;
 use std::path::{Path, PathBuf};
 use std::fs;
-use anyhow::Result;
+use anyhow::{anyhow, Context, Result};
 
 pub struct ConfigLoader;
 
 impl ConfigLoader {
+    /// Load defaults, the system and user files, and `VTT_*` overrides
     pub fn load() -> Result<Config> {
-        if let Some(user) = Self::find_user_config() {
-            return Self::load_from_file(&user);
-        }
-        if let Some(sys) = Self::find_system_config() {
-            return Self::load_from_file(&sys);
+        Self::load_with(None)
+    }
+
+    /// Like [`load`](Self::load), with `explicit` layered over the user file
+    ///
+    /// Unlike the system and user files, an explicit file must exist.
+    pub fn load_with(explicit: Option<&Path>) -> Result<Config> {
+        let mut paths: Vec<PathBuf> = Self::find_system_config()
+            .into_iter()
+            .chain(Self::find_user_config())
+            .collect();
+        if let Some(path) = explicit {
+            paths.push(path.to_path_buf());
         }
-        Ok(Config::default())
+
+        let mut config = Self::load_layers(&paths)?;
+        Self::apply_env(&mut config)?;
+        Ok(config)
     }
-    
+
+    /// Load a single file over the built-in defaults
     pub fn load_from_file(path: &Path) -> Result<Config> {
-        let contents = fs::read_to_string(path)?;
-        Ok(toml::from_str(&contents)?)
+        Self::load_layers(&[path.to_path_buf()])
     }
-    
+
+    /// Merge `paths` in order over the built-in defaults
+    pub fn load_layers(paths: &[PathBuf]) -> Result<Config> {
+        let mut merged = toml::Value::try_from(Config::default())?;
+        for path in paths {
+            let contents = fs::read_to_string(path)
+                .with_context(|| format!("Failed to read config {}", path.display()))?;
+            let mut layer: toml::Value = toml::from_str(&contents)
+                .with_context(|| format!("Invalid config {}", path.display()))?;
+            resolve_vocabulary_file(&mut layer, path);
+            merge(&mut merged, layer);
+        }
+        let mut config: Config = merged.try_into()?;
+        if let Some(ref path) = config.whisper.vocabulary_file {
+            let terms = read_vocabulary(Path::new(path))
+                .with_context(|| format!("Failed to read vocabulary file {}", path))?;
+            for term in terms {
+                if !config.whisper.vocabulary.contains(&term) {
+                    config.whisper.vocabulary.push(term);
+                }
+            }
+        }
+        Ok(config)
+    }
+
+    /// Apply `VTT_*` environment overrides
+    pub fn apply_env(config: &mut Config) -> Result<()> {
+        Self::apply_env_from(config, |key| std::env::var(key).ok())
+    }
+
+    /// Apply `VTT_*` overrides looked up through `var`
+    pub fn apply_env_from(config: &mut Config, var: impl Fn(&str) -> Option<String>) -> Result<()> {
+        if let Some(v) = var("VTT_MODEL_PATH") {
+            config.whisper.model_path = Some(v);
+        }
+        if let Some(v) = var("VTT_MODEL_SIZE") {
+            config.whisper.model_size = v;
+        }
+        if let Some(v) = var("VTT_THREADS") {
+            config.whisper.threads = Some(v.parse().map_err(|_| anyhow!("VTT_THREADS must be a number, got '{}'", v))?);
+        }
+        if let Some(v) = var("VTT_USE_GPU") {
+            config.whisper.enable_gpu = parse_bool("VTT_USE_GPU", &v)?;
+        }
+        if let Some(v) = var("VTT_NO_GPU") {
+            if parse_bool("VTT_NO_GPU", &v)? {
+                config.whisper.enable_gpu = false;
+            }
+        }
+        if let Some(v) = var("VTT_TRANSPORT") {
+            config.mcp.transport = v;
+        }
+        if let Some(v) = var("VTT_BIND_ADDRESS") {
+            config.mcp.bind_address = v;
+        }
+        Ok(())
+    }
+
     fn find_user_config() -> Option<PathBuf> {
         let p = dirs::config_dir()?.join("vtt-mcp").join("config.toml");
         if p.exists() { Some(p) } else { None }
@@ -32,6 +109,55 @@ impl ConfigLoader {
     }
 }
 
+/// Recursively merge `overlay` into `base`; tables merge key by key, anything
+/// else is replaced
+fn merge(base: &mut toml::Value, overlay: toml::Value) {
+    match (base, overlay) {
+        (toml::Value::Table(base), toml::Value::Table(overlay)) => {
+            for (key, value) in overlay {
+                match base.get_mut(&key) {
+                    Some(existing) => merge(existing, value),
+                    None => {
+                        base.insert(key, value);
+                    }
+                }
+            }
+        }
+        (base, overlay) => *base = overlay,
+    }
+}
+
+/// Make a relative `whisper.vocabulary_file` set in `layer` relative to the
+/// directory of `path`, the config file it came from
+fn resolve_vocabulary_file(layer: &mut toml::Value, path: &Path) {
+    let file = layer.get_mut("whisper").and_then(|whisper| whisper.get_mut("vocabulary_file"));
+    if let (Some(toml::Value::String(file)), Some(dir)) = (file, path.parent()) {
+        if Path::new(file.as_str()).is_relative() {
+            *file = dir.join(&*file).to_string_lossy().into_owned();
+        }
+    }
+}
+
+/// Terms in a vocabulary file: one per line, blank lines and `#` comments
+/// skipped
+fn read_vocabulary(path: &Path) -> Result<Vec<String>> {
+    let contents = fs::read_to_string(path)?;
+    Ok(contents
+        .lines()
+        .map(|line| line.split('#').next().unwrap_or_default().trim())
+        .filter(|line| !line.is_empty())
+        .map(str::to_string)
+        .collect())
+}
+
+fn parse_bool(name: &str, value: &str) -> Result<bool> {
+    match value.to_ascii_lowercase().as_str() {
+        "1" | "true" | "yes" | "on" => Ok(true),
+        "0" | "false" | "no" | "off" => Ok(false),
+        _ => Err(anyhow!("{} must be true or false, got '{}'", name, value)),
+    }
+}
+
 
 
 #[cfg(test)]
@@ -81,12 +207,118 @@ transport = "stdio"
         assert_eq!(config.audio.channels, 2);
         assert_eq!(config.vad.threshold, 0.3);
         assert_eq!(config.whisper.model_size, "tiny");
-        assert_eq!(config.whisper.threads, 2);
+        assert_eq!(config.whisper.threads, Some(2));
         assert_eq!(config.whisper.memory.idle_timeout_secs, 120);
         assert_eq!(config.transcription.interval_ms, 250);
         assert!(!config.transcription.detect_language);
     }
 
+    fn write_config(dir: &tempfile::TempDir, name: &str, contents: &str) -> PathBuf {
+        let path = dir.path().join(name);
+        fs::write(&path, contents).unwrap();
+        path
+    }
+
+    #[test]
+    fn test_partial_layers_merge_field_by_field() {
+        let dir = tempfile::tempdir().unwrap();
+        let system = write_config(&dir, "system.toml", r#"
+[whisper]
+model_size = "small"
+threads = 8
+
+[whisper.memory]
+max_sessions = 2
+"#);
+        let user = write_config(&dir, "user.toml", r#"
+[whisper]
+threads = 2
+
+[mcp]
+transport = "http"
+"#);
+
+        let config = ConfigLoader::load_layers(&[system, user]).unwrap();
+        assert_eq!(config.whisper.model_size, "small");
+        assert_eq!(config.whisper.threads, Some(2));
+        assert_eq!(config.whisper.memory.max_sessions, 2);
+        assert_eq!(config.whisper.memory.idle_timeout_secs, 300);
+        assert_eq!(config.mcp.transport, "http");
+        assert_eq!(config.mcp.name, "vtt-mcp");
+        assert_eq!(config.audio.sample_rate, 16000);
+    }
+
+    #[test]
+    fn test_vocabulary_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let vocabulary = write_config(&dir, "vocabulary.txt", "# Product names\nPipeWire\n\n  Kubernetes  # k8s\nWhisper\n");
+        let config = write_config(&dir, "vocabulary.toml", &format!(r#"
+[whisper]
+vocabulary = ["Whisper"]
+vocabulary_file = "{}"
+"#, vocabulary.display()));
+
+        let config = ConfigLoader::load_layers(&[config]).unwrap();
+        assert_eq!(config.whisper.vocabulary, vec!["Whisper", "PipeWire", "Kubernetes"]);
+
+        let missing = write_config(&dir, "missing-vocabulary.toml", r#"
+[whisper]
+vocabulary_file = "/nonexistent/vocabulary.txt"
+"#);
+        assert!(ConfigLoader::load_layers(&[missing]).is_err());
+    }
+
+    #[test]
+    fn test_relative_vocabulary_file() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::create_dir(dir.path().join("terms")).unwrap();
+        write_config(&dir, "terms/vocabulary.txt", "PipeWire
+");
+        let system = write_config(&dir, "system.toml", r#"
+[whisper]
+vocabulary_file = "terms/vocabulary.txt"
+"#);
+        // A later layer that doesn't set it leaves the path as resolved
+        let user = write_config(&dir, "user.toml", r#"
+[whisper]
+threads = 2
+"#);
+
+        let config = ConfigLoader::load_layers(&[system, user]).unwrap();
+        assert_eq!(config.whisper.vocabulary, vec!["PipeWire"]);
+        assert_eq!(
+            config.whisper.vocabulary_file.map(PathBuf::from),
+            Some(dir.path().join("terms/vocabulary.txt"))
+        );
+    }
+
+    #[test]
+    fn test_missing_explicit_config() {
+        let result = ConfigLoader::load_with(Some(Path::new("/nonexistent/vtt-mcp.toml")));
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_env_overrides() {
+        let mut config = Config::default();
+        let env = |key: &str| match key {
+            "VTT_MODEL_PATH" => Some("/models/tiny.bin".to_string()),
+            "VTT_THREADS" => Some("6".to_string()),
+            "VTT_NO_GPU" => Some("1".to_string()),
+            "VTT_BIND_ADDRESS" => Some("0.0.0.0:9000".to_string()),
+            _ => None,
+        };
+        ConfigLoader::apply_env_from(&mut config, env).unwrap();
+        assert_eq!(config.whisper.resolved_model_path(), "/models/tiny.bin");
+        assert_eq!(config.whisper.threads, Some(6));
+        assert!(!config.whisper.enable_gpu);
+        assert_eq!(config.mcp.bind_address, "0.0.0.0:9000");
+        assert_eq!(config.mcp.transport, "stdio");
+
+        let bad = |key: &str| (key == "VTT_THREADS").then(|| "many".to_string());
+        assert!(ConfigLoader::apply_env_from(&mut config, bad).is_err());
+    }
+
     #[test]
     fn test_invalid_toml() {
         let toml_content = "invalid toml content [[[";
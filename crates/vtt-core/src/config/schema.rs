@@ -1,35 +1,194 @@
+use crate::audio::{AudioFormat, AudioSampleFormat, ChannelPolicy};
+use crate::vad::{SegmenterConfig, VadBackendKind};
+use crate::whisper::DecodingOverrides;
 use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Clone, Deserialize, Serialize)]
-pub struct Config { pub audio: AudioConfig, pub vad: VadConfig, pub whisper: WhisperConfig, pub transcription: TranscriptionConfig, pub mcp: McpConfig }
+#[serde(default)]
+pub struct Config { pub audio: AudioConfig, pub vad: VadConfig, pub whisper: WhisperConfig, pub transcription: TranscriptionConfig, pub mcp: McpConfig, pub history: HistoryConfig, pub sessions: SessionConfig }
 
 impl Default for Config {
-    fn default() -> Self { Self { audio: AudioConfig::default(), vad: VadConfig::default(), whisper: WhisperConfig::default(), transcription: TranscriptionConfig::default(), mcp: McpConfig::default() } }
+    fn default() -> Self { Self { audio: AudioConfig::default(), vad: VadConfig::default(), whisper: WhisperConfig::default(), transcription: TranscriptionConfig::default(), mcp: McpConfig::default(), history: HistoryConfig::default(), sessions: SessionConfig::default() } }
 }
 
 #[derive(Debug, Clone, Deserialize, Serialize)]
-pub struct AudioConfig { pub sample_rate: u32, pub channels: u16 }
-impl Default for AudioConfig { fn default() -> Self { Self { sample_rate: 16000, channels: 1 } } }
+#[serde(default)]
+pub struct AudioConfig { pub sample_rate: u32, pub channels: u16, pub channel_policy: ChannelPolicy }
+impl Default for AudioConfig { fn default() -> Self { Self { sample_rate: 16000, channels: 1, channel_policy: ChannelPolicy::Downmix } } }
+
+impl AudioConfig {
+    /// Format to ask the capture device for
+    ///
+    /// The channel count is raised to what the channel policy needs, so
+    /// `{ select = 2 }` asks for at least three channels.
+    pub fn capture_format(&self) -> AudioFormat {
+        AudioFormat::new(self.sample_rate, self.channel_policy.input_channels(self.channels), AudioSampleFormat::F32)
+    }
+}
 
 #[derive(Debug, Clone, Deserialize, Serialize)]
-pub struct VadConfig { pub threshold: f32 }
-impl Default for VadConfig { fn default() -> Self { Self { threshold: 0.5 } } }
+#[serde(default)]
+pub struct VadConfig {
+    /// Detector to run: "energy" or "silero"
+    pub backend: VadBackendKind,
+    /// Speech probability a Silero frame must reach
+    pub threshold: f32,
+    /// RMS level an energy frame must reach
+    pub energy_threshold: f32,
+    /// Speech frames in a row that start speech
+    pub speech_frames: usize,
+    /// Silent frames in a row that end speech
+    pub silence_frames: usize,
+    /// Silero ONNX model file
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub model_path: Option<String>,
+    /// Audio kept in front of each utterance
+    pub pre_roll_ms: u32,
+    /// Shorter bursts of speech are ignored
+    pub min_speech_ms: u32,
+    /// Silence that ends an utterance
+    pub min_silence_ms: u32,
+    /// Utterances are split at this length
+    pub max_segment_ms: u32,
+}
+impl Default for VadConfig {
+    fn default() -> Self {
+        let segments = SegmenterConfig::default();
+        Self {
+            backend: VadBackendKind::Energy,
+            threshold: 0.5,
+            energy_threshold: 0.01,
+            speech_frames: 3,
+            silence_frames: 10,
+            model_path: None,
+            pre_roll_ms: segments.pre_roll_ms,
+            min_speech_ms: segments.min_speech_ms,
+            min_silence_ms: segments.min_silence_ms,
+            max_segment_ms: segments.max_segment_ms,
+        }
+    }
+}
+impl VadConfig {
+    /// Detector settings for [`crate::vad::create_backend`]
+    pub fn detector_config(&self) -> crate::vad::VadConfig {
+        crate::vad::VadConfig {
+            backend: self.backend,
+            energy_threshold: self.energy_threshold.clamp(0.0, 1.0),
+            speech_frames_threshold: self.speech_frames,
+            silence_frames_threshold: self.silence_frames,
+            speech_probability_threshold: self.threshold.clamp(0.0, 1.0),
+            model_path: self.model_path.as_ref().map(Into::into),
+            ..Default::default()
+        }
+    }
+
+    /// Utterance segmentation for audio at `sample_rate`
+    pub fn segmenter_config(&self, sample_rate: u32) -> SegmenterConfig {
+        SegmenterConfig {
+            sample_rate,
+            pre_roll_ms: self.pre_roll_ms,
+            min_speech_ms: self.min_speech_ms,
+            min_silence_ms: self.min_silence_ms.max(1),
+            max_segment_ms: self.max_segment_ms.max(1),
+        }
+    }
+}
 
 #[derive(Debug, Clone, Deserialize, Serialize)]
-pub struct WhisperConfig { pub model_size: String, pub threads: usize, pub enable_gpu: bool, pub memory: MemoryConfig }
-impl Default for WhisperConfig { fn default() -> Self { Self { model_size: String::from("base"), threads: 4, enable_gpu: true, memory: MemoryConfig::default() } } }
+#[serde(default)]
+pub struct WhisperConfig {
+    pub model_size: String,
+    /// Explicit model file; when unset the model is `models/ggml-<model_size>.bin`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub model_path: Option<String>,
+    /// Transcription threads; when unset, one per logical CPU
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub threads: Option<usize>,
+    pub enable_gpu: bool,
+    /// Text to prime the decoder with, e.g. the topic or expected style
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub initial_prompt: Option<String>,
+    /// Names and jargon the model should spell this way
+    pub vocabulary: Vec<String>,
+    /// File of more vocabulary terms, one per line, `#` starting a comment;
+    /// its terms are appended to `vocabulary` when the config is loaded. A
+    /// relative path is taken from the directory of the config file
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub vocabulary_file: Option<String>,
+    pub memory: MemoryConfig,
+    /// Decoding preset and individual overrides, e.g. `preset = "accurate"`
+    pub decoding: DecodingOverrides,
+}
+impl Default for WhisperConfig { fn default() -> Self { Self { model_size: String::from("base"), model_path: None, threads: None, enable_gpu: true, initial_prompt: None, vocabulary: Vec::new(), vocabulary_file: None, memory: MemoryConfig::default(), decoding: DecodingOverrides::default() } } }
+impl WhisperConfig {
+    /// Model file to load: `model_path` if set, else derived from `model_size`
+    pub fn resolved_model_path(&self) -> String {
+        self.model_path.clone().unwrap_or_else(|| format!("models/ggml-{}.bin", self.model_size))
+    }
+
+    /// Threads to transcribe with: `threads` if set, else the CPU count
+    pub fn resolved_threads(&self) -> usize {
+        self.threads.unwrap_or_else(num_cpus::get)
+    }
+}
 
 #[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(default)]
 pub struct MemoryConfig { pub idle_timeout_secs: u64, pub max_sessions: usize }
 impl Default for MemoryConfig { fn default() -> Self { Self { idle_timeout_secs: 300, max_sessions: 10 } } }
 
 #[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(default)]
+pub struct SessionConfig {
+    /// Listening sessions allowed at once; 0 means no limit
+    pub max_concurrent: usize,
+    /// Stop a session that has been recording this long; 0 means no limit
+    pub max_recording_secs: u64,
+    /// Forget stopped sessions this long after they end; 0 keeps them
+    pub finished_ttl_secs: u64,
+}
+impl Default for SessionConfig { fn default() -> Self { Self { max_concurrent: 4, max_recording_secs: 3600, finished_ttl_secs: 1800 } } }
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(default)]
 pub struct TranscriptionConfig { pub interval_ms: u64, pub detect_language: bool }
 impl Default for TranscriptionConfig { fn default() -> Self { Self { interval_ms: 500, detect_language: true } } }
 
 #[derive(Debug, Clone, Deserialize, Serialize)]
-pub struct McpConfig { pub name: String, pub transport: String }
-impl Default for McpConfig { fn default() -> Self { Self { name: String::from("vtt-mcp"), transport: String::from("stdio") } } }
+#[serde(default)]
+pub struct McpConfig {
+    pub name: String,
+    /// "stdio", or "http" / "sse" for the HTTP server, which speaks both
+    /// Streamable HTTP and the legacy HTTP+SSE transport
+    pub transport: String,
+    /// Socket address the HTTP transport listens on
+    pub bind_address: String,
+}
+impl Default for McpConfig { fn default() -> Self { Self { name: String::from("vtt-mcp"), transport: String::from("stdio"), bind_address: String::from("127.0.0.1:8765") } } }
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(default)]
+pub struct HistoryConfig {
+    /// Keep history on disk across restarts; when false it lives in memory only
+    pub persist: bool,
+    /// History file; when unset it is `<data dir>/vtt-mcp/history.jsonl`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub path: Option<String>,
+    /// Newest entries to keep; 0 keeps all
+    pub max_entries: usize,
+    /// Drop entries older than this many days; 0 keeps them forever
+    pub max_age_days: u32,
+}
+impl Default for HistoryConfig { fn default() -> Self { Self { persist: true, path: None, max_entries: 1000, max_age_days: 0 } } }
+impl HistoryConfig {
+    /// History file to use: `path` if set, else under the XDG data dir
+    pub fn resolved_path(&self) -> Option<std::path::PathBuf> {
+        match &self.path {
+            Some(path) => Some(std::path::PathBuf::from(path)),
+            None => dirs::data_dir().map(|dir| dir.join("vtt-mcp").join("history.jsonl")),
+        }
+    }
+}
 
 
 
@@ -58,18 +217,51 @@ mod tests {
     fn test_vad_config() {
         let config = VadConfig::default();
         assert_eq!(config.threshold, 0.5);
+        assert_eq!(config.backend, VadBackendKind::Energy);
+
+        let detector = VadConfig {
+            backend: VadBackendKind::Silero,
+            threshold: 0.6,
+            model_path: Some("models/silero_vad.onnx".to_string()),
+            ..Default::default()
+        }
+        .detector_config();
+        assert_eq!(detector.backend, VadBackendKind::Silero);
+        assert_eq!(detector.speech_probability_threshold, 0.6);
+        assert_eq!(detector.energy_threshold, 0.01);
+        assert_eq!(detector.speech_frames_threshold, 3);
+        assert_eq!(detector.model_path.as_deref(), Some(std::path::Path::new("models/silero_vad.onnx")));
+
+        let segments = VadConfig { min_silence_ms: 800, ..Default::default() }.segmenter_config(16000);
+        assert_eq!(segments.sample_rate, 16000);
+        assert_eq!(segments.min_silence_ms, 800);
+        assert_eq!(segments.max_segment_ms, 30_000);
     }
 
     #[test]
     fn test_whisper_config() {
         let config = WhisperConfig::default();
         assert_eq!(config.model_size, "base");
-        assert_eq!(config.threads, 4);
+        assert_eq!(config.threads, None);
+        assert_eq!(config.resolved_threads(), num_cpus::get());
         assert!(config.enable_gpu);
+        assert_eq!(config.initial_prompt, None);
+        assert!(config.vocabulary.is_empty());
+        assert_eq!(config.decoding, DecodingOverrides::default());
         assert_eq!(config.memory.idle_timeout_secs, 300);
         assert_eq!(config.memory.max_sessions, 10);
     }
 
+    #[test]
+    fn test_resolved_model_path() {
+        let mut config = WhisperConfig::default();
+        assert_eq!(config.resolved_model_path(), "models/ggml-base.bin");
+        config.model_size = String::from("tiny");
+        assert_eq!(config.resolved_model_path(), "models/ggml-tiny.bin");
+        config.model_path = Some(String::from("/opt/models/custom.bin"));
+        assert_eq!(config.resolved_model_path(), "/opt/models/custom.bin");
+    }
+
     #[test]
     fn test_memory_config() {
         let config = MemoryConfig::default();
@@ -89,6 +281,28 @@ mod tests {
         let config = McpConfig::default();
         assert_eq!(config.name, "vtt-mcp");
         assert_eq!(config.transport, "stdio");
+        assert_eq!(config.bind_address, "127.0.0.1:8765");
+    }
+
+    #[test]
+    fn test_session_config() {
+        let config = SessionConfig::default();
+        assert_eq!(config.max_concurrent, 4);
+        assert_eq!(config.max_recording_secs, 3600);
+        assert_eq!(config.finished_ttl_secs, 1800);
+    }
+
+    #[test]
+    fn test_history_config() {
+        let mut config = HistoryConfig::default();
+        assert!(config.persist);
+        assert_eq!(config.max_entries, 1000);
+        assert_eq!(config.max_age_days, 0);
+        if let Some(path) = config.resolved_path() {
+            assert!(path.ends_with("vtt-mcp/history.jsonl"));
+        }
+        config.path = Some(String::from("/tmp/history.jsonl"));
+        assert_eq!(config.resolved_path(), Some(std::path::PathBuf::from("/tmp/history.jsonl")));
     }
 
     #[test]
@@ -105,19 +319,28 @@ mod tests {
 [audio]
 sample_rate = 48000
 channels = 2
+channel_policy = { select = 1 }
 
 [vad]
+backend = "silero"
 threshold = 0.7
+model_path = "models/silero_vad.onnx"
 
 [whisper]
 model_size = "small"
 threads = 8
 enable_gpu = false
+initial_prompt = "Meeting notes."
+vocabulary = ["PipeWire", "Kubernetes"]
 
 [whisper.memory]
 idle_timeout_secs = 600
 max_sessions = 5
 
+[whisper.decoding]
+preset = "accurate"
+beam_size = 3
+
 [transcription]
 interval_ms = 1000
 detect_language = false
@@ -130,14 +353,24 @@ transport = "tcp"
         let config: Config = toml::from_str(toml_str).unwrap();
         assert_eq!(config.audio.sample_rate, 48000);
         assert_eq!(config.audio.channels, 2);
+        assert_eq!(config.audio.channel_policy, ChannelPolicy::Select(1));
+        assert_eq!(config.audio.capture_format(), AudioFormat::new(48000, 2, AudioSampleFormat::F32));
         assert_eq!(config.vad.threshold, 0.7);
+        assert_eq!(config.vad.backend, VadBackendKind::Silero);
+        assert_eq!(config.vad.model_path.as_deref(), Some("models/silero_vad.onnx"));
         assert_eq!(config.whisper.model_size, "small");
-        assert_eq!(config.whisper.threads, 8);
+        assert_eq!(config.whisper.threads, Some(8));
         assert!(!config.whisper.enable_gpu);
+        assert_eq!(config.whisper.initial_prompt.as_deref(), Some("Meeting notes."));
+        assert_eq!(config.whisper.vocabulary, vec!["PipeWire", "Kubernetes"]);
         assert_eq!(config.whisper.memory.idle_timeout_secs, 600);
+        let decoding = config.whisper.decoding.resolve();
+        assert_eq!(decoding.beam_size, 3);
+        assert!(decoding.suppress_non_speech);
         assert_eq!(config.transcription.interval_ms, 1000);
         assert!(!config.transcription.detect_language);
         assert_eq!(config.mcp.name, "test-server");
         assert_eq!(config.mcp.transport, "tcp");
+        assert_eq!(config.mcp.bind_address, "127.0.0.1:8765");
     }
 }
@@ -0,0 +1,227 @@
+//! Caption layout
+//!
+//! Whisper segments are often too long to show as a single subtitle, so they
+//! are split into cues at word boundaries. Word timings come from the
+//! segment's tokens when available; otherwise the segment's time span is
+//! shared out in proportion to word length.
+
+use super::ExportOptions;
+use crate::whisper::Segment;
+
+/// One caption: a time span and its wrapped text lines
+#[derive(Debug, Clone, PartialEq)]
+pub struct Cue {
+    /// Start timestamp in milliseconds
+    pub start_ms: i64,
+    /// End timestamp in milliseconds
+    pub end_ms: i64,
+    /// Text lines, each at most `max_line_chars` long unless a single word is longer
+    pub lines: Vec<String>,
+}
+
+#[derive(Debug, Clone)]
+struct Word {
+    text: String,
+    start_ms: i64,
+    end_ms: i64,
+}
+
+/// Split `segments` into captions that respect `options`
+///
+/// Cues never span two segments, and empty segments produce no cues.
+pub fn build_cues(segments: &[Segment], options: &ExportOptions) -> Vec<Cue> {
+    let max_line_chars = options.max_line_chars.max(1);
+    let max_lines = options.max_lines.max(1);
+    let max_caption_ms = options.max_caption_ms as i64;
+
+    let mut cues = Vec::new();
+    for segment in segments {
+        let mut current: Vec<Word> = Vec::new();
+        for word in words(segment) {
+            if let Some(first) = current.first() {
+                let text = join(current.iter().chain(std::iter::once(&word)));
+                let too_long = wrap(&text, max_line_chars).len() > max_lines;
+                let too_slow = word.end_ms - first.start_ms > max_caption_ms;
+                if too_long || too_slow {
+                    cues.push(cue(&current, max_line_chars));
+                    current.clear();
+                }
+            }
+            current.push(word);
+        }
+        if !current.is_empty() {
+            cues.push(cue(&current, max_line_chars));
+        }
+    }
+    cues
+}
+
+/// Greedily wrap `text` into lines of at most `max_chars` characters
+///
+/// A word longer than `max_chars` gets a line of its own.
+pub fn wrap(text: &str, max_chars: usize) -> Vec<String> {
+    let mut lines: Vec<String> = Vec::new();
+    let mut line = String::new();
+    for word in text.split_whitespace() {
+        if !line.is_empty() && line.chars().count() + 1 + word.chars().count() > max_chars {
+            lines.push(std::mem::take(&mut line));
+        }
+        if !line.is_empty() {
+            line.push(' ');
+        }
+        line.push_str(word);
+    }
+    if !line.is_empty() {
+        lines.push(line);
+    }
+    lines
+}
+
+fn cue(words: &[Word], max_line_chars: usize) -> Cue {
+    let start_ms = words.first().map_or(0, |w| w.start_ms);
+    let end_ms = words.last().map_or(start_ms, |w| w.end_ms).max(start_ms);
+    Cue {
+        start_ms,
+        end_ms,
+        lines: wrap(&join(words.iter()), max_line_chars),
+    }
+}
+
+fn join<'a>(words: impl Iterator<Item = &'a Word>) -> String {
+    words.map(|w| w.text.as_str()).collect::<Vec<_>>().join(" ")
+}
+
+fn words(segment: &Segment) -> Vec<Word> {
+    let clamp = |ms: i64| ms.clamp(segment.start_ms, segment.end_ms.max(segment.start_ms));
+
+    let from_tokens = token_words(segment);
+    if !from_tokens.is_empty() {
+        return from_tokens
+            .into_iter()
+            .map(|w| Word {
+                start_ms: clamp(w.start_ms),
+                end_ms: clamp(w.end_ms.max(w.start_ms)),
+                ..w
+            })
+            .collect();
+    }
+
+    // No token timings: spread the segment's span over its words by length
+    let texts: Vec<&str> = segment.text.split_whitespace().collect();
+    let total_chars: usize = texts.iter().map(|t| t.chars().count()).sum();
+    let span = (segment.end_ms - segment.start_ms).max(0);
+    let mut elapsed_chars = 0;
+    texts
+        .into_iter()
+        .map(|text| {
+            let start_ms = segment.start_ms + span * elapsed_chars as i64 / total_chars.max(1) as i64;
+            elapsed_chars += text.chars().count();
+            let end_ms = segment.start_ms + span * elapsed_chars as i64 / total_chars.max(1) as i64;
+            Word {
+                text: text.to_string(),
+                start_ms,
+                end_ms,
+            }
+        })
+        .collect()
+}
+
+// Tokens that start with whitespace begin a new word; others (punctuation,
+// word pieces) extend the previous one.
+fn token_words(segment: &Segment) -> Vec<Word> {
+    let mut words: Vec<Word> = Vec::new();
+    for token in &segment.tokens {
+        let piece = token.text.trim();
+        if piece.is_empty() {
+            continue;
+        }
+        let starts_word = token.text.starts_with(char::is_whitespace);
+        match words.last_mut() {
+            Some(word) if !starts_word => {
+                word.text.push_str(piece);
+                word.end_ms = token.end_ms;
+            }
+            _ => words.push(Word {
+                text: piece.to_string(),
+                start_ms: token.start_ms,
+                end_ms: token.end_ms,
+            }),
+        }
+    }
+    words
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::whisper::Token;
+
+    fn segment(start_ms: i64, end_ms: i64, text: &str) -> Segment {
+        Segment {
+            start_ms,
+            end_ms,
+            text: text.to_string(),
+            no_speech_probability: 0.0,
+            tokens: Vec::new(),
+        }
+    }
+
+    fn token(text: &str, start_ms: i64, end_ms: i64) -> Token {
+        Token {
+            id: 0,
+            text: text.to_string(),
+            start_ms,
+            end_ms,
+            probability: 1.0,
+        }
+    }
+
+    #[test]
+    fn test_wrap() {
+        assert_eq!(wrap("the quick brown fox", 9), vec!["the quick", "brown fox"]);
+        assert_eq!(wrap("supercalifragilistic is long", 5), vec!["supercalifragilistic", "is", "long"]);
+        assert!(wrap("   ", 10).is_empty());
+    }
+
+    #[test]
+    fn test_short_segment_is_one_cue() {
+        let cues = build_cues(&[segment(1000, 2500, "Hello world")], &ExportOptions::default());
+        assert_eq!(cues, vec![Cue { start_ms: 1000, end_ms: 2500, lines: vec!["Hello world".to_string()] }]);
+    }
+
+    #[test]
+    fn test_long_segment_splits_on_line_limit() {
+        let options = ExportOptions { max_line_chars: 10, max_lines: 1, ..Default::default() };
+        let cues = build_cues(&[segment(0, 4000, "aaaa bbbb cccc dddd")], &options);
+        assert_eq!(cues.len(), 2);
+        assert_eq!(cues[0].lines, vec!["aaaa bbbb"]);
+        assert_eq!(cues[1].lines, vec!["cccc dddd"]);
+        assert_eq!(cues[0].start_ms, 0);
+        assert_eq!(cues[0].end_ms, 2000);
+        assert_eq!(cues[1].end_ms, 4000);
+    }
+
+    #[test]
+    fn test_long_segment_splits_on_duration() {
+        let options = ExportOptions { max_caption_ms: 1500, ..Default::default() };
+        let mut seg = segment(0, 3000, "one two, three");
+        seg.tokens = vec![
+            token(" one", 0, 1000),
+            token(" two", 1000, 1800),
+            token(",", 1800, 1900),
+            token(" three", 2000, 3000),
+        ];
+        let cues = build_cues(&[seg], &options);
+        let texts: Vec<_> = cues.iter().map(|c| c.lines.join(" ")).collect();
+        assert_eq!(texts, vec!["one", "two,", "three"]);
+        assert_eq!(cues[1].start_ms, 1000);
+        assert_eq!(cues[1].end_ms, 1900);
+    }
+
+    #[test]
+    fn test_empty_segments_are_skipped() {
+        let cues = build_cues(&[segment(0, 1000, ""), segment(1000, 2000, "hi")], &ExportOptions::default());
+        assert_eq!(cues.len(), 1);
+        assert_eq!(cues[0].start_ms, 1000);
+    }
+}
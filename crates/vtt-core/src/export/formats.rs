@@ -0,0 +1,166 @@
+//! Writers for each export format
+
+use super::{Cue, ExportResult};
+use crate::whisper::Segment;
+use serde::Serialize;
+use std::fmt::Write;
+
+/// Segment texts joined with spaces
+pub fn text(segments: &[Segment]) -> String {
+    let mut out = segments
+        .iter()
+        .map(|s| s.text.trim())
+        .filter(|t| !t.is_empty())
+        .collect::<Vec<_>>()
+        .join(" ");
+    out.push('\n');
+    out
+}
+
+/// SubRip: numbered cues with `HH:MM:SS,mmm` times
+pub fn srt(cues: &[Cue]) -> String {
+    let mut out = String::new();
+    for (i, cue) in cues.iter().enumerate() {
+        let _ = writeln!(
+            out,
+            "{}\n{} --> {}\n{}\n",
+            i + 1,
+            timestamp(cue.start_ms, ','),
+            timestamp(cue.end_ms, ','),
+            cue.lines.join("\n")
+        );
+    }
+    out
+}
+
+/// WebVTT: `WEBVTT` header and cues with `HH:MM:SS.mmm` times
+pub fn webvtt(cues: &[Cue]) -> String {
+    let mut out = String::from("WEBVTT\n\n");
+    for cue in cues {
+        let _ = writeln!(
+            out,
+            "{} --> {}\n{}\n",
+            timestamp(cue.start_ms, '.'),
+            timestamp(cue.end_ms, '.'),
+            cue.lines.join("\n")
+        );
+    }
+    out
+}
+
+#[derive(Serialize)]
+struct JsonTranscript<'a> {
+    text: String,
+    start_ms: i64,
+    end_ms: i64,
+    segments: &'a [Segment],
+}
+
+/// Full transcript with segments and tokens, pretty-printed
+pub fn json(segments: &[Segment]) -> ExportResult<String> {
+    let doc = JsonTranscript {
+        text: text(segments).trim_end().to_string(),
+        start_ms: segments.first().map_or(0, |s| s.start_ms),
+        end_ms: segments.last().map_or(0, |s| s.end_ms),
+        segments,
+    };
+    let mut out = serde_json::to_string_pretty(&doc)?;
+    out.push('\n');
+    Ok(out)
+}
+
+/// Markdown with one timestamped paragraph per segment
+pub fn markdown(segments: &[Segment]) -> String {
+    let mut out = String::from("# Transcript\n");
+    for segment in segments.iter().filter(|s| !s.text.trim().is_empty()) {
+        let _ = write!(
+            out,
+            "\n**[{}]** {}\n",
+            clock(segment.start_ms),
+            segment.text.trim()
+        );
+    }
+    out
+}
+
+/// Format milliseconds as `HH:MM:SS<sep>mmm`
+pub fn timestamp(ms: i64, sep: char) -> String {
+    format!("{}{}{:03}", clock(ms), sep, ms.max(0) % 1000)
+}
+
+/// Format milliseconds as `HH:MM:SS`
+pub fn clock(ms: i64) -> String {
+    let secs = ms.max(0) / 1000;
+    format!("{:02}:{:02}:{:02}", secs / 3600, secs / 60 % 60, secs % 60)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cues() -> Vec<Cue> {
+        vec![
+            Cue { start_ms: 0, end_ms: 1500, lines: vec!["Hello".to_string()] },
+            Cue {
+                start_ms: 3_723_004,
+                end_ms: 3_725_000,
+                lines: vec!["two".to_string(), "lines".to_string()],
+            },
+        ]
+    }
+
+    fn segments() -> Vec<Segment> {
+        vec![
+            Segment {
+                start_ms: 0,
+                end_ms: 1500,
+                text: "Hello".to_string(),
+                no_speech_probability: 0.0,
+                tokens: Vec::new(),
+            },
+            Segment {
+                start_ms: 61_000,
+                end_ms: 62_000,
+                text: " world ".to_string(),
+                no_speech_probability: 0.1,
+                tokens: Vec::new(),
+            },
+        ]
+    }
+
+    #[test]
+    fn test_timestamp() {
+        assert_eq!(timestamp(0, ','), "00:00:00,000");
+        assert_eq!(timestamp(3_723_004, '.'), "01:02:03.004");
+        assert_eq!(timestamp(-5, ','), "00:00:00,000");
+    }
+
+    #[test]
+    fn test_srt() {
+        assert_eq!(
+            srt(&cues()),
+            "1\n00:00:00,000 --> 00:00:01,500\nHello\n\n2\n01:02:03,004 --> 01:02:05,000\ntwo\nlines\n\n"
+        );
+    }
+
+    #[test]
+    fn test_webvtt() {
+        let out = webvtt(&cues());
+        assert!(out.starts_with("WEBVTT\n\n00:00:00.000 --> 00:00:01.500\nHello\n\n"));
+        assert!(out.ends_with("01:02:03.004 --> 01:02:05.000\ntwo\nlines\n\n"));
+    }
+
+    #[test]
+    fn test_text_and_markdown() {
+        assert_eq!(text(&segments()), "Hello world\n");
+        assert_eq!(markdown(&segments()), "# Transcript\n\n**[00:00:00]** Hello\n\n**[00:01:01]** world\n");
+    }
+
+    #[test]
+    fn test_json() {
+        let value: serde_json::Value = serde_json::from_str(&json(&segments()).unwrap()).unwrap();
+        assert_eq!(value["text"], "Hello world");
+        assert_eq!(value["end_ms"], 62_000);
+        assert_eq!(value["segments"][1]["no_speech_probability"].as_f64().unwrap() as f32, 0.1);
+    }
+}
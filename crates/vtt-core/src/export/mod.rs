@@ -0,0 +1,147 @@
+//! Transcript export
+//!
+//! Turns segment-level transcription data into subtitle files (SRT, WebVTT)
+//! or documents (JSON, timestamped Markdown). Subtitle formats re-flow the
+//! segments into captions that respect [`ExportOptions`].
+
+pub mod cues;
+pub mod formats;
+
+pub use cues::{build_cues, Cue};
+
+use crate::whisper::Segment;
+use std::fmt;
+use std::str::FromStr;
+use thiserror::Error;
+
+/// Errors that can occur while exporting a transcript
+#[derive(Error, Debug)]
+pub enum ExportError {
+    /// The requested format name is not known
+    #[error("Unknown export format '{0}' (expected txt, srt, vtt, json or md)")]
+    UnknownFormat(String),
+
+    /// The transcript could not be serialized
+    #[error("Failed to serialize transcript: {0}")]
+    Serialize(#[from] serde_json::Error),
+}
+
+/// Result type for export operations
+pub type ExportResult<T> = Result<T, ExportError>;
+
+/// Output formats for transcripts
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExportFormat {
+    /// Plain text, no timing
+    Text,
+    /// SubRip subtitles
+    Srt,
+    /// WebVTT subtitles
+    WebVtt,
+    /// Segments and tokens as JSON
+    Json,
+    /// Markdown with a timestamp per segment
+    Markdown,
+}
+
+impl ExportFormat {
+    /// Conventional file extension, without the dot
+    pub fn extension(&self) -> &'static str {
+        match self {
+            Self::Text => "txt",
+            Self::Srt => "srt",
+            Self::WebVtt => "vtt",
+            Self::Json => "json",
+            Self::Markdown => "md",
+        }
+    }
+}
+
+impl FromStr for ExportFormat {
+    type Err = ExportError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "txt" | "text" => Ok(Self::Text),
+            "srt" => Ok(Self::Srt),
+            "vtt" | "webvtt" => Ok(Self::WebVtt),
+            "json" => Ok(Self::Json),
+            "md" | "markdown" => Ok(Self::Markdown),
+            _ => Err(ExportError::UnknownFormat(s.to_string())),
+        }
+    }
+}
+
+impl fmt::Display for ExportFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.extension())
+    }
+}
+
+/// Caption layout limits for subtitle formats
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ExportOptions {
+    /// Maximum characters per caption line
+    /// Default: 42 - the usual broadcast subtitle limit
+    pub max_line_chars: usize,
+
+    /// Maximum lines per caption
+    /// Default: 2
+    pub max_lines: usize,
+
+    /// Maximum time a single caption stays on screen, in milliseconds
+    /// Default: 7000
+    pub max_caption_ms: u64,
+}
+
+impl Default for ExportOptions {
+    fn default() -> Self {
+        Self {
+            max_line_chars: 42,
+            max_lines: 2,
+            max_caption_ms: 7000,
+        }
+    }
+}
+
+/// Render `segments` in `format`
+///
+/// # Errors
+///
+/// Returns an error if JSON serialization fails.
+pub fn export(segments: &[Segment], format: ExportFormat, options: &ExportOptions) -> ExportResult<String> {
+    Ok(match format {
+        ExportFormat::Text => formats::text(segments),
+        ExportFormat::Srt => formats::srt(&build_cues(segments, options)),
+        ExportFormat::WebVtt => formats::webvtt(&build_cues(segments, options)),
+        ExportFormat::Json => formats::json(segments)?,
+        ExportFormat::Markdown => formats::markdown(segments),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_format_from_str() {
+        assert_eq!("SRT".parse::<ExportFormat>().unwrap(), ExportFormat::Srt);
+        assert_eq!("webvtt".parse::<ExportFormat>().unwrap(), ExportFormat::WebVtt);
+        assert_eq!("markdown".parse::<ExportFormat>().unwrap(), ExportFormat::Markdown);
+        assert!(matches!("docx".parse::<ExportFormat>(), Err(ExportError::UnknownFormat(_))));
+    }
+
+    #[test]
+    fn test_format_extension() {
+        assert_eq!(ExportFormat::WebVtt.extension(), "vtt");
+        assert_eq!(ExportFormat::Markdown.to_string(), "md");
+    }
+
+    #[test]
+    fn test_export_options_default() {
+        let options = ExportOptions::default();
+        assert_eq!(options.max_line_chars, 42);
+        assert_eq!(options.max_lines, 2);
+        assert_eq!(options.max_caption_ms, 7000);
+    }
+}
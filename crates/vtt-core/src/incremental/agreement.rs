@@ -0,0 +1,260 @@
+//! LocalAgreement stabilization for streaming transcription
+//!
+//! Re-transcribing a growing buffer gives a new hypothesis every tick, and
+//! the last few words of each one are often wrong. [`LocalAgreement`] only
+//! commits the word prefix two consecutive hypotheses agree on; the rest
+//! stays tentative and may still change. Committed words never change, so
+//! captions built from them don't flicker.
+
+use crate::whisper::Transcription;
+
+/// Committed words this close to the start of a new hypothesis are checked
+/// for a repeat
+const OVERLAP_WINDOW_MS: i64 = 1000;
+/// Longest run of repeated words removed from the start of a hypothesis
+const MAX_OVERLAP_WORDS: usize = 5;
+
+/// A hypothesis word, timed in milliseconds from the start of the stream
+#[derive(Debug, Clone, PartialEq)]
+pub struct TimedWord {
+    /// Word text without surrounding whitespace
+    pub text: String,
+    /// Start of the word
+    pub start_ms: i64,
+    /// End of the word
+    pub end_ms: i64,
+}
+
+impl TimedWord {
+    /// Words of a transcription whose audio began `offset_ms` into the stream
+    ///
+    /// Words are rebuilt from the token timings: a token starting with a
+    /// space begins a new word. Segments without tokens are split on
+    /// whitespace, spreading the segment's time evenly.
+    pub fn from_transcription(transcription: &Transcription, offset_ms: i64) -> Vec<TimedWord> {
+        let mut words: Vec<TimedWord> = Vec::new();
+        for segment in &transcription.segments {
+            if segment.tokens.is_empty() {
+                let texts: Vec<&str> = segment.text.split_whitespace().collect();
+                let span = (segment.end_ms - segment.start_ms).max(0) / texts.len().max(1) as i64;
+                words.extend(texts.iter().enumerate().map(|(i, text)| TimedWord {
+                    text: text.to_string(),
+                    start_ms: offset_ms + segment.start_ms + span * i as i64,
+                    end_ms: offset_ms + segment.start_ms + span * (i as i64 + 1),
+                }));
+                continue;
+            }
+
+            let mut current: Option<TimedWord> = None;
+            for token in &segment.tokens {
+                let text = token.text.trim();
+                if text.is_empty() {
+                    continue;
+                }
+                match current.as_mut() {
+                    Some(word) if !token.text.starts_with(char::is_whitespace) => {
+                        word.text.push_str(text);
+                        word.end_ms = offset_ms + token.end_ms;
+                    }
+                    _ => {
+                        words.extend(current.take());
+                        current = Some(TimedWord {
+                            text: text.to_string(),
+                            start_ms: offset_ms + token.start_ms,
+                            end_ms: offset_ms + token.end_ms,
+                        });
+                    }
+                }
+            }
+            words.extend(current);
+        }
+        words
+    }
+
+    /// Whether two hypotheses contain the same word, ignoring case and
+    /// surrounding punctuation
+    fn same_word(&self, other: &TimedWord) -> bool {
+        normalize(&self.text) == normalize(&other.text)
+    }
+}
+
+fn normalize(word: &str) -> String {
+    word.trim_matches(|c: char| !c.is_alphanumeric()).to_lowercase()
+}
+
+fn join(words: &[TimedWord]) -> String {
+    words.iter().map(|w| w.text.as_str()).collect::<Vec<_>>().join(" ")
+}
+
+/// Commits the words consecutive hypotheses agree on
+#[derive(Debug, Clone, Default)]
+pub struct LocalAgreement {
+    committed: Vec<TimedWord>,
+    tentative: Vec<TimedWord>,
+}
+
+impl LocalAgreement {
+    /// Start with nothing committed
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Feed the next hypothesis and return the words it commits
+    ///
+    /// Words ending before the committed text are dropped, as is a repeat
+    /// of the last committed words at the start of the hypothesis. What is
+    /// left is compared with the previous hypothesis: their common prefix
+    /// is committed and the remainder becomes the new tentative text.
+    pub fn insert(&mut self, hypothesis: Vec<TimedWord>) -> &[TimedWord] {
+        let committed_end = self.committed_end_ms();
+        let mut words: Vec<TimedWord> = hypothesis.into_iter().filter(|w| w.end_ms > committed_end).collect();
+
+        if words.first().is_some_and(|w| w.start_ms - committed_end < OVERLAP_WINDOW_MS) {
+            let longest = MAX_OVERLAP_WORDS.min(self.committed.len()).min(words.len());
+            if let Some(n) = (1..=longest).rev().find(|&n| {
+                let tail = &self.committed[self.committed.len() - n..];
+                tail.iter().zip(&words[..n]).all(|(a, b)| a.same_word(b))
+            }) {
+                words.drain(..n);
+            }
+        }
+
+        let agreed = self.tentative.iter().zip(&words).take_while(|(a, b)| a.same_word(b)).count();
+        let start = self.committed.len();
+        self.committed.extend(words.drain(..agreed));
+        self.tentative = words;
+        &self.committed[start..]
+    }
+
+    /// Commit tentative words that end by `ms`, e.g. because their audio
+    /// has left the buffer and no later hypothesis can confirm them
+    pub fn commit_until(&mut self, ms: i64) -> &[TimedWord] {
+        let count = self.tentative.iter().take_while(|w| w.end_ms <= ms).count();
+        let start = self.committed.len();
+        self.committed.extend(self.tentative.drain(..count));
+        &self.committed[start..]
+    }
+
+    /// Commit everything still tentative, at the end of the stream
+    pub fn finish(&mut self) -> &[TimedWord] {
+        let start = self.committed.len();
+        self.committed.append(&mut self.tentative);
+        &self.committed[start..]
+    }
+
+    /// End of the last committed word, or 0 if nothing is committed
+    pub fn committed_end_ms(&self) -> i64 {
+        self.committed.last().map_or(0, |w| w.end_ms)
+    }
+
+    /// Text that won't change any more
+    pub fn committed_text(&self) -> String {
+        join(&self.committed)
+    }
+
+    /// Text after the committed part that later hypotheses may still revise
+    pub fn tentative_text(&self) -> String {
+        join(&self.tentative)
+    }
+
+    /// Committed and tentative text together
+    pub fn text(&self) -> String {
+        join(&[self.committed.as_slice(), self.tentative.as_slice()].concat())
+    }
+
+    /// Forget everything, e.g. at the start of a new utterance
+    pub fn reset(&mut self) {
+        self.committed.clear();
+        self.tentative.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::whisper::{Segment, Token};
+
+    /// Words 500 ms apart, starting at `start_ms`
+    fn words(text: &str, start_ms: i64) -> Vec<TimedWord> {
+        text.split_whitespace()
+            .enumerate()
+            .map(|(i, w)| TimedWord {
+                text: w.to_string(),
+                start_ms: start_ms + 500 * i as i64,
+                end_ms: start_ms + 500 * (i as i64 + 1),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_commits_agreed_prefix() {
+        let mut agreement = LocalAgreement::new();
+        assert!(agreement.insert(words("the quick brown", 0)).is_empty());
+        assert_eq!(agreement.tentative_text(), "the quick brown");
+
+        let committed = agreement.insert(words("the quick brow fox", 0));
+        assert_eq!(join(committed), "the quick");
+        assert_eq!(agreement.committed_text(), "the quick");
+        assert_eq!(agreement.tentative_text(), "brow fox");
+        assert_eq!(agreement.committed_end_ms(), 1000);
+
+        // Already committed words are skipped, whatever their timing jitter
+        let mut next = words("quick brown fox jumps", 500);
+        next[0].end_ms = 1100;
+        assert_eq!(join(agreement.insert(next)), "");
+        assert_eq!(agreement.tentative_text(), "brown fox jumps");
+
+        assert_eq!(join(agreement.insert(words("Brown fox, jumped", 1000))), "Brown fox,");
+        assert_eq!(agreement.text(), "the quick Brown fox, jumped");
+    }
+
+    #[test]
+    fn test_commit_until_and_finish() {
+        let mut agreement = LocalAgreement::new();
+        agreement.insert(words("one two three", 0));
+        assert_eq!(join(agreement.commit_until(1000)), "one two");
+        assert_eq!(agreement.tentative_text(), "three");
+
+        assert_eq!(join(agreement.finish()), "three");
+        assert_eq!(agreement.committed_text(), "one two three");
+        assert_eq!(agreement.tentative_text(), "");
+
+        agreement.reset();
+        assert_eq!(agreement.text(), "");
+        assert_eq!(agreement.committed_end_ms(), 0);
+    }
+
+    #[test]
+    fn test_words_from_tokens() {
+        let token = |text: &str, start_ms, end_ms| Token {
+            id: 0,
+            text: text.to_string(),
+            start_ms,
+            end_ms,
+            probability: 0.9,
+        };
+        let segment = |text: &str, start_ms, end_ms, tokens| Segment {
+            start_ms,
+            end_ms,
+            text: text.to_string(),
+            no_speech_probability: 0.0,
+            tokens,
+        };
+        let tx = Transcription::from_segments(vec![
+            segment(
+                "Héllo, world",
+                0,
+                1000,
+                vec![token(" H", 0, 100), token("éllo,", 100, 400), token(" world", 500, 1000)],
+            ),
+            segment("no tokens here", 1000, 1600, Vec::new()),
+        ]);
+
+        let words = TimedWord::from_transcription(&tx, 2000);
+        let texts: Vec<&str> = words.iter().map(|w| w.text.as_str()).collect();
+        assert_eq!(texts, ["Héllo,", "world", "no", "tokens", "here"]);
+        assert_eq!((words[0].start_ms, words[0].end_ms), (2000, 2400));
+        assert_eq!((words[3].start_ms, words[3].end_ms), (3200, 3400));
+        assert!(words[0].same_word(&TimedWord { text: "héllo".to_string(), start_ms: 0, end_ms: 0 }));
+    }
+}
@@ -1,5 +1,7 @@
-//! Incremental transcription with duplicate suppression
+//! Incremental transcription with LocalAgreement stabilization
 
+pub mod agreement;
 pub mod transcriber;
 
+pub use agreement::{LocalAgreement, TimedWord};
 pub use transcriber::{IncrementalTranscriber, TranscriberConfig, PartialResult};
@@ -3,6 +3,7 @@
 use std::sync::Arc;
 use tokio::sync::Mutex;
 
+use super::agreement::{LocalAgreement, TimedWord};
 use crate::whisper::WhisperContext;
 use crate::window::{SlidingWindow, WindowConfig};
 
@@ -13,6 +14,9 @@ pub struct TranscriberConfig {
     pub min_text_length: usize,
     pub window_duration_secs: f32,
     pub sample_rate: u32,
+    /// Prompt each window with the text committed before it, so context
+    /// and spelling carry across windows
+    pub carry_prompt: bool,
 }
 
 impl Default for TranscriberConfig {
@@ -20,8 +24,11 @@ impl Default for TranscriberConfig {
         Self {
             transcription_interval_ms: 500,
             min_text_length: 3,
-            window_duration_secs: 3.0,
+            // Committed audio is trimmed, so the window only has to hold
+            // speech no two hypotheses have agreed on yet
+            window_duration_secs: 10.0,
             sample_rate: 16000,
+            carry_prompt: true,
         }
     }
 }
@@ -29,16 +36,23 @@ impl Default for TranscriberConfig {
 /// Partial transcription result
 #[derive(Debug, Clone)]
 pub struct PartialResult {
+    /// Committed and tentative text together
     pub text: String,
+    /// Text two hypotheses agreed on; it won't change any more
+    pub committed_text: String,
+    /// Text after the committed part that may still be revised
+    pub tentative_text: String,
     pub is_final: bool,
     pub timestamp: chrono::DateTime<chrono::Utc>,
     pub sample_count: usize,
 }
 
 impl PartialResult {
-    fn new(text: String, sample_count: usize, is_final: bool) -> Self {
+    fn new(agreement: &LocalAgreement, sample_count: usize, is_final: bool) -> Self {
         Self {
-            text,
+            text: agreement.text(),
+            committed_text: agreement.committed_text(),
+            tentative_text: agreement.tentative_text(),
             is_final,
             timestamp: chrono::Utc::now(),
             sample_count,
@@ -48,17 +62,21 @@ impl PartialResult {
 
 struct TranscriberState {
     window: SlidingWindow,
+    agreement: LocalAgreement,
+    /// Samples pushed since the start or the last `clear`
+    total_samples: usize,
     last_text: String,
 }
 
 pub struct IncrementalTranscriber {
-    whisper: WhisperContext,
+    whisper: Arc<WhisperContext>,
     state: Arc<Mutex<TranscriberState>>,
     config: TranscriberConfig,
 }
 
 impl IncrementalTranscriber {
-    pub fn new(config: TranscriberConfig, whisper: WhisperContext) -> Self {
+    /// Create a transcriber over an owned or shared Whisper context
+    pub fn new(config: TranscriberConfig, whisper: impl Into<Arc<WhisperContext>>) -> Self {
         let window_config = WindowConfig {
             duration_secs: config.window_duration_secs,
             sample_rate: config.sample_rate,
@@ -66,11 +84,13 @@ impl IncrementalTranscriber {
         
         let state = TranscriberState {
             window: SlidingWindow::with_config(window_config),
+            agreement: LocalAgreement::new(),
+            total_samples: 0,
             last_text: String::new(),
         };
         
         Self {
-            whisper,
+            whisper: whisper.into(),
             state: Arc::new(Mutex::new(state)),
             config,
         }
@@ -81,46 +101,84 @@ impl IncrementalTranscriber {
     }
     
     pub async fn push_audio(&self, samples: &[f32]) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
-        let state = self.state.lock().await;
+        let mut state = self.state.lock().await;
+        state.total_samples += samples.len();
         state.window.push(samples).await?;
         Ok(())
     }
     
+    /// Transcribe the uncommitted audio and update the committed text
+    ///
+    /// Each run is one hypothesis for [`LocalAgreement`]: the words it
+    /// shares with the previous run are committed and their audio is
+    /// trimmed from the window. Returns `None` if the text didn't change.
     pub async fn transcribe_current(&self) -> Result<Option<PartialResult>, Box<dyn std::error::Error + Send + Sync>> {
-        let window_data = {
+        let (window_data, offset_ms, previous_text) = {
             let state = self.state.lock().await;
-            state.window.get_all().await
+            let window_data = state.window.get_all().await;
+            let offset = state.total_samples - window_data.len();
+            let previous_text = self.config.carry_prompt.then(|| state.agreement.committed_text());
+            (window_data, self.samples_to_ms(offset), previous_text)
         };
         
-        if window_data.len() < 1600 {
+        // Need at least 100ms of audio before it's worth running the model
+        if window_data.len() < (self.config.sample_rate / 10) as usize {
             return Ok(None);
         }
         
-        match self.whisper.transcribe(&window_data, 16000) {
-            Ok(transcription) => {
-                let text = transcription.text.trim().to_string();
-                
-                if text.len() < self.config.min_text_length {
-                    return Ok(None);
-                }
-                
-                let is_duplicate = {
-                    let mut state = self.state.lock().await;
-                    let duplicate = Self::is_duplicate(&state.last_text, &text, 0.8);
-                    if !duplicate {
-                        state.last_text = text.clone();
-                    }
-                    duplicate
-                };
-                
-                if is_duplicate {
-                    Ok(None)
-                } else {
-                    Ok(Some(PartialResult::new(text, window_data.len(), false)))
-                }
+        // Inference blocks for a while, keep it off the async worker threads
+        let whisper = match previous_text.filter(|text| !text.is_empty()) {
+            Some(text) => {
+                let config = self.whisper.config().clone().with_previous_text(Some(text));
+                Arc::new(self.whisper.with_config(config))
             }
-            Err(_) => Ok(None)
+            None => Arc::clone(&self.whisper),
+        };
+        let sample_rate = self.config.sample_rate;
+        let sample_count = window_data.len();
+        let outcome = tokio::task::spawn_blocking(move || whisper.transcribe(&window_data, sample_rate)).await?;
+        let transcription = match outcome {
+            Ok(transcription) => transcription,
+            Err(_) => return Ok(None),
+        };
+
+        let mut state = self.state.lock().await;
+        let state = &mut *state;
+
+        // Words whose audio has since slid out of the window can't be
+        // confirmed by another hypothesis, so they are committed as they are
+        let window_start = state.total_samples - state.window.len().await;
+        state.agreement.commit_until(self.samples_to_ms(window_start));
+        state.agreement.insert(TimedWord::from_transcription(&transcription, offset_ms));
+
+        let committed_end = self.ms_to_samples(state.agreement.committed_end_ms());
+        state.window.trim_front(committed_end.saturating_sub(window_start)).await;
+
+        let text = state.agreement.text();
+        if text.len() < self.config.min_text_length || text == state.last_text {
+            return Ok(None);
         }
+        state.last_text = text;
+        Ok(Some(PartialResult::new(&state.agreement, sample_count, false)))
+    }
+
+    /// Commit whatever is still tentative, at the end of the stream
+    pub async fn finish(&self) -> PartialResult {
+        let mut state = self.state.lock().await;
+        state.agreement.finish();
+        state.last_text = state.agreement.text();
+        let sample_count = state.window.len().await;
+        PartialResult::new(&state.agreement, sample_count, true)
+    }
+
+    /// Text that won't change any more
+    pub async fn committed_text(&self) -> String {
+        self.state.lock().await.agreement.committed_text()
+    }
+
+    /// Text after the committed part that may still be revised
+    pub async fn tentative_text(&self) -> String {
+        self.state.lock().await.agreement.tentative_text()
     }
     
     pub async fn buffer_duration_secs(&self) -> f32 {
@@ -131,31 +189,17 @@ impl IncrementalTranscriber {
     pub async fn clear(&self) {
         let mut state = self.state.lock().await;
         state.window.clear().await;
+        state.agreement.reset();
+        state.total_samples = 0;
         state.last_text.clear();
     }
-    
-    fn is_duplicate(prev: &str, curr: &str, threshold: f32) -> bool {
-        if prev.is_empty() || curr.is_empty() {
-            return false;
-        }
-        
-        if curr.len() < prev.len() && prev.contains(curr) {
-            return true;
-        }
-        
-        let overlap_len = prev.len().min(curr.len());
-        let min_overlap = (prev.len() as f32 * threshold).ceil() as usize;
-        
-        if overlap_len >= min_overlap {
-            let prev_suffix = &prev[prev.len().saturating_sub(overlap_len)..];
-            let curr_prefix = &curr[..overlap_len.min(curr.len())];
-            
-            if prev_suffix == curr_prefix {
-                return true;
-            }
-        }
-        
-        false
+
+    fn samples_to_ms(&self, samples: usize) -> i64 {
+        (samples as u64 * 1000 / self.config.sample_rate as u64) as i64
+    }
+
+    fn ms_to_samples(&self, ms: i64) -> usize {
+        (ms.max(0) as u64 * self.config.sample_rate as u64 / 1000) as usize
     }
 }
 
@@ -163,10 +207,21 @@ impl IncrementalTranscriber {
 mod tests {
     use super::*;
     
-    #[tokio::test]
-    async fn test_duplicate_detection() {
-        assert!(IncrementalTranscriber::is_duplicate("hello world", "hello", 0.8));
-        assert!(IncrementalTranscriber::is_duplicate("hello world test", "world test", 0.7));
-        assert!(!IncrementalTranscriber::is_duplicate("hello", "world", 0.8));
+    #[test]
+    fn test_partial_result_splits_text() {
+        let mut agreement = LocalAgreement::new();
+        let words = |text: &str| -> Vec<TimedWord> {
+            text.split_whitespace()
+                .enumerate()
+                .map(|(i, w)| TimedWord { text: w.to_string(), start_ms: 300 * i as i64, end_ms: 300 * (i as i64 + 1) })
+                .collect()
+        };
+        agreement.insert(words("héllo wörld again"));
+        agreement.insert(words("héllo wörld agai"));
+
+        let partial = PartialResult::new(&agreement, 16000, false);
+        assert_eq!(partial.committed_text, "héllo wörld");
+        assert_eq!(partial.tentative_text, "agai");
+        assert_eq!(partial.text, "héllo wörld agai");
     }
 }
@@ -4,6 +4,7 @@
 //! - Audio capture
 //! - VAD (Voice Activity Detection)
 //! - Whisper transcription
+//! - Transcript export (SRT, WebVTT, JSON, Markdown)
 //!
 //! # Example Usage
 //! ```
@@ -19,6 +20,7 @@ pub mod whisper;
 pub mod window;
 pub mod incremental;
 pub mod profile;
+pub mod export;
 
 pub use audio::{AudioFormat, AudioError, AudioResult};
 pub use vad::{VadDetector, VadConfig, VadResult};
@@ -26,6 +28,7 @@ pub use whisper::{WhisperContext, WhisperConfig, WhisperError};
 pub use window::{SlidingWindow, WindowConfig, WindowError};
 pub use incremental::{IncrementalTranscriber, TranscriberConfig, PartialResult};
 pub use profile::{Timer, ProfileData, Timing, TimingStats};
+pub use export::{export, ExportFormat, ExportOptions, ExportError};
 
 /// VTT-Core library version
 pub const VERSION: &str = env!("CARGO_PKG_VERSION");
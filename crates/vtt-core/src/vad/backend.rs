@@ -0,0 +1,115 @@
+//! Pluggable VAD backends
+//!
+//! Everything that consumes VAD results works against [`VadBackend`], so the
+//! energy detector and Silero are interchangeable.
+
+use super::{VadConfig, VadDetector, VadError, VadResult};
+use serde::{Deserialize, Serialize};
+
+/// Which detector to run
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum VadBackendKind {
+    /// RMS energy threshold; no model needed
+    #[default]
+    Energy,
+    /// Silero VAD from a local ONNX file
+    Silero,
+}
+
+impl VadBackendKind {
+    /// Name used in config files and status output
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Energy => "energy",
+            Self::Silero => "silero",
+        }
+    }
+}
+
+/// A voice activity detector
+///
+/// Frames are mono `f32` samples in [-1.0, 1.0]. Backends that need a fixed
+/// sample rate or frame size report it; callers must frame the audio to
+/// match.
+pub trait VadBackend: Send {
+    /// Which backend this is
+    fn kind(&self) -> VadBackendKind;
+
+    /// Sample rate frames must be in, if the backend requires one
+    fn sample_rate(&self) -> Option<u32>;
+
+    /// Samples per frame, if the backend requires a fixed size
+    fn frame_size(&self) -> Option<usize>;
+
+    /// Score a frame must reach to count as speech
+    fn threshold(&self) -> f32;
+
+    /// Score one frame without updating the speech/silence state
+    ///
+    /// Stateful models still advance their internal state.
+    fn speech_probability(&mut self, frame: &[f32]) -> Result<f32, VadError>;
+
+    /// Score one frame and return the debounced speech/silence state
+    fn process_frame(&mut self, frame: &[f32]) -> Result<VadResult, VadError>;
+
+    /// State after the last processed frame
+    fn current_state(&self) -> VadResult;
+
+    /// Forget all state, e.g. before a new recording
+    fn reset(&mut self);
+}
+
+/// Build the backend selected by `config.backend`
+///
+/// # Errors
+///
+/// Returns `Unavailable` if Silero is selected but the crate was built
+/// without the `silero` feature or no model path is set, and `Model` if the
+/// model fails to load.
+pub fn create_backend(config: &VadConfig) -> Result<Box<dyn VadBackend>, VadError> {
+    match config.backend {
+        VadBackendKind::Energy => Ok(Box::new(VadDetector::with_config(config.clone()))),
+        #[cfg(feature = "silero")]
+        VadBackendKind::Silero => Ok(Box::new(super::SileroVad::from_config(config)?)),
+        #[cfg(not(feature = "silero"))]
+        VadBackendKind::Silero => Err(VadError::Unavailable(
+            "Silero VAD needs vtt-core built with the `silero` feature".to_string(),
+        )),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_create_energy_backend() {
+        let mut backend = create_backend(&VadConfig::default()).unwrap();
+        assert_eq!(backend.kind(), VadBackendKind::Energy);
+        assert_eq!(backend.frame_size(), None);
+
+        for _ in 0..5 {
+            backend.process_frame(&[0.1; 480]).unwrap();
+        }
+        assert_eq!(backend.current_state(), VadResult::Speech);
+        assert!((backend.speech_probability(&[0.1; 480]).unwrap() - 0.1).abs() < 1e-6);
+
+        backend.reset();
+        assert_eq!(backend.current_state(), VadResult::Silence);
+    }
+
+    #[test]
+    fn test_silero_without_model_is_unavailable() {
+        let config = VadConfig { backend: VadBackendKind::Silero, ..Default::default() };
+        assert!(matches!(create_backend(&config), Err(VadError::Unavailable(_))));
+    }
+
+    #[test]
+    fn test_backend_kind_serde() {
+        let kind: VadBackendKind = serde_json::from_str("\"silero\"").unwrap();
+        assert_eq!(kind, VadBackendKind::Silero);
+        assert_eq!(serde_json::to_string(&VadBackendKind::Energy).unwrap(), "\"energy\"");
+        assert_eq!(VadBackendKind::Silero.as_str(), "silero");
+    }
+}
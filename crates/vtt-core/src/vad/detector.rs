@@ -1,38 +1,30 @@
 //! Voice Activity Detection using energy-based approach
 //!
-//! This is a simple but effective VAD based on signal energy.
-//! For better accuracy, consider integrating ML-based models like Silero VAD.
+//! This is a simple VAD based on signal energy. It needs no model, but
+//! loud noise triggers it and quiet speakers can fall under the threshold;
+//! the Silero backend is more accurate.
 
-use super::{VadConfig, VadError, VadResult};
+use super::backend::{VadBackend, VadBackendKind};
+use super::{Debounce, VadConfig, VadError, VadResult};
 
 /// Voice Activity Detector (energy-based)
 pub struct VadDetector {
     config: VadConfig,
-    speech_frame_count: usize,
-    silence_frame_count: usize,
-    current_state: VadResult,
+    debounce: Debounce,
     speech_segment_length: usize,
 }
 
 impl VadDetector {
     /// Create a new VAD detector
     pub fn new() -> Self {
-        Self {
-            config: VadConfig::default(),
-            speech_frame_count: 0,
-            silence_frame_count: 0,
-            current_state: VadResult::Silence,
-            speech_segment_length: 0,
-        }
+        Self::with_config(VadConfig::default())
     }
 
     /// Create a new VAD detector with custom config
     pub fn with_config(config: VadConfig) -> Self {
         Self {
+            debounce: Debounce::new(&config),
             config,
-            speech_frame_count: 0,
-            silence_frame_count: 0,
-            current_state: VadResult::Silence,
             speech_segment_length: 0,
         }
     }
@@ -64,38 +56,18 @@ impl VadDetector {
         // Calculate RMS energy of the frame
         let energy = calculate_rms_energy(audio);
 
-        // Determine if this frame has speech energy
-        let has_speech_energy = energy >= self.config.energy_threshold;
-
-        // State machine for speech detection
-        let new_state = if has_speech_energy {
-            self.speech_frame_count += 1;
-            self.silence_frame_count = 0;
-
-            if self.speech_frame_count >= self.config.speech_frames_threshold {
-                VadResult::Speech
-            } else {
-                self.current_state // Still in debounce period
-            }
-        } else {
-            self.silence_frame_count += 1;
-
-            if self.silence_frame_count >= self.config.silence_frames_threshold {
-                self.speech_frame_count = 0;
-                self.speech_segment_length = 0;
-                VadResult::Silence
-            } else {
-                self.current_state // Still in hangover period
-            }
-        };
+        // Debounced on both edges: speech needs a run of loud frames and
+        // silence a run of quiet ones
+        let new_state = self.debounce.update(energy >= self.config.energy_threshold);
 
         // Update segment length if we're in speech
         if new_state == VadResult::Speech {
             self.speech_segment_length += 1;
+        } else {
+            self.speech_segment_length = 0;
         }
 
-        self.current_state = new_state;
-        Ok(self.current_state)
+        Ok(new_state)
     }
 
     /// Get the current energy threshold
@@ -110,18 +82,54 @@ impl VadDetector {
 
     /// Get the current state
     pub fn current_state(&self) -> VadResult {
-        self.current_state
+        self.debounce.state()
     }
 
     /// Reset the detector state
     pub fn reset(&mut self) {
-        self.speech_frame_count = 0;
-        self.silence_frame_count = 0;
-        self.current_state = VadResult::Silence;
+        self.debounce.reset();
         self.speech_segment_length = 0;
     }
 }
 
+impl VadBackend for VadDetector {
+    fn kind(&self) -> VadBackendKind {
+        VadBackendKind::Energy
+    }
+
+    fn sample_rate(&self) -> Option<u32> {
+        None
+    }
+
+    fn frame_size(&self) -> Option<usize> {
+        None
+    }
+
+    fn threshold(&self) -> f32 {
+        self.config.energy_threshold
+    }
+
+    /// The frame's RMS level, clamped to [0.0, 1.0]
+    ///
+    /// This isn't a calibrated probability; it is only meaningful against
+    /// the energy threshold.
+    fn speech_probability(&mut self, frame: &[f32]) -> Result<f32, VadError> {
+        Ok(calculate_rms_energy(frame).min(1.0))
+    }
+
+    fn process_frame(&mut self, frame: &[f32]) -> Result<VadResult, VadError> {
+        VadDetector::process_frame(self, frame)
+    }
+
+    fn current_state(&self) -> VadResult {
+        VadDetector::current_state(self)
+    }
+
+    fn reset(&mut self) {
+        VadDetector::reset(self)
+    }
+}
+
 impl Default for VadDetector {
     fn default() -> Self {
         Self::new()
@@ -219,7 +227,7 @@ mod tests {
         // Reset
         detector.reset();
         assert_eq!(detector.current_state(), VadResult::Silence);
-        assert_eq!(detector.speech_frame_count, 0);
+        assert_eq!(detector.debounce.speech_frame_count, 0);
     }
 
     #[test]
@@ -1,15 +1,32 @@
 //! Voice Activity Detection (VAD) module
 //!
-//! This module provides speech detection functionality using an energy-based approach.
-//! For production use, consider integrating Silero VAD or similar ML-based models.
+//! Detectors implement [`VadBackend`]. Two are available: the RMS energy
+//! [`VadDetector`], which needs no model, and Silero VAD run from a local
+//! ONNX file (behind the `silero` feature). [`create_backend`] builds the
+//! one selected by [`VadConfig::backend`]. [`VadSegmenter`] turns a
+//! backend's per-frame decisions into speech segments.
 
+pub mod backend;
 pub mod detector;
+pub mod segmenter;
+#[cfg(feature = "silero")]
+pub mod silero;
 
+pub use backend::{create_backend, VadBackend, VadBackendKind};
 pub use detector::VadDetector;
+pub use segmenter::{SegmenterConfig, SpeechSegment, VadSegmenter};
+#[cfg(feature = "silero")]
+pub use silero::SileroVad;
+
+use std::path::PathBuf;
 
 /// Configuration for VAD processing
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone)]
 pub struct VadConfig {
+    /// Detector to build with [`create_backend`]
+    /// Default: energy
+    pub backend: VadBackendKind,
+
     /// Energy threshold for speech detection (0.0 to 1.0)
     /// Default: 0.01 - values above this are considered potential speech
     pub energy_threshold: f32,
@@ -25,15 +42,25 @@ pub struct VadConfig {
     /// Minimum duration for speech segment (in frames)
     /// Default: 30 (~500ms at 48kHz)
     pub min_speech_duration: usize,
+
+    /// Speech probability a Silero frame must reach (0.0 to 1.0)
+    /// Default: 0.5
+    pub speech_probability_threshold: f32,
+
+    /// Silero ONNX model file, required by the Silero backend
+    pub model_path: Option<PathBuf>,
 }
 
 impl Default for VadConfig {
     fn default() -> Self {
         Self {
+            backend: VadBackendKind::Energy,
             energy_threshold: 0.01,
             speech_frames_threshold: 3,
             silence_frames_threshold: 10,
             min_speech_duration: 30,
+            speech_probability_threshold: 0.5,
+            model_path: None,
         }
     }
 }
@@ -47,6 +74,15 @@ impl VadConfig {
         }
     }
 
+    /// Create a Silero config that loads the model at `model_path`
+    pub fn silero(model_path: impl Into<PathBuf>) -> Self {
+        Self {
+            backend: VadBackendKind::Silero,
+            model_path: Some(model_path.into()),
+            ..Default::default()
+        }
+    }
+
     /// Create a sensitive config (lower threshold, more detections)
     pub fn sensitive() -> Self {
         Self {
@@ -82,9 +118,69 @@ pub enum VadResult {
 /// Error type for VAD operations
 #[derive(Debug, thiserror::Error)]
 pub enum VadError {
+    /// The frame can't be processed by this backend
     #[error("Invalid audio buffer: {0}")]
     InvalidBuffer(String),
+
+    /// The selected backend isn't compiled in or is missing its model
+    #[error("VAD backend unavailable: {0}")]
+    Unavailable(String),
+
+    /// The model failed to load or run
+    #[error("VAD model error: {0}")]
+    Model(String),
 }
 
 /// Type alias for speech energy level (0.0 to 1.0)
 pub type SpeechEnergy = f32;
+
+/// Debounce shared by the backends: a run of speech frames enters the
+/// speech state and a run of silent frames leaves it.
+#[derive(Debug, Clone)]
+pub(crate) struct Debounce {
+    speech_frames_threshold: usize,
+    silence_frames_threshold: usize,
+    pub(crate) speech_frame_count: usize,
+    silence_frame_count: usize,
+    state: VadResult,
+}
+
+impl Debounce {
+    pub(crate) fn new(config: &VadConfig) -> Self {
+        Self {
+            speech_frames_threshold: config.speech_frames_threshold,
+            silence_frames_threshold: config.silence_frames_threshold,
+            speech_frame_count: 0,
+            silence_frame_count: 0,
+            state: VadResult::Silence,
+        }
+    }
+
+    /// Feed one frame's decision and return the debounced state
+    pub(crate) fn update(&mut self, is_speech: bool) -> VadResult {
+        if is_speech {
+            self.speech_frame_count += 1;
+            self.silence_frame_count = 0;
+            if self.speech_frame_count >= self.speech_frames_threshold {
+                self.state = VadResult::Speech;
+            }
+        } else {
+            self.silence_frame_count += 1;
+            if self.silence_frame_count >= self.silence_frames_threshold {
+                self.speech_frame_count = 0;
+                self.state = VadResult::Silence;
+            }
+        }
+        self.state
+    }
+
+    pub(crate) fn state(&self) -> VadResult {
+        self.state
+    }
+
+    pub(crate) fn reset(&mut self) {
+        self.speech_frame_count = 0;
+        self.silence_frame_count = 0;
+        self.state = VadResult::Silence;
+    }
+}
@@ -0,0 +1,372 @@
+//! Utterance segmentation on top of a [`VadBackend`]
+//!
+//! The segmenter frames a continuous stream, scores each frame and turns the
+//! speech/silence decisions into [`SpeechSegment`]s: runs of speech closed by
+//! enough silence, padded with some pre-roll so soft onsets aren't clipped.
+//! Blips shorter than the minimum speech duration are dropped, and long
+//! monologues are split so no segment outgrows the maximum length.
+
+use super::backend::VadBackend;
+use super::VadError;
+
+/// Segmentation timing, in milliseconds of audio
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SegmenterConfig {
+    /// Sample rate of the audio pushed into the segmenter
+    pub sample_rate: u32,
+    /// Audio kept in front of each detected speech onset
+    /// Default: 300
+    pub pre_roll_ms: u32,
+    /// Shorter speech runs are dropped as noise
+    /// Default: 250
+    pub min_speech_ms: u32,
+    /// Silence that ends a segment; shorter pauses stay inside it
+    /// Default: 500
+    pub min_silence_ms: u32,
+    /// Segments are split at this length, including pre-roll
+    /// Default: 30000, Whisper's window
+    pub max_segment_ms: u32,
+}
+
+impl Default for SegmenterConfig {
+    fn default() -> Self {
+        Self {
+            sample_rate: 16000,
+            pre_roll_ms: 300,
+            min_speech_ms: 250,
+            min_silence_ms: 500,
+            max_segment_ms: 30_000,
+        }
+    }
+}
+
+impl SegmenterConfig {
+    fn samples(&self, ms: u32) -> usize {
+        (self.sample_rate as u64 * ms as u64 / 1000) as usize
+    }
+}
+
+/// A span of speech, as sample positions in the pushed stream
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SpeechSegment {
+    /// First sample, including pre-roll
+    pub start_sample: usize,
+    /// One past the last sample
+    pub end_sample: usize,
+}
+
+impl SpeechSegment {
+    /// Number of samples in the segment
+    pub fn len(&self) -> usize {
+        self.end_sample - self.start_sample
+    }
+
+    /// Whether the segment has no samples
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Start of the segment in milliseconds at `sample_rate`
+    pub fn start_ms(&self, sample_rate: u32) -> u64 {
+        self.start_sample as u64 * 1000 / sample_rate as u64
+    }
+
+    /// End of the segment in milliseconds at `sample_rate`
+    pub fn end_ms(&self, sample_rate: u32) -> u64 {
+        self.end_sample as u64 * 1000 / sample_rate as u64
+    }
+}
+
+/// Splits a stream into speech segments
+pub struct VadSegmenter {
+    backend: Box<dyn VadBackend>,
+    config: SegmenterConfig,
+    frame_size: usize,
+    /// Samples not yet filling a whole frame
+    pending: Vec<f32>,
+    /// Stream position of the first pending sample
+    position: usize,
+    /// Onset of the open segment, without pre-roll
+    speech_start: Option<usize>,
+    /// Start of the silence run inside the open segment
+    silence_start: Option<usize>,
+    /// End of the last emitted segment; pre-roll never reaches back past it
+    last_end: usize,
+}
+
+impl VadSegmenter {
+    /// Segment audio at `config.sample_rate` with `backend`
+    ///
+    /// Backends without a fixed frame size get 30 ms frames.
+    ///
+    /// # Errors
+    ///
+    /// Returns `InvalidBuffer` if the backend needs a different sample rate.
+    pub fn new(backend: Box<dyn VadBackend>, config: SegmenterConfig) -> Result<Self, VadError> {
+        if let Some(rate) = backend.sample_rate() {
+            if rate != config.sample_rate {
+                return Err(VadError::InvalidBuffer(format!(
+                    "{} VAD needs {} Hz audio, segmenter configured for {} Hz",
+                    backend.kind().as_str(),
+                    rate,
+                    config.sample_rate
+                )));
+            }
+        }
+        let frame_size = backend.frame_size().unwrap_or_else(|| config.samples(30)).max(1);
+        Ok(Self {
+            backend,
+            config,
+            frame_size,
+            pending: Vec::with_capacity(frame_size),
+            position: 0,
+            speech_start: None,
+            silence_start: None,
+            last_end: 0,
+        })
+    }
+
+    /// Timing this segmenter was built with
+    pub fn config(&self) -> &SegmenterConfig {
+        &self.config
+    }
+
+    /// Samples pushed so far
+    pub fn position(&self) -> usize {
+        self.position + self.pending.len()
+    }
+
+    /// Start of the utterance in progress, including pre-roll
+    ///
+    /// `None` until the speech has lasted the minimum speech duration, so
+    /// callers can show partial results without reacting to clicks.
+    pub fn open_segment_start(&self) -> Option<usize> {
+        let start = self.speech_start?;
+        let spoken = self.silence_start.unwrap_or(self.position) - start;
+        (spoken >= self.config.samples(self.config.min_speech_ms)).then(|| self.padded_start(start))
+    }
+
+    /// Feed samples and return the segments they complete
+    ///
+    /// # Errors
+    ///
+    /// Returns the backend's error if a frame can't be scored.
+    pub fn push(&mut self, samples: &[f32]) -> Result<Vec<SpeechSegment>, VadError> {
+        let mut segments = Vec::new();
+        let mut rest = samples;
+        while !rest.is_empty() {
+            let take = (self.frame_size - self.pending.len()).min(rest.len());
+            self.pending.extend_from_slice(&rest[..take]);
+            rest = &rest[take..];
+            if self.pending.len() == self.frame_size {
+                segments.extend(self.process_pending()?);
+            }
+        }
+        Ok(segments)
+    }
+
+    /// Score what is left and close the segment in progress, if any
+    ///
+    /// # Errors
+    ///
+    /// Returns the backend's error if the last frame can't be scored.
+    pub fn flush(&mut self) -> Result<Option<SpeechSegment>, VadError> {
+        let mut segment = None;
+        if !self.pending.is_empty() {
+            segment = self.process_pending()?;
+        }
+        if let Some(start) = self.speech_start.take() {
+            let end = self.silence_start.take().unwrap_or(self.position);
+            segment = segment.or(self.close(start, end));
+        }
+        Ok(segment)
+    }
+
+    /// Forget all state and restart the stream at sample 0
+    pub fn reset(&mut self) {
+        self.backend.reset();
+        self.pending.clear();
+        self.position = 0;
+        self.speech_start = None;
+        self.silence_start = None;
+        self.last_end = 0;
+    }
+
+    fn process_pending(&mut self) -> Result<Option<SpeechSegment>, VadError> {
+        let is_speech = self.backend.speech_probability(&self.pending)? >= self.backend.threshold();
+        let frame_start = self.position;
+        let frame_end = frame_start + self.pending.len();
+        self.position = frame_end;
+        self.pending.clear();
+
+        let Some(start) = self.speech_start else {
+            if is_speech {
+                self.speech_start = Some(frame_start);
+            }
+            return Ok(None);
+        };
+
+        if is_speech {
+            self.silence_start = None;
+        } else if self.silence_start.is_none() {
+            self.silence_start = Some(frame_start);
+        }
+
+        if let Some(silence) = self.silence_start {
+            if frame_end - silence >= self.config.samples(self.config.min_silence_ms) {
+                self.speech_start = None;
+                self.silence_start = None;
+                return Ok(self.close(start, silence));
+            }
+        }
+
+        if frame_end - self.padded_start(start) >= self.config.samples(self.config.max_segment_ms) {
+            // Prefer cutting at the pause in progress; otherwise cut mid-speech
+            // and carry on with a new segment straight away
+            let end = self.silence_start.unwrap_or(frame_end);
+            self.speech_start = self.silence_start.is_none().then_some(frame_end);
+            self.silence_start = None;
+            return Ok(self.close(start, end));
+        }
+
+        Ok(None)
+    }
+
+    fn padded_start(&self, start: usize) -> usize {
+        start.saturating_sub(self.config.samples(self.config.pre_roll_ms)).max(self.last_end)
+    }
+
+    fn close(&mut self, start: usize, end: usize) -> Option<SpeechSegment> {
+        if end - start < self.config.samples(self.config.min_speech_ms) {
+            return None;
+        }
+        let segment = SpeechSegment { start_sample: self.padded_start(start), end_sample: end };
+        self.last_end = end;
+        Some(segment)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::vad::{VadConfig, VadDetector};
+
+    const RATE: u32 = 16000;
+
+    fn segmenter(config: SegmenterConfig) -> VadSegmenter {
+        VadSegmenter::new(Box::new(VadDetector::with_config(VadConfig::default())), config).unwrap()
+    }
+
+    fn tone(ms: u32) -> Vec<f32> {
+        vec![0.1; (RATE * ms / 1000) as usize]
+    }
+
+    fn silence(ms: u32) -> Vec<f32> {
+        vec![0.0; (RATE * ms / 1000) as usize]
+    }
+
+    fn ms(samples: usize) -> u32 {
+        (samples as u64 * 1000 / RATE as u64) as u32
+    }
+
+    #[test]
+    fn test_segment_with_pre_roll() {
+        let mut seg = segmenter(SegmenterConfig::default());
+        assert!(seg.push(&silence(990)).unwrap().is_empty());
+        assert!(seg.push(&tone(600)).unwrap().is_empty());
+        assert_eq!(seg.open_segment_start().map(ms), Some(690));
+
+        let segments = seg.push(&silence(600)).unwrap();
+        assert_eq!(segments.len(), 1);
+        assert_eq!(ms(segments[0].start_sample), 690);
+        assert_eq!(ms(segments[0].end_sample), 1590);
+        assert_eq!(segments[0].start_ms(RATE), 690);
+        assert!(seg.open_segment_start().is_none());
+        assert_eq!(seg.flush().unwrap(), None);
+    }
+
+    #[test]
+    fn test_short_pause_stays_in_segment() {
+        let mut seg = segmenter(SegmenterConfig { pre_roll_ms: 0, ..Default::default() });
+        let mut audio = tone(600);
+        audio.extend(silence(300));
+        audio.extend(tone(600));
+        audio.extend(silence(600));
+
+        let segments = seg.push(&audio).unwrap();
+        assert_eq!(segments.len(), 1);
+        assert_eq!(ms(segments[0].start_sample), 0);
+        assert_eq!(ms(segments[0].end_sample), 1500);
+    }
+
+    #[test]
+    fn test_drops_short_blips() {
+        let mut seg = segmenter(SegmenterConfig::default());
+        let mut audio = silence(300);
+        audio.extend(tone(90));
+        audio.extend(silence(900));
+        assert!(seg.push(&audio).unwrap().is_empty());
+        assert!(seg.flush().unwrap().is_none());
+    }
+
+    #[test]
+    fn test_splits_at_max_length() {
+        let config = SegmenterConfig { pre_roll_ms: 0, max_segment_ms: 1200, ..Default::default() };
+        let mut seg = segmenter(config);
+        let segments = seg.push(&tone(3000)).unwrap();
+        assert_eq!(segments.len(), 2);
+        assert_eq!(segments[0].len(), seg.config().samples(1200));
+        assert_eq!(segments[1].start_sample, segments[0].end_sample);
+
+        let last = seg.flush().unwrap().unwrap();
+        assert_eq!(last.start_sample, segments[1].end_sample);
+        assert_eq!(ms(last.end_sample), 3000);
+    }
+
+    #[test]
+    fn test_pre_roll_stops_at_previous_segment() {
+        let config = SegmenterConfig { pre_roll_ms: 1000, ..Default::default() };
+        let mut seg = segmenter(config);
+        let mut audio = tone(600);
+        audio.extend(silence(510));
+        audio.extend(tone(600));
+        audio.extend(silence(600));
+
+        let segments = seg.push(&audio).unwrap();
+        assert_eq!(segments.len(), 2);
+        assert_eq!(segments[1].start_sample, segments[0].end_sample);
+    }
+
+    #[test]
+    fn test_rejects_mismatched_sample_rate() {
+        struct Fixed;
+        impl VadBackend for Fixed {
+            fn kind(&self) -> crate::vad::VadBackendKind {
+                crate::vad::VadBackendKind::Silero
+            }
+            fn sample_rate(&self) -> Option<u32> {
+                Some(16000)
+            }
+            fn frame_size(&self) -> Option<usize> {
+                Some(512)
+            }
+            fn threshold(&self) -> f32 {
+                0.5
+            }
+            fn speech_probability(&mut self, _: &[f32]) -> Result<f32, VadError> {
+                Ok(0.0)
+            }
+            fn process_frame(&mut self, _: &[f32]) -> Result<crate::vad::VadResult, VadError> {
+                Ok(crate::vad::VadResult::Silence)
+            }
+            fn current_state(&self) -> crate::vad::VadResult {
+                crate::vad::VadResult::Silence
+            }
+            fn reset(&mut self) {}
+        }
+
+        let config = SegmenterConfig { sample_rate: 48000, ..Default::default() };
+        assert!(matches!(VadSegmenter::new(Box::new(Fixed), config), Err(VadError::InvalidBuffer(_))));
+        assert!(VadSegmenter::new(Box::new(Fixed), SegmenterConfig::default()).is_ok());
+    }
+}
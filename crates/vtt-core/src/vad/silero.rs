@@ -0,0 +1,222 @@
+//! Silero VAD through ONNX Runtime
+//!
+//! Runs the Silero model on CPU from a local `.onnx` file. Frames are
+//! 16 kHz mono; v4 models take 30 ms (480 samples) and v5 models, which only
+//! accept 512-sample windows, take 32 ms. The layout is detected from the
+//! model's inputs when it is loaded.
+
+use super::backend::{VadBackend, VadBackendKind};
+use super::{Debounce, VadConfig, VadError, VadResult};
+use ort::session::Session;
+use ort::value::Tensor;
+use std::path::Path;
+
+/// Sample rate Silero runs at
+pub const SILERO_SAMPLE_RATE: u32 = 16000;
+
+/// Frame size for v4 models: 30 ms at 16 kHz
+pub const SILERO_FRAME_SIZE: usize = 480;
+
+// v5 models only accept 512-sample windows and expect the previous
+// window's last 64 samples in front of each one
+const V5_FRAME_SIZE: usize = 512;
+const V5_CONTEXT_SIZE: usize = 64;
+const V5_STATE_LEN: usize = 2 * 128;
+const V4_STATE_LEN: usize = 2 * 64;
+
+/// Recurrent state carried between frames, in the model's input layout
+enum ModelState {
+    /// `input`, `sr`, `h`, `c` -> `output`, `hn`, `cn`
+    V4 { h: Vec<f32>, c: Vec<f32> },
+    /// `input`, `state`, `sr` -> `output`, `stateN`
+    V5 { state: Vec<f32>, context: Vec<f32> },
+}
+
+impl ModelState {
+    fn for_session(session: &Session) -> Self {
+        if session.inputs.iter().any(|input| input.name == "state") {
+            Self::V5 { state: vec![0.0; V5_STATE_LEN], context: vec![0.0; V5_CONTEXT_SIZE] }
+        } else {
+            Self::V4 { h: vec![0.0; V4_STATE_LEN], c: vec![0.0; V4_STATE_LEN] }
+        }
+    }
+
+    fn frame_size(&self) -> usize {
+        match self {
+            Self::V4 { .. } => SILERO_FRAME_SIZE,
+            Self::V5 { .. } => V5_FRAME_SIZE,
+        }
+    }
+
+    fn clear(&mut self) {
+        match self {
+            Self::V4 { h, c } => {
+                h.fill(0.0);
+                c.fill(0.0);
+            }
+            Self::V5 { state, context } => {
+                state.fill(0.0);
+                context.fill(0.0);
+            }
+        }
+    }
+}
+
+/// Silero voice activity detector
+pub struct SileroVad {
+    session: Session,
+    state: ModelState,
+    threshold: f32,
+    debounce: Debounce,
+}
+
+impl SileroVad {
+    /// Load the model at `config.model_path`
+    ///
+    /// # Errors
+    ///
+    /// Returns `Unavailable` if no model path is set and `Model` if the file
+    /// can't be loaded.
+    pub fn from_config(config: &VadConfig) -> Result<Self, VadError> {
+        let path = config
+            .model_path
+            .as_deref()
+            .ok_or_else(|| VadError::Unavailable("Silero VAD needs a model path".to_string()))?;
+        let mut vad = Self::load(path)?;
+        vad.threshold = config.speech_probability_threshold.clamp(0.0, 1.0);
+        vad.debounce = Debounce::new(config);
+        Ok(vad)
+    }
+
+    /// Load a model with the default thresholds
+    ///
+    /// # Errors
+    ///
+    /// Returns `Model` if the file can't be loaded.
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self, VadError> {
+        let path = path.as_ref();
+        let session = Session::builder()
+            .and_then(|builder| builder.with_intra_threads(1))
+            .and_then(|builder| builder.commit_from_file(path))
+            .map_err(|e| VadError::Model(format!("{}: {}", path.display(), e)))?;
+        let state = ModelState::for_session(&session);
+        let config = VadConfig::default();
+        Ok(Self {
+            session,
+            state,
+            threshold: config.speech_probability_threshold,
+            debounce: Debounce::new(&config),
+        })
+    }
+
+    /// Speech probability a frame must reach to count as speech
+    pub fn threshold(&self) -> f32 {
+        self.threshold
+    }
+
+    fn run(&mut self, frame: &[f32]) -> Result<f32, VadError> {
+        let frame_size = self.state.frame_size();
+        if frame.len() > frame_size {
+            return Err(VadError::InvalidBuffer(format!(
+                "Silero takes frames of at most {} samples, got {}",
+                frame_size,
+                frame.len()
+            )));
+        }
+        // A short final frame is padded with silence
+        let mut samples = frame.to_vec();
+        samples.resize(frame_size, 0.0);
+
+        let model_error = |e: ort::Error| VadError::Model(e.to_string());
+        let sr = Tensor::from_array(([1usize], vec![SILERO_SAMPLE_RATE as i64])).map_err(model_error)?;
+
+        match &mut self.state {
+            ModelState::V4 { h, c } => {
+                let input = Tensor::from_array(([1usize, frame_size], samples)).map_err(model_error)?;
+                let h_in = Tensor::from_array(([2usize, 1, 64], h.clone())).map_err(model_error)?;
+                let c_in = Tensor::from_array(([2usize, 1, 64], c.clone())).map_err(model_error)?;
+                let outputs = self
+                    .session
+                    .run(ort::inputs!["input" => input, "sr" => sr, "h" => h_in, "c" => c_in])
+                    .map_err(model_error)?;
+
+                let (_, hn) = outputs["hn"].try_extract_tensor::<f32>().map_err(model_error)?;
+                let (_, cn) = outputs["cn"].try_extract_tensor::<f32>().map_err(model_error)?;
+                h.copy_from_slice(hn);
+                c.copy_from_slice(cn);
+                let (_, probability) = outputs["output"].try_extract_tensor::<f32>().map_err(model_error)?;
+                Ok(probability[0])
+            }
+            ModelState::V5 { state, context } => {
+                let mut windowed = Vec::with_capacity(V5_CONTEXT_SIZE + frame_size);
+                windowed.extend_from_slice(context);
+                windowed.extend_from_slice(&samples);
+                context.copy_from_slice(&samples[frame_size - V5_CONTEXT_SIZE..]);
+
+                let input =
+                    Tensor::from_array(([1usize, windowed.len()], windowed)).map_err(model_error)?;
+                let state_in = Tensor::from_array(([2usize, 1, 128], state.clone())).map_err(model_error)?;
+                let outputs = self
+                    .session
+                    .run(ort::inputs!["input" => input, "state" => state_in, "sr" => sr])
+                    .map_err(model_error)?;
+
+                let (_, state_n) = outputs["stateN"].try_extract_tensor::<f32>().map_err(model_error)?;
+                state.copy_from_slice(state_n);
+                let (_, probability) = outputs["output"].try_extract_tensor::<f32>().map_err(model_error)?;
+                Ok(probability[0])
+            }
+        }
+    }
+}
+
+impl VadBackend for SileroVad {
+    fn kind(&self) -> VadBackendKind {
+        VadBackendKind::Silero
+    }
+
+    fn sample_rate(&self) -> Option<u32> {
+        Some(SILERO_SAMPLE_RATE)
+    }
+
+    fn frame_size(&self) -> Option<usize> {
+        Some(self.state.frame_size())
+    }
+
+    fn threshold(&self) -> f32 {
+        self.threshold
+    }
+
+    fn speech_probability(&mut self, frame: &[f32]) -> Result<f32, VadError> {
+        self.run(frame)
+    }
+
+    fn process_frame(&mut self, frame: &[f32]) -> Result<VadResult, VadError> {
+        if frame.is_empty() {
+            return Ok(VadResult::Unknown);
+        }
+        let probability = self.run(frame)?;
+        Ok(self.debounce.update(probability >= self.threshold))
+    }
+
+    fn current_state(&self) -> VadResult {
+        self.debounce.state()
+    }
+
+    fn reset(&mut self) {
+        self.state.clear();
+        self.debounce.reset();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_missing_model() {
+        assert!(matches!(SileroVad::load("/nonexistent/silero_vad.onnx"), Err(VadError::Model(_))));
+        let config = VadConfig { backend: VadBackendKind::Silero, ..Default::default() };
+        assert!(matches!(SileroVad::from_config(&config), Err(VadError::Unavailable(_))));
+    }
+}
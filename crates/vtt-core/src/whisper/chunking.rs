@@ -0,0 +1,253 @@
+//! Chunked transcription of long audio
+//!
+//! whisper.cpp transcribes a buffer in one blocking call and reports nothing
+//! until it is done, which for an hour of audio is a long time. Long audio is
+//! split into chunks of about 30 seconds instead. [`plan_chunks`] cuts in the
+//! silences a VAD found, so words aren't cut in half, and lets neighbouring
+//! chunks overlap a little so Whisper hears every word with some context.
+//! Each chunk owns the span between its two cuts: [`stitch`] keeps the
+//! segments whose midpoint falls in that span, moved onto the timeline of
+//! the whole recording.
+
+use super::{Segment, Token, Transcription};
+use crate::vad::SpeechSegment;
+
+/// How long audio is split, in milliseconds of audio
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ChunkConfig {
+    /// Sample rate of the audio being split
+    pub sample_rate: u32,
+    /// Longest chunk, overlap included; audio up to this long is not split
+    /// Default: 30000, Whisper's window
+    pub max_chunk_ms: u32,
+    /// Cuts are looked for no earlier than this after the previous one
+    /// Default: 15000
+    pub min_chunk_ms: u32,
+    /// Audio each chunk shares with its neighbours on either side
+    /// Default: 1000
+    pub overlap_ms: u32,
+}
+
+impl Default for ChunkConfig {
+    fn default() -> Self {
+        Self {
+            sample_rate: 16000,
+            max_chunk_ms: 30_000,
+            min_chunk_ms: 15_000,
+            overlap_ms: 1_000,
+        }
+    }
+}
+
+impl ChunkConfig {
+    fn samples(&self, ms: u32) -> usize {
+        (self.sample_rate as u64 * ms as u64 / 1000) as usize
+    }
+}
+
+/// A chunk of a longer recording, as sample positions in it
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AudioChunk {
+    /// First sample transcribed, including overlap
+    pub start_sample: usize,
+    /// One past the last sample transcribed, including overlap
+    pub end_sample: usize,
+    /// Start of the span whose segments this chunk keeps
+    pub keep_start: usize,
+    /// End of the span whose segments this chunk keeps
+    pub keep_end: usize,
+}
+
+impl AudioChunk {
+    /// All of `len` samples as one chunk
+    pub fn whole(len: usize) -> Self {
+        Self { start_sample: 0, end_sample: len, keep_start: 0, keep_end: len }
+    }
+
+    /// Number of samples transcribed
+    pub fn len(&self) -> usize {
+        self.end_sample - self.start_sample
+    }
+
+    /// Whether the chunk has no samples
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+fn to_ms(sample: usize, sample_rate: u32) -> i64 {
+    (sample as u64 * 1000 / sample_rate.max(1) as u64) as i64
+}
+
+/// Split `total_samples` of audio into chunks
+///
+/// `speech` is what a [`VadSegmenter`](crate::vad::VadSegmenter) found in
+/// the audio, in order. Each cut goes in the middle of the latest silence
+/// that keeps the chunk within `max_chunk_ms`; without one, the chunk is cut
+/// at full length. Audio no longer than `max_chunk_ms` is a single chunk.
+pub fn plan_chunks(total_samples: usize, speech: &[SpeechSegment], config: &ChunkConfig) -> Vec<AudioChunk> {
+    let max_chunk = config.samples(config.max_chunk_ms).max(1);
+    if total_samples <= max_chunk {
+        return vec![AudioChunk::whole(total_samples)];
+    }
+
+    let overlap = config.samples(config.overlap_ms);
+    let max_keep = max_chunk.saturating_sub(2 * overlap).max(1);
+    let min_keep = config.samples(config.min_chunk_ms).min(max_keep);
+
+    // Silences before, between and after the speech segments
+    let mut silences = Vec::with_capacity(speech.len() + 1);
+    let mut silence_start = 0;
+    for segment in speech {
+        silences.push((silence_start, segment.start_sample));
+        silence_start = silence_start.max(segment.end_sample);
+    }
+    silences.push((silence_start, total_samples));
+    silences.retain(|&(start, end)| start < end);
+
+    let mut cuts = vec![0];
+    let mut start = 0;
+    while total_samples - start > max_keep {
+        let (earliest, latest) = (start + min_keep.max(1), start + max_keep);
+        let cut = silences
+            .iter()
+            .filter_map(|&(silence_start, silence_end)| {
+                let (from, to) = (silence_start.max(earliest), silence_end.min(latest));
+                (from <= to).then(|| ((silence_start + silence_end) / 2).clamp(from, to))
+            })
+            .max()
+            .unwrap_or(latest);
+        cuts.push(cut);
+        start = cut;
+    }
+    cuts.push(total_samples);
+
+    cuts.windows(2)
+        .map(|keep| AudioChunk {
+            start_sample: keep[0].saturating_sub(overlap),
+            end_sample: (keep[1] + overlap).min(total_samples),
+            keep_start: keep[0],
+            keep_end: keep[1],
+        })
+        .collect()
+}
+
+/// Join the transcriptions of planned chunks into one
+///
+/// Segment and token times are moved from the chunk's timeline onto the
+/// recording's. Segments of the overlap are kept by the chunk whose span
+/// holds their midpoint, so each stretch of speech appears once. The
+/// language is the first one a chunk reports.
+pub fn stitch(chunks: Vec<(AudioChunk, Transcription)>, sample_rate: u32) -> Transcription {
+    let last = chunks.len().saturating_sub(1);
+    let mut language = None;
+    let mut segments = Vec::new();
+    for (index, (chunk, transcription)) in chunks.into_iter().enumerate() {
+        language = language.or(transcription.language);
+        let offset_ms = to_ms(chunk.start_sample, sample_rate);
+        let keep_start_ms = to_ms(chunk.keep_start, sample_rate);
+        let keep_end_ms = if index == last { i64::MAX } else { to_ms(chunk.keep_end, sample_rate) };
+
+        segments.extend(
+            transcription
+                .segments
+                .into_iter()
+                .map(|segment| Segment {
+                    start_ms: segment.start_ms + offset_ms,
+                    end_ms: segment.end_ms + offset_ms,
+                    tokens: segment
+                        .tokens
+                        .into_iter()
+                        .map(|token| Token {
+                            start_ms: token.start_ms + offset_ms,
+                            end_ms: token.end_ms + offset_ms,
+                            ..token
+                        })
+                        .collect(),
+                    ..segment
+                })
+                .filter(|segment| {
+                    let midpoint = (segment.start_ms + segment.end_ms) / 2;
+                    (keep_start_ms..keep_end_ms).contains(&midpoint)
+                }),
+        );
+    }
+
+    let mut transcription = Transcription::from_segments(segments);
+    transcription.language = language;
+    transcription
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const RATE: usize = 16000;
+
+    fn speech(ranges: &[(usize, usize)]) -> Vec<SpeechSegment> {
+        ranges
+            .iter()
+            .map(|&(start, end)| SpeechSegment { start_sample: start * RATE, end_sample: end * RATE })
+            .collect()
+    }
+
+    #[test]
+    fn test_short_audio_is_one_chunk() {
+        let chunks = plan_chunks(20 * RATE, &speech(&[(1, 19)]), &ChunkConfig::default());
+        assert_eq!(chunks, [AudioChunk::whole(20 * RATE)]);
+    }
+
+    #[test]
+    fn test_cuts_in_silences() {
+        let config = ChunkConfig::default();
+        let total = 70 * RATE;
+        // Pauses at 20-22 s, 26-27 s and 50-54 s
+        let chunks = plan_chunks(total, &speech(&[(0, 20), (22, 26), (27, 50), (54, 70)]), &config);
+
+        let keeps: Vec<(usize, usize)> = chunks.iter().map(|c| (c.keep_start / RATE, c.keep_end / RATE)).collect();
+        // The latest pause within 28 s of the previous cut wins
+        assert_eq!(keeps[0], (0, 26));
+        assert_eq!(keeps[1].0, 26);
+        assert_eq!(chunks.last().unwrap().keep_end, total);
+        for pair in chunks.windows(2) {
+            assert_eq!(pair[0].keep_end, pair[1].keep_start);
+        }
+        for chunk in &chunks {
+            assert!(chunk.len() <= 30 * RATE, "{:?}", chunk);
+            assert_eq!(chunk.start_sample, chunk.keep_start.saturating_sub(RATE));
+        }
+    }
+
+    #[test]
+    fn test_cuts_without_silence() {
+        let chunks = plan_chunks(100 * RATE, &speech(&[(0, 100)]), &ChunkConfig::default());
+        assert_eq!(chunks.len(), 4);
+        assert_eq!((chunks[0].keep_end, chunks[0].end_sample), (28 * RATE, 29 * RATE));
+        assert_eq!(chunks[1].start_sample, 27 * RATE);
+        assert!(chunks.iter().all(|c| c.len() <= 30 * RATE));
+    }
+
+    #[test]
+    fn test_stitch() {
+        let segment = |start_ms, end_ms, text: &str| Segment {
+            start_ms,
+            end_ms,
+            text: text.to_string(),
+            no_speech_probability: 0.0,
+            tokens: vec![Token { id: 0, text: text.to_string(), start_ms, end_ms, probability: 0.8 }],
+        };
+        let first = AudioChunk { start_sample: 0, end_sample: 21 * RATE, keep_start: 0, keep_end: 20 * RATE };
+        let second = AudioChunk { start_sample: 19 * RATE, end_sample: 40 * RATE, keep_start: 20 * RATE, keep_end: 40 * RATE };
+        let mut first_tx = Transcription::from_segments(vec![segment(0, 10_000, "one"), segment(18_000, 21_000, "two")]);
+        first_tx.language = Some("de".to_string());
+        // "two" again, heard in the overlap, then "three"
+        let second_tx = Transcription::from_segments(vec![segment(0, 1_500, "two"), segment(2_000, 9_000, "three")]);
+
+        let stitched = stitch(vec![(first, first_tx), (second, second_tx)], RATE as u32);
+        assert_eq!(stitched.text, "one two three");
+        assert_eq!(stitched.language.as_deref(), Some("de"));
+        assert_eq!((stitched.segments[2].start_ms, stitched.segments[2].end_ms), (21_000, 28_000));
+        assert_eq!(stitched.segments[2].tokens[0].start_ms, 21_000);
+        assert_eq!(stitched.end_timestamp, 28_000);
+    }
+}
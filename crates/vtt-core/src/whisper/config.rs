@@ -1,5 +1,59 @@
 //! Whisper configuration
 
+use super::decoding::DecodingOptions;
+use super::error::WhisperError;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::str::FromStr;
+
+/// Longest tail of earlier text carried into the prompt, in characters.
+/// Whisper only reads the last 224 prompt tokens, and the glossary and
+/// initial prompt need room too.
+const MAX_PREVIOUS_TEXT_CHARS: usize = 400;
+
+/// What Whisper produces from the audio
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum Task {
+    /// Text in the spoken language
+    #[default]
+    Transcribe,
+    /// English text, whatever the spoken language
+    Translate,
+}
+
+impl Task {
+    /// Name used in tool parameters and on the command line
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Transcribe => "transcribe",
+            Self::Translate => "translate",
+        }
+    }
+}
+
+impl FromStr for Task {
+    type Err = WhisperError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "transcribe" => Ok(Self::Transcribe),
+            "translate" => Ok(Self::Translate),
+            _ => Err(WhisperError::InvalidParameter(format!(
+                "Unknown task '{}' (expected transcribe or translate)",
+                s
+            ))),
+        }
+    }
+}
+
+impl fmt::Display for Task {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
 /// Configuration for Whisper transcription
 #[derive(Debug, Clone)]
 pub struct WhisperConfig {
@@ -23,6 +77,21 @@ pub struct WhisperConfig {
     /// Translate to English (if applicable)
     pub translate: bool,
 
+    /// Text to prime the decoder with, e.g. a description of the topic or
+    /// a sample of the expected spelling and punctuation
+    pub initial_prompt: Option<String>,
+
+    /// Names, jargon and other terms the model should prefer spelling this
+    /// way; passed to the decoder as a glossary in the prompt
+    pub vocabulary: Vec<String>,
+
+    /// Text transcribed just before this audio, so a stream keeps its
+    /// context across windows. Only the tail is used.
+    pub previous_text: Option<String>,
+
+    /// Sampling strategy, temperature fallback and suppression
+    pub decoding: DecodingOptions,
+
     /// Maximum length of context (in tokens) to use for transcription
     pub n_max_context: i32,
 
@@ -61,6 +130,10 @@ impl Default for WhisperConfig {
             required_sample_rate: 16000,
             language: None,
             translate: false,
+            initial_prompt: None,
+            vocabulary: Vec::new(),
+            previous_text: None,
+            decoding: DecodingOptions::default(),
             n_max_context: 0,
             n_max_text_tokens: 0,
             offset_ms: 0,
@@ -102,6 +175,73 @@ impl WhisperConfig {
         self
     }
 
+    /// Set the decoding options
+    pub fn with_decoding(mut self, decoding: DecodingOptions) -> Self {
+        self.decoding = decoding;
+        self
+    }
+
+    /// Set the initial prompt; an empty prompt clears it
+    pub fn with_initial_prompt(mut self, prompt: Option<String>) -> Self {
+        self.initial_prompt = prompt.filter(|p| !p.trim().is_empty());
+        self
+    }
+
+    /// Set the vocabulary terms, dropping blank ones
+    pub fn with_vocabulary<I, S>(mut self, terms: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.vocabulary = terms
+            .into_iter()
+            .map(|t| t.into().trim().to_string())
+            .filter(|t| !t.is_empty())
+            .collect();
+        self
+    }
+
+    /// Set the text transcribed before this audio
+    pub fn with_previous_text(mut self, text: Option<String>) -> Self {
+        self.previous_text = text.filter(|t| !t.trim().is_empty());
+        self
+    }
+
+    /// Prompt passed to the decoder, if any
+    ///
+    /// The vocabulary comes first as a glossary line, then the initial
+    /// prompt, then the tail of the previous text, which is closest to the
+    /// audio and so has the most influence. Null bytes are removed since
+    /// whisper.cpp takes a C string.
+    pub fn prompt(&self) -> Option<String> {
+        let mut parts = Vec::new();
+        if !self.vocabulary.is_empty() {
+            parts.push(format!("Glossary: {}.", self.vocabulary.join(", ")));
+        }
+        if let Some(ref prompt) = self.initial_prompt {
+            parts.push(prompt.trim().to_string());
+        }
+        if let Some(ref text) = self.previous_text {
+            parts.push(tail(text.trim(), MAX_PREVIOUS_TEXT_CHARS).to_string());
+        }
+        let prompt = parts.join(" ").replace('\0', "");
+        (!prompt.trim().is_empty()).then_some(prompt)
+    }
+
+    /// Transcribe in the spoken language or translate to English
+    pub fn with_task(self, task: Task) -> Self {
+        self.with_translation(task == Task::Translate)
+    }
+
+    /// Whether this config transcribes or translates
+    pub fn task(&self) -> Task {
+        if self.translate {
+            Task::Translate
+        } else {
+            Task::Transcribe
+        }
+    }
+
     /// Set idle timeout for model unloading (in seconds)
     /// Set to None to keep model loaded permanently
     pub fn with_idle_timeout(mut self, timeout_secs: Option<u64>) -> Self {
@@ -116,12 +256,26 @@ impl WhisperConfig {
     }
 }
 
-
-
+/// The last `max_chars` characters of `text`, starting at a word boundary
+/// when the text is cut
+fn tail(text: &str, max_chars: usize) -> &str {
+    let Some((start, _)) = text.char_indices().rev().nth(max_chars.saturating_sub(1)) else {
+        return text;
+    };
+    let rest = &text[start..];
+    if start == 0 || text[..start].ends_with(char::is_whitespace) {
+        return rest;
+    }
+    match rest.find(char::is_whitespace) {
+        Some(space) => rest[space..].trim_start(),
+        None => rest,
+    }
+}
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::whisper::DecodingPreset;
 
     #[test]
     fn test_whisper_config_default() {
@@ -138,13 +292,28 @@ mod tests {
             .with_language("en")
             .with_threads(8)
             .with_gpu(false)
-            .with_translation(true);
+            .with_translation(true)
+            .with_decoding(DecodingPreset::Accurate.options());
         
         assert_eq!(config.model_path, "custom/model.bin");
         assert_eq!(config.language, Some("en".to_string()));
         assert_eq!(config.n_threads, 8);
         assert!(!config.use_gpu);
         assert!(config.translate);
+        assert_eq!(config.decoding.beam_size, 5);
+    }
+
+    #[test]
+    fn test_task() {
+        let config = WhisperConfig::default();
+        assert_eq!(config.task(), Task::Transcribe);
+        let config = config.with_task(Task::Translate);
+        assert!(config.translate);
+        assert_eq!(config.task(), Task::Translate);
+
+        assert_eq!("Translate".parse::<Task>().unwrap(), Task::Translate);
+        assert!("summarize".parse::<Task>().is_err());
+        assert_eq!(Task::Transcribe.to_string(), "transcribe");
     }
 
     #[test]
@@ -178,4 +347,37 @@ mod tests {
         
         assert_eq!(config.max_sessions, 1); // Should be clamped to 1
     }
+
+    #[test]
+    fn test_prompt_composition() {
+        assert_eq!(WhisperConfig::default().prompt(), None);
+
+        let config = WhisperConfig::default()
+            .with_vocabulary(["Kubernetes", " ", "PipeWire"])
+            .with_initial_prompt(Some("A talk about Linux audio.".to_string()))
+            .with_previous_text(Some("and then the graph\0 restarts".to_string()));
+        assert_eq!(config.vocabulary, vec!["Kubernetes", "PipeWire"]);
+        assert_eq!(
+            config.prompt().as_deref(),
+            Some("Glossary: Kubernetes, PipeWire. A talk about Linux audio. and then the graph restarts")
+        );
+
+        let config = WhisperConfig::default().with_initial_prompt(Some("  ".to_string()));
+        assert_eq!(config.initial_prompt, None);
+        assert_eq!(config.prompt(), None);
+    }
+
+    #[test]
+    fn test_previous_text_tail() {
+        assert_eq!(tail("short text", 100), "short text");
+        assert_eq!(tail("one two three", 7), "three");
+        assert_eq!(tail("one two three", 9), "two three");
+        assert_eq!(tail("héllo wörld", 6), "wörld");
+
+        let long = "word ".repeat(200);
+        let config = WhisperConfig::default().with_previous_text(Some(long));
+        let prompt = config.prompt().unwrap();
+        assert!(prompt.len() <= MAX_PREVIOUS_TEXT_CHARS);
+        assert!(prompt.starts_with("word"));
+    }
 }
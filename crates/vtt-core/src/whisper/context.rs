@@ -1,9 +1,17 @@
 //! Whisper context and transcription
 
-use crate::whisper::{Transcription, WhisperConfig, WhisperError, WhisperResult};
+use crate::audio::resample::resample_mono;
+use crate::whisper::{stitch, AudioChunk, LanguageProbability, Segment, Token, Transcription, WhisperConfig, WhisperError, WhisperResult};
 use std::path::Path;
 use std::sync::{Arc, Mutex};
 
+/// Audio language detection listens to: one Whisper window
+const LANGUAGE_DETECTION_SECS: usize = 30;
+
+/// A loaded Whisper model plus the settings used to transcribe with it
+///
+/// Cloning is cheap: clones share the loaded model.
+#[derive(Clone)]
 pub struct WhisperContext {
     context: Arc<Mutex<whisper_rs::WhisperContext>>,
     config: WhisperConfig,
@@ -39,33 +47,36 @@ impl WhisperContext {
         Self::new(WhisperConfig::default())
     }
 
-    pub fn transcribe(&self, audio_data: &[f32], sample_rate: u32) -> WhisperResult<Transcription> {
-        if audio_data.is_empty() {
-            return Err(WhisperError::InvalidAudio("Audio data is empty".to_string()));
-        }
-
-        if audio_data.len() < 100 {
-            return Err(WhisperError::InvalidAudio("Audio data too short".to_string()));
+    /// Share this context's loaded model under different transcription settings
+    ///
+    /// Model-level fields of `cfg` (`model_path`, `use_gpu`) are not applied;
+    /// they only take effect when a model is loaded.
+    pub fn with_config(&self, cfg: WhisperConfig) -> Self {
+        Self {
+            context: Arc::clone(&self.context),
+            config: cfg,
         }
+    }
 
-        if audio_data.iter().any(|v| !v.is_finite()) {
-            return Err(WhisperError::InvalidAudio("Audio data contains NaN or infinite values".to_string()));
-        }
+    pub fn transcribe(&self, audio_data: &[f32], sample_rate: u32) -> WhisperResult<Transcription> {
+        self.config.decoding.validate()?;
+        let processed_audio = self.prepare_audio(audio_data, sample_rate)?;
+        let (mut state, token_eot) = self.create_state()?;
 
-        let processed_audio = if sample_rate != self.config.required_sample_rate {
-            self.resample_audio(audio_data, sample_rate, self.config.required_sample_rate)?
+        let decoding = &self.config.decoding;
+        let strategy = if decoding.is_beam_search() {
+            whisper_rs::SamplingStrategy::BeamSearch { beam_size: decoding.beam_size as i32, patience: -1.0 }
         } else {
-            audio_data.to_vec()
+            whisper_rs::SamplingStrategy::Greedy { best_of: decoding.best_of as i32 }
         };
-
-        let ctx = self.context.lock()
-            .map_err(|e| WhisperError::ContextError(format!("Failed to lock context: {}", e)))?;
-
-        let mut state = ctx.create_state().map_err(|e| {
-            WhisperError::TranscriptionFailed(format!("Failed to create state: {}", e))
-        })?;
-
-        let mut params = whisper_rs::FullParams::new(whisper_rs::SamplingStrategy::Greedy { best_of: 0 });
+        let mut params = whisper_rs::FullParams::new(strategy);
+        params.set_temperature(decoding.temperature);
+        params.set_temperature_inc(decoding.temperature_increment);
+        params.set_entropy_thold(decoding.entropy_threshold);
+        params.set_logprob_thold(decoding.logprob_threshold);
+        params.set_no_speech_thold(decoding.no_speech_threshold);
+        params.set_suppress_blank(decoding.suppress_blank);
+        params.set_suppress_nst(decoding.suppress_non_speech);
         params.set_n_threads(self.config.n_threads);
         params.set_translate(self.config.translate);
 
@@ -79,6 +90,11 @@ impl WhisperContext {
             log::debug!("Using language: {:?}", lang);
         }
 
+        if let Some(prompt) = self.config.prompt() {
+            log::debug!("Using initial prompt: {:?}", prompt);
+            params.set_initial_prompt(&prompt);
+        }
+
         params.set_offset_ms(self.config.offset_ms);
         params.set_duration_ms(self.config.duration_ms);
         params.set_token_timestamps(true);
@@ -92,58 +108,172 @@ impl WhisperContext {
             WhisperError::TranscriptionFailed(format!("Full inference failed: {}", e))
         })?;
 
-        let mut text = String::new();
-        let mut start_timestamp = 0_i64;
-        let mut end_timestamp = 0_i64;
-
+        // whisper.cpp reports times in centiseconds
+        let mut segments = Vec::new();
         for segment in state.as_iter() {
-            let segment_text = segment.to_string();
-            let segment_start = segment.start_timestamp();
-            let segment_end = segment.end_timestamp();
-
-            if text.is_empty() {
-                start_timestamp = segment_start as i64;
+            let mut tokens = Vec::new();
+            for i in 0..segment.n_tokens() {
+                let Some(token) = segment.get_token(i) else {
+                    continue;
+                };
+                let data = token.token_data();
+                // Special and timestamp tokens sort after end-of-text
+                if data.id >= token_eot {
+                    continue;
+                }
+                tokens.push(Token {
+                    id: data.id,
+                    text: token.to_str_lossy().map(|t| t.into_owned()).unwrap_or_default(),
+                    start_ms: data.t0 * 10,
+                    end_ms: data.t1 * 10,
+                    probability: data.p,
+                });
             }
-            end_timestamp = segment_end as i64;
 
-            if !text.is_empty() && !segment_text.trim().is_empty() {
-                text.push(' ');
-            }
-            text.push_str(segment_text.trim());
+            segments.push(Segment {
+                start_ms: segment.start_timestamp() * 10,
+                end_ms: segment.end_timestamp() * 10,
+                text: segment.to_string().trim().to_string(),
+                no_speech_probability: segment.no_speech_probability(),
+                tokens,
+            });
         }
 
-        Ok(Transcription::new(text.trim().to_string(), start_timestamp, end_timestamp))
+        let mut transcription = Transcription::from_segments(segments);
+        transcription.language = whisper_rs::get_lang_str(state.full_lang_id_from_state()).map(str::to_string);
+        Ok(transcription)
     }
 
-    fn resample_audio(&self, audio_data: &[f32], from_sample_rate: u32, to_sample_rate: u32) -> WhisperResult<Vec<f32>> {
-        if from_sample_rate == to_sample_rate {
-            return Ok(audio_data.to_vec());
+    /// Transcribe long audio chunk by chunk, as planned by
+    /// [`plan_chunks`](crate::whisper::plan_chunks)
+    ///
+    /// Each chunk is prompted with the text before it, and under
+    /// auto-detect the language the first chunk was heard in is kept for
+    /// the rest. `on_chunk` is called with the number of chunks done and the
+    /// total after each one.
+    pub fn transcribe_chunks(
+        &self,
+        audio_data: &[f32],
+        sample_rate: u32,
+        chunks: &[AudioChunk],
+        mut on_chunk: impl FnMut(usize, usize),
+    ) -> WhisperResult<Transcription> {
+        let mut config = self.config.clone();
+        let mut done: Vec<(AudioChunk, Transcription)> = Vec::with_capacity(chunks.len());
+        for chunk in chunks {
+            let samples = audio_data.get(chunk.start_sample..chunk.end_sample).ok_or_else(|| {
+                WhisperError::InvalidParameter(format!(
+                    "Chunk {}..{} is outside the {} samples of audio",
+                    chunk.start_sample,
+                    chunk.end_sample,
+                    audio_data.len()
+                ))
+            })?;
+            let transcription = self.with_config(config.clone()).transcribe(samples, sample_rate)?;
+            if config.language.as_deref().is_none_or(|lang| lang == "auto") {
+                config.language = transcription.language.clone().or(config.language);
+            }
+            done.push((*chunk, transcription));
+            let text: Vec<&str> = done.iter().map(|(_, tx)| tx.text.as_str()).collect();
+            config = config.with_previous_text(Some(text.join(" ")));
+            on_chunk(done.len(), chunks.len());
         }
+        Ok(stitch(done, sample_rate))
+    }
 
-        let ratio = from_sample_rate as f32 / to_sample_rate as f32;
-        let output_length = ((audio_data.len() as f32) / ratio).ceil() as usize;
-        let mut resampled = vec![0.0_f32; output_length];
-
-        for i in 0..output_length {
-            let src_pos = (i as f32) * ratio;
-            let src_idx = src_pos.floor() as usize;
-            let frac = src_pos - src_pos.floor();
-
-            if src_idx + 1 < audio_data.len() {
-                let sample1 = audio_data[src_idx];
-                let sample2 = audio_data[src_idx + 1];
-                resampled[i] = sample1 * (1.0 - frac) + sample2 * frac;
-            } else if src_idx < audio_data.len() {
-                resampled[i] = audio_data[src_idx];
-            }
+    /// Detect the spoken language from the first 30 seconds of audio
+    ///
+    /// Returns the `top_n` most likely languages, most likely first. Any
+    /// language Whisper knows may be returned, not only
+    /// [`SUPPORTED_LANGUAGES`](crate::whisper::SUPPORTED_LANGUAGES).
+    ///
+    /// # Errors
+    ///
+    /// Returns `InvalidParameter` for an English-only model, which can't
+    /// tell languages apart.
+    pub fn detect_language(
+        &self,
+        audio_data: &[f32],
+        sample_rate: u32,
+        top_n: usize,
+    ) -> WhisperResult<Vec<LanguageProbability>> {
+        // Only the first window is read, so don't resample or compute the
+        // spectrogram of the rest
+        let window = |rate: u32| rate as usize * LANGUAGE_DETECTION_SECS;
+        let audio_data = &audio_data[..audio_data.len().min(window(sample_rate))];
+        let mut processed_audio = self.prepare_audio(audio_data, sample_rate)?;
+        processed_audio.truncate(window(self.config.required_sample_rate));
+        let multilingual = self.context.lock()
+            .map_err(|e| WhisperError::ContextError(format!("Failed to lock context: {}", e)))?
+            .is_multilingual();
+        if !multilingual {
+            return Err(WhisperError::InvalidParameter(format!(
+                "{} is an English-only model and can't detect languages",
+                self.config.model_path
+            )));
         }
 
-        Ok(resampled)
+        let (mut state, _) = self.create_state()?;
+        let threads = self.config.n_threads.max(1) as usize;
+        state.pcm_to_mel(&processed_audio, threads).map_err(|e| {
+            WhisperError::TranscriptionFailed(format!("Failed to compute spectrogram: {}", e))
+        })?;
+        let (_, probabilities) = state.lang_detect(0, threads).map_err(|e| {
+            WhisperError::TranscriptionFailed(format!("Language detection failed: {}", e))
+        })?;
+
+        let mut languages: Vec<LanguageProbability> = probabilities
+            .iter()
+            .enumerate()
+            .filter_map(|(id, &probability)| {
+                let code = whisper_rs::get_lang_str(id as i32)?;
+                let name = whisper_rs::get_lang_str_full(id as i32).unwrap_or(code);
+                Some(LanguageProbability::new(code, name, probability))
+            })
+            .collect();
+        languages.sort_by(|a, b| b.probability.total_cmp(&a.probability));
+        languages.truncate(top_n);
+        Ok(languages)
     }
 
     pub fn config(&self) -> &WhisperConfig {
         &self.config
     }
+
+    /// Check the audio and resample it to the model's rate
+    fn prepare_audio(&self, audio_data: &[f32], sample_rate: u32) -> WhisperResult<Vec<f32>> {
+        if audio_data.is_empty() {
+            return Err(WhisperError::InvalidAudio("Audio data is empty".to_string()));
+        }
+
+        if audio_data.len() < 100 {
+            return Err(WhisperError::InvalidAudio("Audio data too short".to_string()));
+        }
+
+        if audio_data.iter().any(|v| !v.is_finite()) {
+            return Err(WhisperError::InvalidAudio("Audio data contains NaN or infinite values".to_string()));
+        }
+
+        Ok(if sample_rate != self.config.required_sample_rate {
+            resample_mono(audio_data, sample_rate, self.config.required_sample_rate)
+        } else {
+            audio_data.to_vec()
+        })
+    }
+
+    /// A fresh decoding state and the model's end-of-text token
+    ///
+    /// States keep the model alive on their own, so the lock is only
+    /// needed while creating one and sessions can run concurrently.
+    fn create_state(&self) -> WhisperResult<(whisper_rs::WhisperState, i32)> {
+        let ctx = self.context.lock()
+            .map_err(|e| WhisperError::ContextError(format!("Failed to lock context: {}", e)))?;
+
+        let state = ctx.create_state().map_err(|e| {
+            WhisperError::TranscriptionFailed(format!("Failed to create state: {}", e))
+        })?;
+        Ok((state, ctx.token_eot()))
+    }
 }
 
 pub fn check_model_available(cfg: &WhisperConfig) -> Result<(), WhisperError> {
@@ -166,5 +296,48 @@ mod tests {
         assert_eq!(t.start_timestamp, 0);
         assert_eq!(t.end_timestamp, 1000);
         assert_eq!(t.duration_ms(), 1000);
+        assert!(t.segments.is_empty());
+        assert_eq!(t.confidence(), None);
+    }
+
+    #[test]
+    fn test_transcription_from_segments() {
+        let token = |text: &str, start_ms, probability| Token {
+            id: 1,
+            text: text.to_string(),
+            start_ms,
+            end_ms: start_ms + 200,
+            probability,
+        };
+        let segments = vec![
+            Segment {
+                start_ms: 0,
+                end_ms: 1200,
+                text: "Hello".to_string(),
+                no_speech_probability: 0.01,
+                tokens: vec![token(" Hello", 0, 0.9)],
+            },
+            Segment {
+                start_ms: 1200,
+                end_ms: 1500,
+                text: String::new(),
+                no_speech_probability: 0.9,
+                tokens: Vec::new(),
+            },
+            Segment {
+                start_ms: 1500,
+                end_ms: 2500,
+                text: "world".to_string(),
+                no_speech_probability: 0.02,
+                tokens: vec![token(" world", 1500, 0.7)],
+            },
+        ];
+
+        let t = Transcription::from_segments(segments);
+        assert_eq!(t.text, "Hello world");
+        assert_eq!(t.start_timestamp, 0);
+        assert_eq!(t.end_timestamp, 2500);
+        assert_eq!(t.segments.len(), 3);
+        assert!((t.confidence().unwrap() - 0.8).abs() < 1e-6);
     }
 }
@@ -0,0 +1,291 @@
+//! Decoding controls
+//!
+//! Whisper picks each token greedily or with beam search. When a decoded
+//! segment looks wrong (its text is too repetitive or its average log
+//! probability too low) the segment is decoded again at a higher temperature.
+//! [`DecodingOptions`] holds those knobs, [`DecodingPreset`] names the usual
+//! trade-offs between latency and accuracy, and [`DecodingOverrides`] layers
+//! a preset and individual settings from a config file or tool call on top
+//! of a base.
+
+use super::error::{WhisperError, WhisperResult};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::str::FromStr;
+
+/// Most beams, or candidates, whisper.cpp decodes at once
+/// (`WHISPER_MAX_DECODERS`); it refuses to run with more
+pub const MAX_DECODERS: u32 = 8;
+
+/// Named decoding settings
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum DecodingPreset {
+    /// Greedy, one candidate, no temperature fallback; lowest latency, for
+    /// live captions
+    Fast,
+    /// whisper.cpp's defaults: greedy with temperature fallback
+    #[default]
+    Balanced,
+    /// Beam search over 5 beams with temperature fallback; several times
+    /// slower, for files and final passes
+    Accurate,
+}
+
+impl DecodingPreset {
+    /// Name used in config files and tool parameters
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Fast => "fast",
+            Self::Balanced => "balanced",
+            Self::Accurate => "accurate",
+        }
+    }
+
+    /// Settings this preset stands for
+    pub fn options(self) -> DecodingOptions {
+        let balanced = DecodingOptions {
+            beam_size: 1,
+            best_of: 5,
+            temperature: 0.0,
+            temperature_increment: 0.2,
+            entropy_threshold: 2.4,
+            logprob_threshold: -1.0,
+            no_speech_threshold: 0.6,
+            suppress_blank: true,
+            suppress_non_speech: false,
+        };
+        match self {
+            Self::Fast => DecodingOptions { best_of: 1, temperature_increment: 0.0, ..balanced },
+            Self::Balanced => balanced,
+            Self::Accurate => DecodingOptions { beam_size: 5, suppress_non_speech: true, ..balanced },
+        }
+    }
+}
+
+impl FromStr for DecodingPreset {
+    type Err = WhisperError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "fast" => Ok(Self::Fast),
+            "balanced" => Ok(Self::Balanced),
+            "accurate" => Ok(Self::Accurate),
+            _ => Err(WhisperError::InvalidParameter(format!(
+                "Unknown decoding preset '{}' (expected fast, balanced or accurate)",
+                s
+            ))),
+        }
+    }
+}
+
+impl fmt::Display for DecodingPreset {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// How Whisper decodes tokens
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DecodingOptions {
+    /// Beams kept during beam search; 1 decodes greedily
+    pub beam_size: u32,
+
+    /// Candidates sampled per greedy decode when the temperature is above
+    /// zero; the most likely one is kept
+    pub best_of: u32,
+
+    /// Sampling temperature of the first attempt; 0 always picks the most
+    /// likely token
+    pub temperature: f32,
+
+    /// Added to the temperature each time a segment fails the thresholds
+    /// below and is decoded again; 0 disables the fallback
+    pub temperature_increment: f32,
+
+    /// Segments whose token entropy falls below this are treated as
+    /// repetition loops and decoded again
+    pub entropy_threshold: f32,
+
+    /// Segments whose average token log probability falls below this are
+    /// decoded again
+    pub logprob_threshold: f32,
+
+    /// Segments whose no-speech probability exceeds this, and that also
+    /// fail the log probability threshold, are treated as silence
+    pub no_speech_threshold: f32,
+
+    /// Don't start a segment with a blank
+    pub suppress_blank: bool,
+
+    /// Suppress non-speech tokens such as music notes and bracketed sound
+    /// descriptions
+    pub suppress_non_speech: bool,
+}
+
+impl Default for DecodingOptions {
+    fn default() -> Self {
+        DecodingPreset::default().options()
+    }
+}
+
+impl DecodingOptions {
+    /// Whether these options use beam search rather than greedy decoding
+    pub fn is_beam_search(&self) -> bool {
+        self.beam_size > 1
+    }
+
+    /// Check the settings are in range
+    ///
+    /// # Errors
+    ///
+    /// Returns `InvalidParameter` naming the first setting out of range.
+    pub fn validate(&self) -> WhisperResult<()> {
+        let invalid = |message: &str| Err(WhisperError::InvalidParameter(message.to_string()));
+        if !(1..=MAX_DECODERS).contains(&self.beam_size) {
+            return invalid("beam_size must be between 1 and 8");
+        }
+        if !(1..=MAX_DECODERS).contains(&self.best_of) {
+            return invalid("best_of must be between 1 and 8");
+        }
+        if !(0.0..=1.0).contains(&self.temperature) {
+            return invalid("temperature must be between 0 and 1");
+        }
+        if !(0.0..=1.0).contains(&self.temperature_increment) {
+            return invalid("temperature_increment must be between 0 and 1");
+        }
+        if !self.entropy_threshold.is_finite() || !self.logprob_threshold.is_finite() {
+            return invalid("entropy_threshold and logprob_threshold must be finite");
+        }
+        if !(0.0..=1.0).contains(&self.no_speech_threshold) {
+            return invalid("no_speech_threshold must be between 0 and 1");
+        }
+        Ok(())
+    }
+}
+
+/// A preset and individual settings to apply over some base options
+///
+/// Unset fields keep the base value. A preset replaces the base entirely
+/// before the individual settings are applied.
+///
+/// The field docs double as the descriptions in the tools' JSON schema.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(default)]
+pub struct DecodingOverrides {
+    /// "fast" (greedy, no fallback), "balanced" or "accurate" (beam search);
+    /// replaces the base settings
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub preset: Option<DecodingPreset>,
+    /// Beams for beam search (1-8); 1 decodes greedily
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub beam_size: Option<u32>,
+    /// Candidates sampled per greedy decode above temperature 0 (1-8)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub best_of: Option<u32>,
+    /// Temperature of the first attempt (0-1)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub temperature: Option<f32>,
+    /// Temperature added on each retry of a failed segment; 0 disables retries
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub temperature_increment: Option<f32>,
+    /// Retry segments whose token entropy is below this (repetition loops)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub entropy_threshold: Option<f32>,
+    /// Retry segments whose average token log probability is below this
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub logprob_threshold: Option<f32>,
+    /// Treat low-confidence segments above this no-speech probability as silence
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub no_speech_threshold: Option<f32>,
+    /// Don't start a segment with a blank
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub suppress_blank: Option<bool>,
+    /// Suppress non-speech tokens such as [music] or sound descriptions
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub suppress_non_speech: Option<bool>,
+}
+
+impl DecodingOverrides {
+    /// `base` with these overrides applied
+    pub fn apply_to(&self, base: DecodingOptions) -> DecodingOptions {
+        let base = self.preset.map_or(base, DecodingPreset::options);
+        DecodingOptions {
+            beam_size: self.beam_size.unwrap_or(base.beam_size),
+            best_of: self.best_of.unwrap_or(base.best_of),
+            temperature: self.temperature.unwrap_or(base.temperature),
+            temperature_increment: self.temperature_increment.unwrap_or(base.temperature_increment),
+            entropy_threshold: self.entropy_threshold.unwrap_or(base.entropy_threshold),
+            logprob_threshold: self.logprob_threshold.unwrap_or(base.logprob_threshold),
+            no_speech_threshold: self.no_speech_threshold.unwrap_or(base.no_speech_threshold),
+            suppress_blank: self.suppress_blank.unwrap_or(base.suppress_blank),
+            suppress_non_speech: self.suppress_non_speech.unwrap_or(base.suppress_non_speech),
+        }
+    }
+
+    /// The defaults with these overrides applied
+    pub fn resolve(&self) -> DecodingOptions {
+        self.apply_to(DecodingOptions::default())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_presets() {
+        let fast = DecodingPreset::Fast.options();
+        assert!(!fast.is_beam_search());
+        assert_eq!(fast.temperature_increment, 0.0);
+        let accurate = DecodingPreset::Accurate.options();
+        assert!(accurate.is_beam_search());
+        assert_eq!(accurate.beam_size, 5);
+        assert_eq!(DecodingOptions::default(), DecodingPreset::Balanced.options());
+
+        assert_eq!("Accurate".parse::<DecodingPreset>().unwrap(), DecodingPreset::Accurate);
+        assert!("slow".parse::<DecodingPreset>().is_err());
+        assert_eq!(DecodingPreset::Fast.to_string(), "fast");
+        for preset in [DecodingPreset::Fast, DecodingPreset::Balanced, DecodingPreset::Accurate] {
+            assert!(preset.options().validate().is_ok());
+        }
+    }
+
+    #[test]
+    fn test_overrides_layer_over_base() {
+        let config = DecodingOverrides { preset: Some(DecodingPreset::Accurate), beam_size: Some(8), ..Default::default() };
+        let options = config.resolve();
+        assert_eq!(options.beam_size, 8);
+        assert!(options.suppress_non_speech);
+
+        // Settings without a preset keep the rest of the base
+        let call = DecodingOverrides { temperature_increment: Some(0.0), ..Default::default() };
+        let options = call.apply_to(options);
+        assert_eq!(options.beam_size, 8);
+        assert_eq!(options.temperature_increment, 0.0);
+
+        // A preset starts over
+        let call = DecodingOverrides { preset: Some(DecodingPreset::Fast), ..Default::default() };
+        assert_eq!(call.apply_to(options), DecodingPreset::Fast.options());
+    }
+
+    #[test]
+    fn test_validate() {
+        let invalid = [
+            DecodingOverrides { beam_size: Some(0), ..Default::default() },
+            DecodingOverrides { beam_size: Some(MAX_DECODERS + 1), ..Default::default() },
+            DecodingOverrides { best_of: Some(0), ..Default::default() },
+            DecodingOverrides { best_of: Some(MAX_DECODERS + 1), ..Default::default() },
+            DecodingOverrides { temperature: Some(-0.1), ..Default::default() },
+            DecodingOverrides { temperature_increment: Some(1.5), ..Default::default() },
+            DecodingOverrides { logprob_threshold: Some(f32::NAN), ..Default::default() },
+            DecodingOverrides { no_speech_threshold: Some(2.0), ..Default::default() },
+        ];
+        for overrides in invalid {
+            assert!(matches!(overrides.resolve().validate(), Err(WhisperError::InvalidParameter(_))), "{:?}", overrides);
+        }
+        let widest = DecodingOverrides { beam_size: Some(MAX_DECODERS), best_of: Some(MAX_DECODERS), ..Default::default() };
+        assert!(widest.resolve().validate().is_ok());
+    }
+}
@@ -1,5 +1,6 @@
 //! Error types for Whisper operations
 
+use serde::Serialize;
 use std::path::PathBuf;
 
 /// Errors that can occur during Whisper operations
@@ -33,11 +34,50 @@ pub enum WhisperError {
     /// Invalid parameter
     #[error("Invalid parameter: {0}")]
     InvalidParameter(String),
+
+    /// The model already serves as many sessions as it is allowed to
+    #[error("Model {path} is busy: {max_sessions} sessions already active")]
+    TooManySessions {
+        /// Path to the model file
+        path: PathBuf,
+        /// Configured session limit
+        max_sessions: usize,
+    },
 }
 
 /// Type alias for Whisper operation results
 pub type WhisperResult<T> = Result<T, WhisperError>;
 
+/// One decoded token with its timing
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Token {
+    /// Vocabulary id of the token
+    pub id: i32,
+    /// Token text, including any leading space
+    pub text: String,
+    /// Start timestamp in milliseconds
+    pub start_ms: i64,
+    /// End timestamp in milliseconds
+    pub end_ms: i64,
+    /// Probability the model assigned to this token
+    pub probability: f32,
+}
+
+/// A contiguous span of transcribed speech
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Segment {
+    /// Start timestamp in milliseconds
+    pub start_ms: i64,
+    /// End timestamp in milliseconds
+    pub end_ms: i64,
+    /// Segment text, trimmed
+    pub text: String,
+    /// Probability that the segment contains no speech
+    pub no_speech_probability: f32,
+    /// Text tokens of the segment; special and timestamp tokens are left out
+    pub tokens: Vec<Token>,
+}
+
 /// Transcription result from Whisper
 #[derive(Debug, Clone)]
 pub struct Transcription {
@@ -47,6 +87,11 @@ pub struct Transcription {
     pub start_timestamp: i64,
     /// End timestamp in milliseconds
     pub end_timestamp: i64,
+    /// Segments in recording order
+    pub segments: Vec<Segment>,
+    /// Code of the language Whisper transcribed in, e.g. "en"; the
+    /// detected one when auto-detecting
+    pub language: Option<String>,
 }
 
 impl Transcription {
@@ -55,10 +100,48 @@ impl Transcription {
             text,
             start_timestamp,
             end_timestamp,
+            segments: Vec::new(),
+            language: None,
+        }
+    }
+
+    /// Build a transcription from its segments
+    ///
+    /// The text joins the non-empty segment texts with spaces and the
+    /// timestamps span the first to the last segment.
+    pub fn from_segments(segments: Vec<Segment>) -> Self {
+        let text = segments
+            .iter()
+            .map(|s| s.text.as_str())
+            .filter(|t| !t.is_empty())
+            .collect::<Vec<_>>()
+            .join(" ");
+        let start_timestamp = segments.first().map_or(0, |s| s.start_ms);
+        let end_timestamp = segments.last().map_or(0, |s| s.end_ms);
+
+        Self {
+            text,
+            start_timestamp,
+            end_timestamp,
+            segments,
+            language: None,
         }
     }
 
     pub fn duration_ms(&self) -> i64 {
         self.end_timestamp - self.start_timestamp
     }
+
+    /// Mean token probability, or `None` if there are no tokens
+    pub fn confidence(&self) -> Option<f32> {
+        let probabilities: Vec<f32> = self
+            .segments
+            .iter()
+            .flat_map(|s| s.tokens.iter().map(|t| t.probability))
+            .collect();
+        if probabilities.is_empty() {
+            return None;
+        }
+        Some(probabilities.iter().sum::<f32>() / probabilities.len() as f32)
+    }
 }
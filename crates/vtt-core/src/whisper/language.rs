@@ -18,6 +18,23 @@ impl Language {
     }
 }
 
+/// A detected language and how likely it is
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LanguageProbability {
+    pub language: Language,
+    /// Probability between 0 and 1
+    pub probability: f32,
+}
+
+impl LanguageProbability {
+    /// Languages in [`SUPPORTED_LANGUAGES`] get their display name; others
+    /// keep `name`, whisper.cpp's lowercase one
+    pub fn new(code: &'static str, name: &'static str, probability: f32) -> Self {
+        let language = Language::from_code(code).unwrap_or(Language::new(code, name));
+        Self { language, probability }
+    }
+}
+
 pub const SUPPORTED_LANGUAGES: &[Language] = &[
     Language::new("en", "English"), 
     Language::new("es", "Spanish"),
@@ -47,3 +64,17 @@ pub fn display_name(code: &str) -> String {
         .map(|l| l.name.to_string())
         .unwrap_or_else(|| format!("Unknown ({})", code))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_language_probability_names() {
+        let german = LanguageProbability::new("de", "german", 0.8);
+        assert_eq!(german.language, Language::new("de", "German"));
+        let swahili = LanguageProbability::new("sw", "swahili", 0.1);
+        assert_eq!(swahili.language.name, "swahili");
+        assert!(!Language::is_valid("sw"));
+    }
+}
@@ -1,5 +1,7 @@
-pub mod config; pub mod context; pub mod error; pub mod gpu; pub mod downloader; pub mod language;
-pub use config::WhisperConfig; pub use context::WhisperContext; pub use error::{WhisperError, WhisperResult, Transcription};
+pub mod chunking; pub mod config; pub mod context; pub mod decoding; pub mod error; pub mod gpu; pub mod downloader; pub mod language; pub mod memory; pub mod pool;
+pub use chunking::{plan_chunks, stitch, AudioChunk, ChunkConfig}; pub use config::{Task, WhisperConfig}; pub use context::WhisperContext; pub use decoding::{DecodingOptions, DecodingOverrides, DecodingPreset}; pub use error::{WhisperError, WhisperResult, Segment, Token, Transcription};
 pub use gpu::{GpuBackend, GpuDetection, GpuDeviceInfo, detect_gpu, get_gpu_info, is_gpu_available, get_gpu_message};
 pub use downloader::{ModelDownloadConfig, download_model, ensure_model, needs_download};
-pub use language::{Language, SUPPORTED_LANGUAGES, AUTO_DETECT, supported_codes, display_name};
+pub use language::{Language, LanguageProbability, SUPPORTED_LANGUAGES, AUTO_DETECT, supported_codes, display_name};
+pub use memory::{MemoryStats, MemoryTracker};
+pub use pool::{ModelKey, ModelLease, ModelPool};
@@ -0,0 +1,272 @@
+//! Shared pool of loaded Whisper models
+//!
+//! Loading a model reads hundreds of MB from disk, so each model is loaded
+//! once per (model path, GPU flag) and handed out as a [`ModelLease`].
+//! Leases are counted with a [`MemoryTracker`]; a model that has had no
+//! leases for longer than its `idle_timeout_secs` is unloaded. Callers
+//! asking for a model that is still loading wait for that load rather than
+//! starting their own.
+
+use crate::whisper::memory::{MemoryStats, MemoryTracker};
+use crate::whisper::{WhisperConfig, WhisperContext, WhisperError, WhisperResult};
+use std::collections::{HashMap, HashSet};
+use std::ops::Deref;
+use std::path::PathBuf;
+use std::sync::{Arc, Condvar, Mutex};
+use std::time::Duration;
+
+/// Identifies one loaded model in the pool
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ModelKey {
+    /// Path to the model file
+    pub model_path: String,
+    /// Whether the model was loaded with GPU acceleration
+    pub use_gpu: bool,
+}
+
+impl From<&WhisperConfig> for ModelKey {
+    fn from(cfg: &WhisperConfig) -> Self {
+        Self {
+            model_path: cfg.model_path.clone(),
+            use_gpu: cfg.use_gpu,
+        }
+    }
+}
+
+struct PooledModel {
+    context: WhisperContext,
+    tracker: Arc<MemoryTracker>,
+    idle_timeout_secs: Option<u64>,
+}
+
+/// Pool of shared Whisper models
+///
+/// Cloning the pool is cheap; clones share the same loaded models.
+#[derive(Clone, Default)]
+pub struct ModelPool {
+    models: Arc<Mutex<HashMap<ModelKey, PooledModel>>>,
+    /// Models an `acquire` is looking up or loading, and the condition
+    /// signalled when one is done
+    loading: Arc<(Mutex<HashSet<ModelKey>>, Condvar)>,
+}
+
+/// Held by the one caller looking up or loading a model; others asking for
+/// the same model wait until it is dropped
+struct LoadClaim<'a> {
+    pool: &'a ModelPool,
+    key: ModelKey,
+}
+
+impl Drop for LoadClaim<'_> {
+    fn drop(&mut self) {
+        let (loading, done) = &*self.pool.loading;
+        loading.lock().unwrap().remove(&self.key);
+        done.notify_all();
+    }
+}
+
+impl ModelPool {
+    /// Create an empty pool
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Get a lease on the model described by `cfg`, loading it if needed
+    ///
+    /// The lease transcribes with `cfg`'s settings. This blocks while a model
+    /// is being loaded, by this call or a concurrent one for the same model,
+    /// so call it from a blocking context.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the model cannot be loaded, or
+    /// [`WhisperError::TooManySessions`] if it already has `cfg.max_sessions`
+    /// leases out.
+    pub fn acquire(&self, cfg: WhisperConfig) -> WhisperResult<ModelLease> {
+        self.evict_idle();
+
+        let key = ModelKey::from(&cfg);
+        // A model is loaded by one caller at a time; the others wait here
+        // and then find it loaded
+        let _claim = self.claim(&key);
+        if let Some(lease) = self.try_lease(&key, &cfg)? {
+            return Ok(lease);
+        }
+
+        // Load without holding the pool lock so other models stay usable
+        log::info!("Loading Whisper model {} (gpu: {})", key.model_path, key.use_gpu);
+        let context = WhisperContext::new(cfg.clone())?;
+        let model_size = std::fs::metadata(&key.model_path).map(|m| m.len()).unwrap_or(0);
+
+        self.models.lock().unwrap().insert(key.clone(), PooledModel {
+            context,
+            tracker: Arc::new(MemoryTracker::new(model_size)),
+            idle_timeout_secs: cfg.idle_timeout_secs,
+        });
+
+        self.try_lease(&key, &cfg)?
+            .ok_or_else(|| WhisperError::ContextError(format!("Model {} vanished from pool", key.model_path)))
+    }
+
+    /// Wait until nobody else is looking up or loading `key`, then claim it
+    fn claim(&self, key: &ModelKey) -> LoadClaim<'_> {
+        let (loading, done) = &*self.loading;
+        let mut loading = done.wait_while(loading.lock().unwrap(), |loading| loading.contains(key)).unwrap();
+        loading.insert(key.clone());
+        LoadClaim { pool: self, key: key.clone() }
+    }
+
+    fn try_lease(&self, key: &ModelKey, cfg: &WhisperConfig) -> WhisperResult<Option<ModelLease>> {
+        let mut models = self.models.lock().unwrap();
+        let Some(model) = models.get_mut(key) else {
+            return Ok(None);
+        };
+
+        if model.tracker.ref_count() >= cfg.max_sessions {
+            return Err(WhisperError::TooManySessions {
+                path: PathBuf::from(&key.model_path),
+                max_sessions: cfg.max_sessions,
+            });
+        }
+
+        model.idle_timeout_secs = cfg.idle_timeout_secs;
+        model.tracker.inc_ref();
+        Ok(Some(ModelLease {
+            context: model.context.with_config(cfg.clone()),
+            tracker: Arc::clone(&model.tracker),
+        }))
+    }
+
+    /// Unload models that have been unused for longer than their idle timeout
+    ///
+    /// Returns the number of models unloaded.
+    pub fn evict_idle(&self) -> usize {
+        let mut models = self.models.lock().unwrap();
+        let before = models.len();
+        models.retain(|key, model| {
+            let unload = model.tracker.should_unload(model.idle_timeout_secs);
+            if unload {
+                log::info!("Unloading idle Whisper model {}", key.model_path);
+            }
+            !unload
+        });
+        before - models.len()
+    }
+
+    /// Keys of all currently loaded models
+    pub fn loaded_models(&self) -> Vec<ModelKey> {
+        self.models.lock().unwrap().keys().cloned().collect()
+    }
+
+    /// Usage statistics for a loaded model
+    pub fn stats(&self, key: &ModelKey) -> Option<MemoryStats> {
+        self.models.lock().unwrap().get(key).map(|m| m.tracker.get_stats())
+    }
+
+    /// Periodically unload idle models in the background
+    pub fn spawn_reaper(&self, period: Duration) -> tokio::task::JoinHandle<()> {
+        let pool = self.clone();
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(period);
+            loop {
+                ticker.tick().await;
+                pool.evict_idle();
+            }
+        })
+    }
+}
+
+/// A shared model checked out from a [`ModelPool`]
+///
+/// Dereferences to the [`WhisperContext`]; the pool's lease count drops when
+/// this is dropped.
+pub struct ModelLease {
+    context: WhisperContext,
+    tracker: Arc<MemoryTracker>,
+}
+
+impl ModelLease {
+    /// The leased context, configured with the settings it was acquired with
+    pub fn context(&self) -> &WhisperContext {
+        &self.context
+    }
+}
+
+impl std::fmt::Debug for ModelLease {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ModelLease")
+            .field("model_path", &self.context.config().model_path)
+            .field("tracker", &self.tracker)
+            .finish()
+    }
+}
+
+impl Deref for ModelLease {
+    type Target = WhisperContext;
+
+    fn deref(&self) -> &Self::Target {
+        &self.context
+    }
+}
+
+impl Drop for ModelLease {
+    fn drop(&mut self) {
+        self.tracker.dec_ref();
+        // Idle time counts from the last release, not the last acquire
+        self.tracker.update_activity();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_model_key_from_config() {
+        let a = WhisperConfig::default().with_model_path("a.bin").with_gpu(false);
+        let b = WhisperConfig::default().with_model_path("a.bin").with_gpu(true);
+        assert_eq!(ModelKey::from(&a), ModelKey::from(&a.clone().with_language("de")));
+        assert_ne!(ModelKey::from(&a), ModelKey::from(&b));
+    }
+
+    #[test]
+    fn test_acquire_missing_model() {
+        let pool = ModelPool::new();
+        let cfg = WhisperConfig::default().with_model_path("/nonexistent/ggml-none.bin");
+        let result = pool.acquire(cfg.clone());
+        assert!(matches!(result, Err(WhisperError::ModelNotFound(_))));
+        assert!(pool.loaded_models().is_empty());
+        assert!(pool.stats(&ModelKey::from(&cfg)).is_none());
+    }
+
+    #[test]
+    fn test_concurrent_loads_wait() {
+        use std::sync::mpsc;
+
+        let pool = ModelPool::new();
+        let key = ModelKey::from(&WhisperConfig::default().with_model_path("a.bin"));
+        let loading = pool.claim(&key);
+
+        let (tx, rx) = mpsc::channel();
+        let waiter = {
+            let (pool, key) = (pool.clone(), key.clone());
+            std::thread::spawn(move || {
+                let _claim = pool.claim(&key);
+                tx.send(()).unwrap();
+            })
+        };
+        // Other models aren't held up
+        drop(pool.claim(&ModelKey { use_gpu: !key.use_gpu, ..key.clone() }));
+        assert!(rx.recv_timeout(Duration::from_millis(100)).is_err());
+
+        drop(loading);
+        rx.recv_timeout(Duration::from_secs(5)).unwrap();
+        waiter.join().unwrap();
+    }
+
+    #[test]
+    fn test_evict_idle_empty_pool() {
+        let pool = ModelPool::new();
+        assert_eq!(pool.evict_idle(), 0);
+    }
+}
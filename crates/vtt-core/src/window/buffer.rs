@@ -73,6 +73,11 @@ impl WindowInner {
             capacity,
         }
     }
+
+    /// Position of the oldest sample in the buffer
+    fn read_pos(&self) -> usize {
+        (self.write_pos + self.capacity - self.len) % self.capacity.max(1)
+    }
 }
 
 /// A sliding window buffer for audio samples
@@ -164,11 +169,7 @@ impl SlidingWindow {
         }
         
         let mut result = Vec::with_capacity(len);
-        let read_pos = if inner.len == inner.capacity {
-            inner.write_pos
-        } else {
-            0
-        };
+        let read_pos = inner.read_pos();
         
         for i in 0..len {
             result.push(inner.buffer[(read_pos + i) % inner.capacity]);
@@ -188,11 +189,7 @@ impl SlidingWindow {
         let mut result = Vec::with_capacity(available);
         
         // Calculate read position
-        let read_pos = if inner.len == inner.capacity {
-            inner.write_pos
-        } else {
-            0
-        };
+        let read_pos = inner.read_pos();
         
         // Read the last 'available' samples
         let start_offset = inner.len - available;
@@ -204,6 +201,27 @@ impl SlidingWindow {
         Ok(result)
     }
     
+    /// Number of samples currently in the window
+    pub async fn len(&self) -> usize {
+        self.inner.lock().await.len
+    }
+
+    /// Whether the window holds no samples
+    pub async fn is_empty(&self) -> bool {
+        self.len().await == 0
+    }
+
+    /// Drop the oldest `count` samples, e.g. audio that has already been
+    /// transcribed for good
+    ///
+    /// Returns how many samples were dropped.
+    pub async fn trim_front(&self, count: usize) -> usize {
+        let mut inner = self.inner.lock().await;
+        let count = count.min(inner.len);
+        inner.len -= count;
+        count
+    }
+
     /// Get the current duration of audio in the window (in seconds)
     pub async fn duration_secs(&self) -> f32 {
         let inner = self.inner.lock().await;
@@ -266,6 +284,26 @@ mod tests {
         assert!(matches!(result, Err(WindowError::BufferOverflow { .. })));
     }
     
+    #[tokio::test]
+    async fn test_trim_front() {
+        let window = SlidingWindow::with_config(WindowConfig::new(1.0, 100));
+        let samples: Vec<f32> = (0..150).map(|i| i as f32).collect();
+        let _ = window.push(&samples).await;
+
+        assert_eq!(window.trim_front(30).await, 30);
+        assert_eq!(window.len().await, 70);
+        let all = window.get_all().await;
+        assert_eq!(all[0], 80.0);
+        assert_eq!(all[69], 149.0);
+
+        window.push(&[150.0, 151.0]).await.unwrap();
+        assert_eq!(window.get_last_seconds(0.02).await.unwrap(), vec![150.0, 151.0]);
+        assert_eq!(window.get_all().await[0], 80.0);
+
+        assert_eq!(window.trim_front(500).await, 72);
+        assert!(window.is_empty().await);
+    }
+
     #[tokio::test]
     async fn test_duration() {
         let window = SlidingWindow::with_config(WindowConfig::new(2.0, 100));
@@ -29,7 +29,7 @@ fn test_capture_and_save() {
     let samples = capture.take_buffer();
     assert!(!samples.is_empty(), "No audio captured");
 
-    write_wav("/tmp/test_capture.wav", &samples, capture.format()).expect("Failed to write WAV");
+    write_wav("/tmp/test_capture.wav", &samples, &capture.format()).expect("Failed to write WAV");
 
     println!("Captured {} samples", samples.len());
 }
@@ -32,6 +32,10 @@ pub enum VttError {
     #[error("Device not found: {0}")]
     DeviceNotFound(String),
 
+    /// Voice activity detection error
+    #[error("VAD error: {0}")]
+    Vad(#[from] vtt_core::vad::VadError),
+
     /// Session error
     #[error("Session error: {0}")]
     Session(String),
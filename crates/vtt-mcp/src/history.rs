@@ -0,0 +1,384 @@
+//! Transcription history
+//!
+//! Finished transcriptions are kept newest-first in memory and, when
+//! persistence is enabled, appended to a JSON Lines file so they survive
+//! restarts. Deletions and retention pruning rewrite the file.
+
+use crate::server::TranscriptionResult;
+use chrono::{DateTime, Utc};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
+use uuid::Uuid;
+use vtt_core::config::schema::HistoryConfig;
+
+/// Where the audio of a history entry came from
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum HistorySource {
+    /// A listening session on an input device
+    Microphone {
+        /// Device the session captured from; `None` for the system default
+        device: Option<String>,
+    },
+    /// An audio file passed to `transcribe_clip`
+    File {
+        /// Path of the file as given by the client
+        path: String,
+    },
+}
+
+/// One stored transcription
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct HistoryRecord {
+    /// Session (or clip) id the transcription belongs to
+    pub session_id: Uuid,
+    /// When capture or decoding started
+    pub started_at: DateTime<Utc>,
+    /// When the transcription finished
+    pub finished_at: DateTime<Utc>,
+    /// Model file used
+    pub model: String,
+    /// Requested language; `None` means auto-detect
+    pub language: Option<String>,
+    /// Microphone or file
+    pub source: HistorySource,
+    /// Text, confidence and segments
+    pub transcription: TranscriptionResult,
+}
+
+/// Filters and paging for [`HistoryStore::query`]
+#[derive(Debug, Clone, Default)]
+pub struct HistoryQuery {
+    /// Case-insensitive words that must all appear in the text
+    pub text: Option<String>,
+    /// Only entries finished at or after this time
+    pub since: Option<DateTime<Utc>>,
+    /// Only entries finished before this time
+    pub until: Option<DateTime<Utc>>,
+    /// Matching entries to skip
+    pub offset: usize,
+    /// Maximum entries to return
+    pub limit: usize,
+}
+
+/// One page of matching entries
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+pub struct HistoryPage {
+    /// Number of entries matching the filters, across all pages
+    pub total: usize,
+    /// Offset of the first returned entry
+    pub offset: usize,
+    /// Matching entries, newest first
+    pub entries: Vec<HistoryRecord>,
+}
+
+/// Transcription history, optionally backed by a JSONL file
+#[derive(Debug, Default)]
+pub struct HistoryStore {
+    /// Newest first
+    records: Vec<HistoryRecord>,
+    path: Option<PathBuf>,
+    max_entries: usize,
+    max_age_days: u32,
+}
+
+impl HistoryStore {
+    /// History that is never written to disk
+    pub fn in_memory(config: &HistoryConfig) -> Self {
+        Self {
+            records: Vec::new(),
+            path: None,
+            max_entries: config.max_entries,
+            max_age_days: config.max_age_days,
+        }
+    }
+
+    /// Open the history described by `config`
+    ///
+    /// Existing entries are loaded and pruned to the retention limits.
+    /// Lines that can't be parsed are skipped with a warning.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the history file exists but can't be read, or
+    /// its directory can't be created.
+    pub fn open(config: &HistoryConfig) -> io::Result<Self> {
+        let mut store = Self::in_memory(config);
+        if !config.persist {
+            return Ok(store);
+        }
+        let path = config.resolved_path()
+            .ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, "no data directory for history"))?;
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        store.records = load(&path)?;
+        store.records.sort_by_key(|r| std::cmp::Reverse(r.finished_at));
+        store.path = Some(path);
+        if store.prune() {
+            store.rewrite()?;
+        }
+        Ok(store)
+    }
+
+    /// File the history is persisted to, if any
+    pub fn path(&self) -> Option<&Path> {
+        self.path.as_deref()
+    }
+
+    /// Number of stored entries
+    pub fn len(&self) -> usize {
+        self.records.len()
+    }
+
+    /// Whether the history is empty
+    pub fn is_empty(&self) -> bool {
+        self.records.is_empty()
+    }
+
+    /// Most recent entry
+    pub fn latest(&self) -> Option<&HistoryRecord> {
+        self.records.first()
+    }
+
+    /// Entry for a session, if stored
+    pub fn get(&self, session_id: Uuid) -> Option<&HistoryRecord> {
+        self.records.iter().find(|r| r.session_id == session_id)
+    }
+
+    /// Add an entry, persisting it and applying retention limits
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the history file can't be written. The entry is
+    /// still kept in memory.
+    pub fn insert(&mut self, record: HistoryRecord) -> io::Result<()> {
+        let line = serde_json::to_string(&record)?;
+        self.records.insert(0, record);
+        let pruned = self.prune();
+
+        let Some(path) = &self.path else {
+            return Ok(());
+        };
+        if pruned {
+            self.rewrite()
+        } else {
+            let mut file = OpenOptions::new().create(true).append(true).open(path)?;
+            writeln!(file, "{}", line)
+        }
+    }
+
+    /// Entries matching `query`, newest first
+    pub fn query(&self, query: &HistoryQuery) -> HistoryPage {
+        let words: Vec<String> = query.text.as_deref()
+            .map(|t| t.split_whitespace().map(str::to_lowercase).collect())
+            .unwrap_or_default();
+
+        let matching: Vec<&HistoryRecord> = self.records.iter()
+            .filter(|r| query.since.is_none_or(|since| r.finished_at >= since))
+            .filter(|r| query.until.is_none_or(|until| r.finished_at < until))
+            .filter(|r| {
+                let text = r.transcription.text.to_lowercase();
+                words.iter().all(|w| text.contains(w.as_str()))
+            })
+            .collect();
+
+        HistoryPage {
+            total: matching.len(),
+            offset: query.offset,
+            entries: matching.into_iter().skip(query.offset).take(query.limit).cloned().collect(),
+        }
+    }
+
+    /// Remove the given sessions, returning how many entries were deleted
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the history file can't be rewritten.
+    pub fn delete(&mut self, session_ids: &[Uuid]) -> io::Result<usize> {
+        let before = self.records.len();
+        self.records.retain(|r| !session_ids.contains(&r.session_id));
+        let deleted = before - self.records.len();
+        if deleted > 0 {
+            self.rewrite()?;
+        }
+        Ok(deleted)
+    }
+
+    /// Remove every entry, returning how many were deleted
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the history file can't be rewritten.
+    pub fn clear(&mut self) -> io::Result<usize> {
+        let deleted = self.records.len();
+        self.records.clear();
+        self.rewrite()?;
+        Ok(deleted)
+    }
+
+    // Drop entries beyond the retention limits; true if any were dropped
+    fn prune(&mut self) -> bool {
+        let before = self.records.len();
+        if self.max_age_days > 0 {
+            let cutoff = Utc::now() - chrono::Duration::days(self.max_age_days.into());
+            self.records.retain(|r| r.finished_at >= cutoff);
+        }
+        if self.max_entries > 0 {
+            self.records.truncate(self.max_entries);
+        }
+        self.records.len() != before
+    }
+
+    // Replace the file with the current entries, oldest first
+    fn rewrite(&self) -> io::Result<()> {
+        let Some(path) = &self.path else {
+            return Ok(());
+        };
+        let tmp = path.with_extension("jsonl.tmp");
+        {
+            let mut file = io::BufWriter::new(File::create(&tmp)?);
+            for record in self.records.iter().rev() {
+                serde_json::to_writer(&mut file, record)?;
+                file.write_all(b"\n")?;
+            }
+            file.flush()?;
+        }
+        fs::rename(tmp, path)
+    }
+}
+
+fn load(path: &Path) -> io::Result<Vec<HistoryRecord>> {
+    let file = match File::open(path) {
+        Ok(file) => file,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(e),
+    };
+
+    let mut records = Vec::new();
+    for (n, line) in BufReader::new(file).lines().enumerate() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        match serde_json::from_str(&line) {
+            Ok(record) => records.push(record),
+            Err(e) => tracing::warn!(path = %path.display(), line = n + 1, error = %e, "Skipping bad history entry"),
+        }
+    }
+    Ok(records)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn record(text: &str, days_ago: i64) -> HistoryRecord {
+        let finished_at = Utc::now() - chrono::Duration::days(days_ago);
+        HistoryRecord {
+            session_id: Uuid::new_v4(),
+            started_at: finished_at,
+            finished_at,
+            model: "models/ggml-base.bin".to_string(),
+            language: Some("en".to_string()),
+            source: HistorySource::File { path: "clip.wav".to_string() },
+            transcription: TranscriptionResult {
+                text: text.to_string(),
+                confidence: None,
+                start_ms: 0,
+                end_ms: 1000,
+                segments: Vec::new(),
+                language: None,
+                original: None,
+            },
+        }
+    }
+
+    fn config(name: &str) -> HistoryConfig {
+        let path = std::env::temp_dir().join(format!("vtt-history-{}-{}.jsonl", std::process::id(), name));
+        let _ = fs::remove_file(&path);
+        HistoryConfig {
+            persist: true,
+            path: Some(path.to_string_lossy().into_owned()),
+            max_entries: 0,
+            max_age_days: 0,
+        }
+    }
+
+    #[test]
+    fn test_persists_across_reopen() {
+        let config = config("reopen");
+        let mut store = HistoryStore::open(&config).unwrap();
+        store.insert(record("first", 1)).unwrap();
+        store.insert(record("second", 0)).unwrap();
+        let deleted = store.latest().unwrap().session_id;
+
+        let mut store = HistoryStore::open(&config).unwrap();
+        assert_eq!(store.len(), 2);
+        assert_eq!(store.latest().unwrap().transcription.text, "second");
+
+        assert_eq!(store.delete(&[deleted]).unwrap(), 1);
+        let store = HistoryStore::open(&config).unwrap();
+        assert_eq!(store.len(), 1);
+        assert_eq!(store.latest().unwrap().transcription.text, "first");
+        let _ = fs::remove_file(store.path().unwrap());
+    }
+
+    #[test]
+    fn test_retention_limits() {
+        let mut config = config("retention");
+        config.max_entries = 2;
+        config.max_age_days = 30;
+        let mut store = HistoryStore::open(&config).unwrap();
+        store.insert(record("too old", 40)).unwrap();
+        assert!(store.is_empty());
+
+        for text in ["a", "b", "c"] {
+            store.insert(record(text, 0)).unwrap();
+        }
+        let store = HistoryStore::open(&config).unwrap();
+        let texts: Vec<_> = store.query(&HistoryQuery { limit: 10, ..Default::default() })
+            .entries.into_iter().map(|r| r.transcription.text).collect();
+        assert_eq!(texts, vec!["c", "b"]);
+        let _ = fs::remove_file(store.path().unwrap());
+    }
+
+    #[test]
+    fn test_query_search_dates_and_paging() {
+        let mut store = HistoryStore::in_memory(&HistoryConfig::default());
+        store.insert(record("Meeting notes about the budget", 10)).unwrap();
+        store.insert(record("Budget review", 5)).unwrap();
+        store.insert(record("Grocery list", 1)).unwrap();
+
+        let page = store.query(&HistoryQuery { text: Some("BUDGET".to_string()), limit: 10, ..Default::default() });
+        assert_eq!(page.total, 2);
+        assert_eq!(page.entries[0].transcription.text, "Budget review");
+
+        let page = store.query(&HistoryQuery { text: Some("budget notes".to_string()), limit: 10, ..Default::default() });
+        assert_eq!(page.total, 1);
+
+        let since = Some(Utc::now() - chrono::Duration::days(7));
+        let page = store.query(&HistoryQuery { since, limit: 10, ..Default::default() });
+        assert_eq!(page.total, 2);
+
+        let page = store.query(&HistoryQuery { offset: 1, limit: 1, ..Default::default() });
+        assert_eq!(page.total, 3);
+        assert_eq!(page.entries.len(), 1);
+        assert_eq!(page.entries[0].transcription.text, "Budget review");
+    }
+
+    #[test]
+    fn test_skips_malformed_lines() {
+        let config = config("malformed");
+        let path = config.resolved_path().unwrap();
+        let good = serde_json::to_string(&record("ok", 0)).unwrap();
+        fs::write(&path, format!("{{not json\n{}\n\n", good)).unwrap();
+
+        let store = HistoryStore::open(&config).unwrap();
+        assert_eq!(store.len(), 1);
+        let _ = fs::remove_file(path);
+    }
+}
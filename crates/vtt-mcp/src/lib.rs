@@ -7,7 +7,10 @@
 #![warn(clippy::all)]
 
 pub mod error;
+pub mod history;
+pub mod resources;
 pub mod server;
+pub mod sse;
 
 pub use error::{VttError, VttResult};
 pub use server::VttMcpServer;
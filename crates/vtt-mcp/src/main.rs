@@ -2,13 +2,55 @@
 //!
 //! Entry point for the Voice-to-Text MCP server.
 
-use anyhow::Result;
+use anyhow::{Context, Result};
+use clap::Parser;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::Duration;
 use tracing_subscriber::{EnvFilter, fmt, prelude::*};
+use vtt_core::config::ConfigLoader;
 use vtt_mcp::VttMcpServer;
 use rmcp::{transport::stdio, ServiceExt};
+use rmcp::transport::streamable_http_server::{
+    StreamableHttpServerConfig, StreamableHttpService, session::local::LocalSessionManager,
+};
+
+/// Voice-to-Text MCP server
+///
+/// Flags override the config files and `VTT_*` environment variables.
+#[derive(Parser, Debug)]
+#[command(name = "vtt-mcp")]
+#[command(version = env!("CARGO_PKG_VERSION"))]
+struct Args {
+    /// Config file layered over the system and user config
+    #[arg(short, long)]
+    config: Option<PathBuf>,
+
+    /// MCP transport: "stdio", or "http" / "sse" for the HTTP server
+    #[arg(long)]
+    transport: Option<String>,
+
+    /// Address the HTTP transport listens on
+    #[arg(long)]
+    bind_address: Option<String>,
+
+    /// Default Whisper model file
+    #[arg(short, long)]
+    model: Option<String>,
+
+    /// Default number of transcription threads
+    #[arg(short, long)]
+    threads: Option<usize>,
+
+    /// Disable GPU acceleration by default
+    #[arg(long)]
+    no_gpu: bool,
+}
 
 #[tokio::main]
 async fn main() -> Result<()> {
+    let args = Args::parse();
+
     // Initialize tracing with structured logging
     let env_filter = EnvFilter::try_from_default_env()
         .unwrap_or_else(|_| EnvFilter::new("info,vtt_mcp=debug,vtt_core=debug"));
@@ -28,8 +70,43 @@ async fn main() -> Result<()> {
         "Starting VTT MCP server"
     );
 
-    // Create and run the server with STDIO transport
-    let service = VttMcpServer::new()
+    let mut config = ConfigLoader::load_with(args.config.as_deref())
+        .context("Failed to load configuration")?;
+    if let Some(transport) = args.transport {
+        config.mcp.transport = transport;
+    }
+    if let Some(bind_address) = args.bind_address {
+        config.mcp.bind_address = bind_address;
+    }
+    if let Some(model) = args.model {
+        config.whisper.model_path = Some(model);
+    }
+    if let Some(threads) = args.threads {
+        config.whisper.threads = Some(threads);
+    }
+    if args.no_gpu {
+        config.whisper.enable_gpu = false;
+    }
+    let transport = config.mcp.transport.clone();
+    let bind_address = config.mcp.bind_address.clone();
+
+    let server = VttMcpServer::with_config(config);
+
+    // Unload Whisper models nobody has used for their idle timeout
+    server.models().spawn_reaper(Duration::from_secs(30));
+    // Stop overlong recordings and forget finished sessions
+    server.spawn_session_reaper(Duration::from_secs(10));
+
+    match transport.as_str() {
+        "stdio" => serve_stdio(server).await,
+        "http" | "sse" => serve_http(server, &bind_address).await,
+        other => anyhow::bail!("Unsupported MCP transport '{}' (expected \"stdio\", \"http\" or \"sse\")", other),
+    }
+}
+
+/// Serve a single client over STDIO
+async fn serve_stdio(server: VttMcpServer) -> Result<()> {
+    let service = server
         .serve(stdio())
         .await
         .inspect_err(|e| {
@@ -42,3 +119,33 @@ async fn main() -> Result<()> {
 
     Ok(())
 }
+
+/// Serve any number of clients over Streamable HTTP at `/mcp`, and over the
+/// legacy HTTP+SSE transport at `/sse` and `/message`
+///
+/// Every client session gets its own handler, but all of them share the
+/// server's sessions, history and model pool.
+async fn serve_http(server: VttMcpServer, bind_address: &str) -> Result<()> {
+    let sse = vtt_mcp::sse::router(server.clone());
+    let service = StreamableHttpService::new(
+        move || Ok(server.for_connection()),
+        Arc::new(LocalSessionManager::default()),
+        StreamableHttpServerConfig::default(),
+    );
+    let router = axum::Router::new().nest_service("/mcp", service).merge(sse);
+
+    let listener = tokio::net::TcpListener::bind(bind_address)
+        .await
+        .with_context(|| format!("Failed to bind {}", bind_address))?;
+
+    let addr = listener.local_addr()?;
+    tracing::info!("Server listening on http://{}/mcp (legacy SSE: http://{}/sse)", addr, addr);
+
+    axum::serve(listener, router)
+        .with_graceful_shutdown(async {
+            let _ = tokio::signal::ctrl_c().await;
+        })
+        .await?;
+
+    Ok(())
+}
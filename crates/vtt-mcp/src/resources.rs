@@ -0,0 +1,168 @@
+//! Resource URIs
+//!
+//! Every resource the server exposes belongs to one of the templates in
+//! [`ResourceUri::templates`]. Handlers parse the requested URI once with
+//! [`ResourceUri::parse`] and dispatch on the variant.
+
+use crate::error::{VttError, VttResult};
+use rmcp::model::{Annotated, RawResourceTemplate, ResourceTemplate};
+use std::fmt;
+use uuid::Uuid;
+
+/// A parsed resource URI
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ResourceUri {
+    /// `transcript://live/{session_id}`: the session's current text
+    LiveTranscript(Uuid),
+    /// `transcript://history/{session_id}`: the final transcript as JSON
+    HistoryTranscript(Uuid),
+    /// `session://{session_id}`: status, configuration and timings as JSON
+    Session(Uuid),
+    /// `audio://session/{session_id}.wav`: the recorded audio
+    SessionAudio(Uuid),
+}
+
+const LIVE_PREFIX: &str = "transcript://live/";
+const HISTORY_PREFIX: &str = "transcript://history/";
+const SESSION_PREFIX: &str = "session://";
+const AUDIO_PREFIX: &str = "audio://session/";
+const AUDIO_SUFFIX: &str = ".wav";
+
+impl ResourceUri {
+    /// Parse a concrete resource URI
+    ///
+    /// # Errors
+    ///
+    /// Returns `InvalidParams` if the URI matches no template or the
+    /// session id isn't a UUID.
+    pub fn parse(uri: &str) -> VttResult<Self> {
+        let (id, make): (&str, fn(Uuid) -> Self) = if let Some(id) = uri.strip_prefix(LIVE_PREFIX) {
+            (id, Self::LiveTranscript)
+        } else if let Some(id) = uri.strip_prefix(HISTORY_PREFIX) {
+            (id, Self::HistoryTranscript)
+        } else if let Some(id) = uri.strip_prefix(SESSION_PREFIX) {
+            (id, Self::Session)
+        } else if let Some(id) = uri.strip_prefix(AUDIO_PREFIX).and_then(|rest| rest.strip_suffix(AUDIO_SUFFIX)) {
+            (id, Self::SessionAudio)
+        } else {
+            return Err(VttError::invalid_params(format!(
+                "Unknown resource URI '{}'. Use resources/templates/list to see supported URIs",
+                uri
+            )));
+        };
+
+        let session_id = Uuid::parse_str(id)
+            .map_err(|_| VttError::invalid_params(format!("Invalid session ID in '{}'", uri)))?;
+        Ok(make(session_id))
+    }
+
+    /// Session the resource belongs to
+    pub fn session_id(&self) -> Uuid {
+        match *self {
+            Self::LiveTranscript(id) | Self::HistoryTranscript(id) | Self::Session(id) | Self::SessionAudio(id) => id,
+        }
+    }
+
+    /// MIME type of the resource contents
+    pub fn mime_type(&self) -> &'static str {
+        match self {
+            Self::LiveTranscript(_) => "text/plain",
+            Self::HistoryTranscript(_) | Self::Session(_) => "application/json",
+            Self::SessionAudio(_) => "audio/wav",
+        }
+    }
+
+    /// URI templates for every resource type, for `resources/templates/list`
+    pub fn templates() -> Vec<ResourceTemplate> {
+        let template = |uri_template: &str, name: &str, description: &str, mime_type: &str| {
+            Annotated::new(
+                RawResourceTemplate {
+                    uri_template: uri_template.to_string(),
+                    name: name.to_string(),
+                    title: None,
+                    description: Some(description.to_string()),
+                    mime_type: Some(mime_type.to_string()),
+                },
+                None,
+            )
+        };
+
+        vec![
+            template(
+                "transcript://live/{session_id}",
+                "live-transcript",
+                "Current text of a listening session; subscribe for updates",
+                "text/plain",
+            ),
+            template(
+                "transcript://history/{session_id}",
+                "history-transcript",
+                "Final transcript with segments and token timings",
+                "application/json",
+            ),
+            template(
+                "session://{session_id}",
+                "session",
+                "Session status, configuration and timings",
+                "application/json",
+            ),
+            template(
+                "audio://session/{session_id}.wav",
+                "session-audio",
+                "Audio recorded by a stopped session, as 16-bit mono WAV",
+                "audio/wav",
+            ),
+        ]
+    }
+}
+
+impl fmt::Display for ResourceUri {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::LiveTranscript(id) => write!(f, "{}{}", LIVE_PREFIX, id),
+            Self::HistoryTranscript(id) => write!(f, "{}{}", HISTORY_PREFIX, id),
+            Self::Session(id) => write!(f, "{}{}", SESSION_PREFIX, id),
+            Self::SessionAudio(id) => write!(f, "{}{}{}", AUDIO_PREFIX, id, AUDIO_SUFFIX),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_round_trip() {
+        let id = Uuid::new_v4();
+        for uri in [
+            ResourceUri::LiveTranscript(id),
+            ResourceUri::HistoryTranscript(id),
+            ResourceUri::Session(id),
+            ResourceUri::SessionAudio(id),
+        ] {
+            assert_eq!(ResourceUri::parse(&uri.to_string()).unwrap(), uri);
+            assert_eq!(uri.session_id(), id);
+        }
+        assert_eq!(ResourceUri::SessionAudio(id).to_string(), format!("audio://session/{}.wav", id));
+    }
+
+    #[test]
+    fn test_rejects_unknown_uris() {
+        let id = Uuid::new_v4();
+        assert!(ResourceUri::parse(&format!("transcript://other/{}", id)).is_err());
+        assert!(ResourceUri::parse(&format!("audio://session/{}", id)).is_err());
+        assert!(ResourceUri::parse("session://not-a-uuid").is_err());
+    }
+
+    #[test]
+    fn test_templates_match_parser() {
+        let id = Uuid::new_v4().to_string();
+        let templates = ResourceUri::templates();
+        assert_eq!(templates.len(), 4);
+        for template in templates {
+            let uri = template.raw.uri_template.replace("{session_id}", &id);
+            let parsed = ResourceUri::parse(&uri).unwrap();
+            assert_eq!(template.raw.mime_type.as_deref(), Some(parsed.mime_type()));
+        }
+    }
+}
@@ -2,29 +2,56 @@
 
 use crate::error::{VttError};
 use crate::error::VttResult;
+use crate::resources::ResourceUri;
+use crate::history::{HistoryPage, HistoryQuery, HistoryRecord, HistorySource, HistoryStore};
+use base64::Engine;
 use chrono::{DateTime, Utc};
 use rmcp::{
     handler::server::{ServerHandler, tool::ToolRouter, wrapper::Parameters},
-    model::{Annotated, CallToolResult, Content, ErrorData as McpError, ListResourceTemplatesResult, ListResourcesResult, PaginatedRequestParam, RawResource, ReadResourceRequestParam, ReadResourceResult, Resource, ResourceContents, ResourcesCapability, ServerCapabilities, ServerInfo, SubscribeRequestParam, UnsubscribeRequestParam},
-    service::{RequestContext, RoleServer},
-    tool, tool_router,
+    model::{Annotated, CallToolResult, ProgressNotificationParam, ProgressToken, Content, ErrorData as McpError, JsonObject, ListResourceTemplatesResult, ListResourcesResult, PaginatedRequestParam, RawResource, ReadResourceRequestParam, ReadResourceResult, Resource, ResourceContents, ResourceUpdatedNotificationParam, ResourcesCapability, ServerCapabilities, ServerInfo, SubscribeRequestParam, UnsubscribeRequestParam},
+    service::{Peer, RequestContext, RoleServer},
+    tool, tool_handler, tool_router,
 };
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::sync::Arc;
-use tokio::sync::{Mutex, broadcast};
+use std::time::Duration;
+use tokio::sync::{Mutex, broadcast, mpsc, oneshot};
+use tokio::task::JoinHandle;
 use uuid::Uuid;
 
-use vtt_core::audio::{AudioCapture, list_devices};
-use vtt_core::whisper::{WhisperContext, WhisperConfig, Transcription};
+use vtt_core::audio::{AudioCapture, AudioFormat, DecodedAudio, Resampler, decode_file, encode_wav, find_device, list_devices};
+use vtt_core::audio::decode::WHISPER_SAMPLE_RATE;
+use vtt_core::config::Config;
+use vtt_core::export::{export, ExportFormat, ExportOptions};
+use vtt_core::incremental::{LocalAgreement, TimedWord};
+use vtt_core::vad::{create_backend, SegmenterConfig, SpeechSegment, VadSegmenter};
+use vtt_core::whisper::{plan_chunks, AudioChunk, ChunkConfig, DecodingOverrides, ModelLease, ModelPool, Segment, Task, Token, WhisperConfig, WhisperError, Transcription};
 use vtt_core::whisper::language::{Language, SUPPORTED_LANGUAGES, display_name};
 
+/// `listen_once` defaults: trailing silence that ends the utterance, how
+/// long to wait for speech to start, and the longest it listens
+const LISTEN_ONCE_SILENCE_MS: u32 = 1500;
+const LISTEN_ONCE_SPEECH_TIMEOUT_SECS: u64 = 10;
+const LISTEN_ONCE_MAX_DURATION_SECS: u64 = 60;
+/// How often `listen_once` drains the capture
+const LISTEN_ONCE_POLL: Duration = Duration::from_millis(100);
+/// Candidate languages `detect_language` returns by default
+const DETECT_LANGUAGE_TOP_N: usize = 5;
+/// Longest stretch of an utterance a live session re-transcribes per tick,
+/// Whisper's window
+const LIVE_WINDOW_MS: u64 = 30_000;
+
 /// Transcription update broadcast to subscribers
 #[derive(Debug, Clone, Serialize)]
 pub struct TranscriptionUpdate {
     pub session_id: Uuid,
     pub text: String,
+    /// Start of `text` that won't change any more
+    pub committed_text: String,
+    /// Rest of `text`, which later updates may still revise
+    pub tentative_text: String,
     pub is_final: bool,
     pub timestamp: DateTime<Utc>,
     pub confidence: Option<f32>,
@@ -35,66 +62,365 @@ pub struct TranscriptionUpdate {
 pub struct SessionSubscriber {
     pub client_id: String,
     pub subscribed_at: DateTime<Utc>,
+    /// Connection that receives `notifications/resources/updated`
+    pub peer: Peer<RoleServer>,
 }
 
 /// MCP Server for Voice-to-Text functionality
 #[derive(Clone)]
 pub struct VttMcpServer {
     sessions: Arc<Mutex<HashMap<Uuid, SessionState>>>,
-    transcription_history: Arc<Mutex<Vec<HistoryEntry>>>,
+    transcription_history: Arc<Mutex<HistoryStore>>,
     audio_config: Arc<Mutex<AudioRuntimeConfig>>,
     tool_router: ToolRouter<Self>,
     /// Track subscribers for each session's live transcription
     subscribers: Arc<Mutex<HashMap<Uuid, Vec<SessionSubscriber>>>>,
     /// Broadcast channel for transcription updates
     transcription_tx: broadcast::Sender<TranscriptionUpdate>,
+    /// Server-wide settings (transcription interval, limits, ...)
+    config: Arc<Config>,
+    /// Identifies the client connection this handler instance serves
+    connection_id: Uuid,
+    /// Loaded Whisper models shared by all sessions and tool calls
+    models: ModelPool,
 }
 
 impl VttMcpServer {
+    /// Create a server with default settings whose history is kept in
+    /// memory only
     pub fn new() -> Self {
+        let mut config = Config::default();
+        config.history.persist = false;
+        Self::with_config(config)
+    }
+
+    /// Create a server using the given configuration
+    ///
+    /// If the history file can't be opened, history is kept in memory.
+    pub fn with_config(config: Config) -> Self {
         let (transcription_tx, _) = broadcast::channel(100);
+        let history = HistoryStore::open(&config.history).unwrap_or_else(|e| {
+            tracing::warn!(error = %e, "Failed to open transcription history; keeping it in memory");
+            HistoryStore::in_memory(&config.history)
+        });
         Self {
             sessions: Arc::new(Mutex::new(HashMap::new())),
-            transcription_history: Arc::new(Mutex::new(Vec::new())),
+            transcription_history: Arc::new(Mutex::new(history)),
             audio_config: Arc::new(Mutex::new(AudioRuntimeConfig::default())),
             tool_router: Self::tool_router(),
             subscribers: Arc::new(Mutex::new(HashMap::new())),
             transcription_tx,
+            config: Arc::new(config),
+            connection_id: Uuid::new_v4(),
+            models: ModelPool::new(),
+        }
+    }
+
+    /// Shared pool of loaded Whisper models
+    pub fn models(&self) -> &ModelPool {
+        &self.models
+    }
+
+    /// Handler for a new client connection sharing this server's state
+    ///
+    /// Sessions, history and subscriptions are shared; only the connection
+    /// id used to tell subscribers apart is fresh.
+    pub fn for_connection(&self) -> Self {
+        Self {
+            connection_id: Uuid::new_v4(),
+            ..self.clone()
+        }
+    }
+
+    /// Transcription of a live session, or of a history entry if the
+    /// session is gone
+    async fn find_transcription(&self, session_id: Uuid) -> Option<TranscriptionResult> {
+        if let Some(tx) = self.sessions.lock().await.get(&session_id).and_then(|s| s.transcription.clone()) {
+            return Some(tx);
         }
+        self.transcription_history.lock().await.get(session_id)
+            .map(|entry| entry.transcription.clone())
     }
 
     async fn store_transcription_in_history(
         &self,
         session_id: Uuid,
+        started_at: DateTime<Utc>,
         config: WhisperConfig,
+        source: HistorySource,
         transcription: TranscriptionResult,
     ) {
-        let entry = HistoryEntry {
+        let record = HistoryRecord {
             session_id,
-            timestamp: Utc::now(),
-            config,
+            started_at,
+            finished_at: Utc::now(),
+            model: config.model_path,
+            language: transcription.language.clone().or(config.language),
+            source,
             transcription,
         };
-        let mut history = self.transcription_history.lock().await;
-        history.insert(0, entry);
-        if history.len() > 100 {
-            history.truncate(100);
+        if let Err(e) = self.transcription_history.lock().await.insert(record) {
+            tracing::warn!(session_id = %session_id, error = %e, "Failed to persist transcription history");
         }
     }
 
     /// Broadcast transcription update to all subscribers
+    ///
+    /// The text also becomes the session's live transcript, as served by
+    /// `transcript://live/{session_id}` and `session://{session_id}`.
     pub async fn broadcast_transcription(&self, update: TranscriptionUpdate) {
+        if let Some(session) = self.sessions.lock().await.get_mut(&update.session_id) {
+            session.committed_text = update.committed_text.clone();
+            session.tentative_text = update.tentative_text.clone();
+        }
+        self.notify_subscribers(update.session_id).await;
         let _ = self.transcription_tx.send(update);
     }
 
+    /// Send `notifications/resources/updated` to every subscriber of a session
+    ///
+    /// Subscribers whose connection has gone away are dropped.
+    async fn notify_subscribers(&self, session_id: Uuid) {
+        let subscribers = self.get_subscribers(session_id).await;
+        if subscribers.is_empty() {
+            return;
+        }
+
+        let uri = ResourceUri::LiveTranscript(session_id).to_string();
+        let mut disconnected = Vec::new();
+        for subscriber in subscribers {
+            let param = ResourceUpdatedNotificationParam { uri: uri.clone() };
+            if subscriber.peer.is_transport_closed()
+                || subscriber.peer.notify_resource_updated(param).await.is_err()
+            {
+                tracing::debug!("Dropping disconnected subscriber {} of {}", subscriber.client_id, uri);
+                disconnected.push(subscriber.client_id);
+            }
+        }
+
+        for client_id in disconnected {
+            let _ = self.remove_subscriber(session_id, &client_id).await;
+        }
+    }
+
+    /// Check out a shared model for `config`, loading it on first use
+    async fn acquire_model(&self, config: WhisperConfig) -> VttResult<ModelLease> {
+        let models = self.models.clone();
+        tokio::task::spawn_blocking(move || models.acquire(config))
+            .await
+            .map_err(|e| VttError::internal(e.to_string()))?
+            .map_err(|e| match e {
+                WhisperError::TooManySessions { .. } => VttError::Session(e.to_string()),
+                e => VttError::Model(e.to_string()),
+            })
+    }
+
+    /// Whisper settings for one call; per-call parameters win over the
+    /// server configuration
+    ///
+    /// `language` is a code from `list_languages` or "auto".
+    fn whisper_config(&self, overrides: WhisperOverrides) -> VttResult<WhisperConfig> {
+        let WhisperOverrides { model_path, language, threads, use_gpu, task, initial_prompt, vocabulary, decoding } = overrides;
+        let language = language.as_deref();
+        if let Some(lang) = language {
+            if lang != "auto" && !Language::is_valid(lang) {
+                return Err(VttError::invalid_params(format!(
+                    "Unsupported language code: '{}'. Use list_languages tool to see supported languages.",
+                    lang
+                )));
+            }
+        }
+
+        let whisper = &self.config.whisper;
+        let decoding = decoding.unwrap_or_default().apply_to(whisper.decoding.resolve());
+        decoding.validate().map_err(|e| VttError::invalid_params(e.to_string()))?;
+
+        let memory = &whisper.memory;
+        Ok(WhisperConfig {
+            model_path: model_path.unwrap_or_else(|| whisper.resolved_model_path()),
+            // None means auto-detect
+            language: language.filter(|l| *l != "auto").map(str::to_string),
+            use_gpu: use_gpu.unwrap_or(whisper.enable_gpu),
+            n_threads: threads.unwrap_or_else(|| whisper.resolved_threads()) as i32,
+            idle_timeout_secs: Some(memory.idle_timeout_secs),
+            max_sessions: memory.max_sessions,
+            decoding,
+            ..Default::default()
+        }
+        .with_task(task.unwrap_or_default())
+        .with_initial_prompt(initial_prompt.or_else(|| whisper.initial_prompt.clone()))
+        .with_vocabulary(vocabulary.unwrap_or_else(|| whisper.vocabulary.clone())))
+    }
+
+    /// VAD segmenter for 16 kHz mono capture, from the `[vad]` section
+    ///
+    /// Built before the device is opened so a missing or unusable VAD model
+    /// fails the call rather than the capture.
+    fn vad_segmenter(&self, config: SegmenterConfig) -> VttResult<VadSegmenter> {
+        let backend = create_backend(&self.config.vad.detector_config())?;
+        Ok(VadSegmenter::new(backend, config)?)
+    }
+
+    /// Split a decoded clip into chunks at the silences the `[vad]`
+    /// detector finds, handing the samples back with the chunks
+    ///
+    /// Clips that fit in one chunk skip voice activity detection. If the
+    /// detector can't run, long clips are cut at full chunk length instead.
+    async fn plan_clip_chunks(&self, samples: Vec<f32>) -> VttResult<(Vec<f32>, Vec<AudioChunk>)> {
+        let config = ChunkConfig { sample_rate: WHISPER_SAMPLE_RATE, ..Default::default() };
+        if samples.len() as u64 * 1000 <= WHISPER_SAMPLE_RATE as u64 * config.max_chunk_ms as u64 {
+            let chunks = vec![AudioChunk::whole(samples.len())];
+            return Ok((samples, chunks));
+        }
+
+        // Chunks are cut below, so the segmenter needn't split long speech
+        let segmenter = self.vad_segmenter(SegmenterConfig {
+            max_segment_ms: u32::MAX,
+            ..self.config.vad.segmenter_config(WHISPER_SAMPLE_RATE)
+        });
+        tokio::task::spawn_blocking(move || {
+            let speech = segmenter.and_then(|mut segmenter| {
+                let mut speech = segmenter.push(&samples)?;
+                speech.extend(segmenter.flush()?);
+                Ok(speech)
+            });
+            let speech = speech.unwrap_or_else(|e: VttError| {
+                tracing::warn!(error = %e, "Voice activity detection failed; cutting chunks at full length");
+                Vec::new()
+            });
+            let chunks = plan_chunks(samples.len(), &speech, &config);
+            (samples, chunks)
+        })
+        .await
+        .map_err(|e| VttError::internal(e.to_string()))
+    }
+
+    /// Open and start a capture device
+    ///
+    /// An explicit device wins over the one picked with `configure_audio`.
+    /// Returns the running capture and the device name, if any.
+    async fn open_capture(&self, device_name: Option<String>) -> VttResult<(AudioCapture, Option<String>)> {
+        let device_name = match device_name {
+            Some(name) => Some(name),
+            None => self.audio_config.lock().await.default_device.clone(),
+        };
+        let format = self.config.audio.capture_format();
+        let mut capture = match device_name.as_deref() {
+            Some(name) => {
+                let device = find_device(name).map_err(|_| VttError::device_not_found(name))?;
+                AudioCapture::with_device(&device, format)
+            }
+            None => AudioCapture::with_format(format),
+        }?
+        .with_channel_policy(self.config.audio.channel_policy);
+        capture.start()?;
+        Ok((capture, device_name))
+    }
+
+    /// Background task driving live transcription for one listening session
+    ///
+    /// Drains the capture every `interval_ms`, resampling as it goes, keeps
+    /// the full 16 kHz mono recording for the final pass in `stop_listening`,
+    /// and runs it through the VAD segmenter on a blocking thread. Each
+    /// utterance is transcribed once it ends and published with `is_final`;
+    /// while one is in progress the part of it not yet committed is
+    /// re-transcribed every interval and stabilized by a [`LiveTranscript`],
+    /// so partial results only revise their tentative words. With
+    /// `carry_prompt`, each transcription is prompted with the committed
+    /// text before it. Hands the capture back once `stop_rx` fires.
+    async fn run_live_transcription(
+        self,
+        session_id: Uuid,
+        mut capture: AudioCapture,
+        model: ModelLease,
+        mut segmenter: VadSegmenter,
+        carry_prompt: bool,
+        mut stop_rx: oneshot::Receiver<()>,
+    ) -> LiveCapture {
+        let interval_ms = self.config.transcription.interval_ms.max(1);
+        let mut resampler = Resampler::new(capture.format().sample_rate, WHISPER_SAMPLE_RATE, 1);
+        let mut recording = Vec::new();
+        let mut live = LiveTranscript::default();
+
+        let mut ticker = tokio::time::interval(Duration::from_millis(interval_ms));
+        loop {
+            tokio::select! {
+                _ = &mut stop_rx => break,
+                _ = ticker.tick() => {}
+            }
+
+            let chunk = resampler.process(&capture.format().to_mono(&capture.take_buffer()));
+            if chunk.is_empty() {
+                continue;
+            }
+            recording.extend_from_slice(&chunk);
+
+            // Segment positions index into `recording`: both start at the
+            // first captured sample
+            let pushed = match with_segmenter(segmenter, move |segmenter| segmenter.push(&chunk)).await {
+                Ok((returned, pushed)) => {
+                    segmenter = returned;
+                    pushed
+                }
+                Err(e) => {
+                    tracing::error!(session_id = %session_id, error = %e, "Stopping live transcription");
+                    break;
+                }
+            };
+            let segments = match pushed {
+                Ok(segments) => segments,
+                Err(e) => {
+                    tracing::warn!(session_id = %session_id, error = %e, "Voice activity detection failed");
+                    continue;
+                }
+            };
+
+            for segment in segments {
+                let utterance = &recording[segment.start_sample..segment.end_sample];
+                let previous = carry_prompt.then(|| live.finished.clone());
+                let tx = transcribe_utterance(session_id, &model, utterance, previous.as_deref()).await;
+                let text = tx.as_ref().map_or("", |tx| tx.text.trim());
+                if let Some(update) = live.finish_utterance(session_id, text) {
+                    self.broadcast_transcription(update).await;
+                }
+            }
+
+            if let Some(start) = segmenter.open_segment_start() {
+                let window_start = live.window_start(start, recording.len());
+                if window_start == recording.len() {
+                    continue;
+                }
+                let previous = carry_prompt.then(|| live.prompt());
+                let window = &recording[window_start..];
+                if let Some(tx) = transcribe_utterance(session_id, &model, window, previous.as_deref()).await {
+                    if let Some(update) = live.hypothesis(session_id, &tx, window_start) {
+                        self.broadcast_transcription(update).await;
+                    }
+                }
+            }
+        }
+
+        LiveCapture { capture, resampler, recording, model }
+    }
+
     /// Add a subscriber to a session
-    pub async fn add_subscriber(&self, session_id: Uuid, client_id: String) -> VttResult<()> {
+    ///
+    /// Subscribing again from the same client replaces the earlier entry.
+    pub async fn add_subscriber(
+        &self,
+        session_id: Uuid,
+        client_id: String,
+        peer: Peer<RoleServer>,
+    ) -> VttResult<()> {
         let subscriber = SessionSubscriber {
             client_id,
             subscribed_at: Utc::now(),
+            peer,
         };
         let mut subscribers = self.subscribers.lock().await;
-        subscribers.entry(session_id).or_insert_with(Vec::new).push(subscriber);
+        let subs = subscribers.entry(session_id).or_insert_with(Vec::new);
+        subs.retain(|s| s.client_id != subscriber.client_id);
+        subs.push(subscriber);
         Ok(())
     }
 
@@ -121,6 +447,105 @@ impl VttMcpServer {
         let mut subscribers = self.subscribers.lock().await;
         subscribers.remove(&session_id);
     }
+
+    /// Apply the `[sessions]` limits once
+    ///
+    /// Listening sessions past `max_recording_secs` are stopped with
+    /// [`SessionStatus::Error`], keeping their recording but skipping
+    /// transcription. Sessions that ended more than `finished_ttl_secs` ago
+    /// are forgotten; their transcripts remain in the history.
+    pub async fn reap_sessions(&self) {
+        let limits = &self.config.sessions;
+        let now = Utc::now();
+        let older_than = |time: DateTime<Utc>, secs: u64| secs > 0 && (now - time).num_seconds() >= secs as i64;
+
+        let (overdue, expired): (Vec<Uuid>, Vec<Uuid>) = {
+            let sessions = self.sessions.lock().await;
+            let overdue = sessions.iter()
+                .filter(|(_, s)| s.status == SessionStatus::Listening && older_than(s.start_time, limits.max_recording_secs))
+                .map(|(id, _)| *id)
+                .collect();
+            let expired = sessions.iter()
+                .filter(|(_, s)| s.transcription_timestamp.is_some_and(|t| older_than(t, limits.finished_ttl_secs)))
+                .map(|(id, _)| *id)
+                .collect();
+            (overdue, expired)
+        };
+
+        for session_id in overdue {
+            let reason = format!(
+                "Stopped after reaching the maximum recording duration ({}s)",
+                limits.max_recording_secs
+            );
+            self.fail_session(session_id, reason).await;
+        }
+
+        if expired.is_empty() {
+            return;
+        }
+        let mut sessions = self.sessions.lock().await;
+        for session_id in &expired {
+            sessions.remove(session_id);
+        }
+        drop(sessions);
+        for session_id in expired {
+            tracing::debug!(session_id = %session_id, "Forgot finished session");
+            self.cleanup_subscribers(session_id).await;
+        }
+    }
+
+    /// Periodically apply the `[sessions]` limits in the background
+    pub fn spawn_session_reaper(&self, period: Duration) -> JoinHandle<()> {
+        let server = self.clone();
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(period);
+            loop {
+                ticker.tick().await;
+                server.reap_sessions().await;
+            }
+        })
+    }
+
+    /// Stop a listening session that hit a limit or failed and mark it failed
+    ///
+    /// The audio captured so far stays available as the session's recording.
+    /// A `listen_once` call on the session is cancelled.
+    async fn fail_session(&self, session_id: Uuid, reason: String) {
+        let live = {
+            let mut sessions = self.sessions.lock().await;
+            let Some(session) = sessions.get_mut(&session_id) else { return };
+            if session.status != SessionStatus::Listening {
+                return;
+            }
+            session.status = SessionStatus::Error;
+            session.error = Some(reason.clone());
+            session.transcription_timestamp = Some(Utc::now());
+            session.cancel = None;
+            session.live.take()
+        };
+        tracing::warn!(session_id = %session_id, reason = %reason, "Stopping session");
+
+        let drained = match live {
+            Some(live) => live.finish().await
+                .and_then(|capture| capture.drain().map_err(|e| McpError::from(VttError::Audio(e))))
+                .map(Some),
+            None => Ok(None),
+        };
+        let recording = match drained {
+            Ok(Some((samples, format, _model))) => encode_recording(session_id, &samples, &format),
+            Ok(None) => None,
+            Err(e) => {
+                tracing::warn!(session_id = %session_id, error = %e.message, "Failed to stop capture");
+                None
+            }
+        };
+        if let Some(session) = self.sessions.lock().await.get_mut(&session_id) {
+            session.recording = recording;
+        }
+
+        self.notify_subscribers(session_id).await;
+        self.cleanup_subscribers(session_id).await;
+    }
 }
 
 impl Default for VttMcpServer {
@@ -130,13 +555,17 @@ impl Default for VttMcpServer {
 }
 
 /// Implement ServerHandler for rmcp
+#[tool_handler]
 impl ServerHandler for VttMcpServer {
     fn get_info(&self) -> ServerInfo {
         ServerInfo {
             protocol_version: rmcp::model::ProtocolVersion::default(),
             capabilities: ServerCapabilities {
                 tools: Some(rmcp::model::ToolsCapability::default()),
-                resources: Some(ResourcesCapability::default()), // Enable resources
+                resources: Some(ResourcesCapability {
+                    subscribe: Some(true),
+                    ..Default::default()
+                }),
                 ..Default::default()
             },
             server_info: rmcp::model::Implementation {
@@ -145,36 +574,41 @@ impl ServerHandler for VttMcpServer {
                 ..Default::default()
             },
             instructions: Some(
-                "Voice-to-Text MCP server providing real-time transcription via Whisper. Resources: transcript://live/{session_id}".to_string()
+                "Voice-to-Text MCP server providing real-time transcription via Whisper. Resources: transcript://live/{session_id}, transcript://history/{session_id}, session://{session_id}, audio://session/{session_id}.wav".to_string()
             ),
         }
     }
 
-    /// List available resources (active listening sessions)
+    /// List concrete resources: every session, its recording, and every
+    /// stored transcript
     fn list_resources(
         &self,
         _request: Option<PaginatedRequestParam>,
         _context: RequestContext<RoleServer>,
     ) -> impl std::future::Future<Output = Result<ListResourcesResult, McpError>> + Send + '_ {
         async move {
+            let mut resources = Vec::new();
+
             let sessions = self.sessions.lock().await;
-            let resources: Vec<Resource> = sessions
-                .iter()
-                .filter(|(_, s)| s.status == SessionStatus::Listening)
-                .map(|(id, _)| {
-                    let raw = RawResource {
-                        uri: format!("transcript://live/{}", id),
-                        name: format!("session-{}", id),
-                        title: Some(format!("Live transcription for session {}", id)),
-                        description: Some("Real-time transcription stream".to_string()),
-                        mime_type: Some("text".to_string()),
-                        size: None,
-                        icons: None,
-                        meta: None,
-                    };
-                    Annotated::new(raw, None)
-                })
-                .collect();
+            for (id, session) in sessions.iter() {
+                resources.push(resource(ResourceUri::Session(*id), format!("Session {} ({})", id, session.status_display())));
+                if session.status == SessionStatus::Listening {
+                    resources.push(resource(ResourceUri::LiveTranscript(*id), format!("Live transcription for session {}", id)));
+                }
+                if session.recording.is_some() {
+                    resources.push(resource(ResourceUri::SessionAudio(*id), format!("Recording of session {}", id)));
+                }
+            }
+            drop(sessions);
+
+            let history = self.transcription_history.lock().await;
+            let all = HistoryQuery { limit: usize::MAX, ..Default::default() };
+            for entry in history.query(&all).entries {
+                resources.push(resource(
+                    ResourceUri::HistoryTranscript(entry.session_id),
+                    format!("Transcript from {}", entry.finished_at.to_rfc3339_opts(chrono::SecondsFormat::Secs, true)),
+                ));
+            }
 
             Ok(ListResourcesResult {
                 resources,
@@ -191,18 +625,7 @@ impl ServerHandler for VttMcpServer {
         context: RequestContext<RoleServer>,
     ) -> impl std::future::Future<Output = Result<(), McpError>> + Send + '_ {
         async move {
-            let uri = request.uri;
-
-            // Parse session_id from URI
-            if !uri.starts_with("transcript://live/") {
-                return Err(McpError::from(VttError::invalid_params(
-                    "Invalid resource URI. Expected: transcript://live/{session_id}"
-                )));
-            }
-
-            let session_id_str = uri.strip_prefix("transcript://live/").unwrap();
-            let session_id = Uuid::parse_str(session_id_str)
-                .map_err(|_| McpError::from(VttError::invalid_params("Invalid session ID format")))?;
+            let session_id = live_session_id(&request.uri)?;
 
             // Verify session exists and is listening
             let sessions = self.sessions.lock().await;
@@ -211,14 +634,13 @@ impl ServerHandler for VttMcpServer {
             }
             drop(sessions);
 
-            // Generate a unique client ID from connection info
-            let client_id = format!("{:?}", std::ptr::addr_of!(context));
+            let client_id = self.connection_id.to_string();
 
             // Add subscriber
-            self.add_subscriber(session_id, client_id.clone()).await
+            self.add_subscriber(session_id, client_id.clone(), context.peer).await
                 .map_err(|e| McpError::from(VttError::internal(e.to_string())))?;
 
-            tracing::info!("Client {} subscribed to transcript://live/{}", client_id, session_id);
+            tracing::info!("Client {} subscribed to {}", client_id, request.uri);
 
             Ok(())
         }
@@ -228,80 +650,125 @@ impl ServerHandler for VttMcpServer {
     fn unsubscribe(
         &self,
         request: UnsubscribeRequestParam,
-        context: RequestContext<RoleServer>,
+        _context: RequestContext<RoleServer>,
     ) -> impl std::future::Future<Output = Result<(), McpError>> + Send + '_ {
         async move {
-            let uri = request.uri;
+            let session_id = live_session_id(&request.uri)?;
 
-            if !uri.starts_with("transcript://live/") {
-                return Err(McpError::from(VttError::invalid_params("Invalid resource URI")));
-            }
-
-            let session_id_str = uri.strip_prefix("transcript://live/").unwrap();
-            let session_id = Uuid::parse_str(session_id_str)
-                .map_err(|_| McpError::from(VttError::invalid_params("Invalid session ID format")))?;
-
-            let client_id = format!("{:?}", std::ptr::addr_of!(context));
+            let client_id = self.connection_id.to_string();
 
             self.remove_subscriber(session_id, &client_id).await
                 .map_err(|e| McpError::from(VttError::internal(e.to_string())))?;
 
-            tracing::info!("Client {} unsubscribed from transcript://live/{}", client_id, session_id);
+            tracing::info!("Client {} unsubscribed from {}", client_id, request.uri);
 
             Ok(())
         }
     }
 
-    /// Read current transcription state
+    /// Read a resource by URI
     fn read_resource(
         &self,
         request: ReadResourceRequestParam,
         _context: RequestContext<RoleServer>,
     ) -> impl std::future::Future<Output = Result<ReadResourceResult, McpError>> + Send + '_ {
         async move {
-            let uri = request.uri;
-
-            if !uri.starts_with("transcript://live/") {
-                return Err(McpError::from(VttError::invalid_params("Invalid resource URI")));
-            }
-
-            let session_id_str = uri.strip_prefix("transcript://live/").unwrap();
-            let session_id = Uuid::parse_str(session_id_str)
-                .map_err(|_| McpError::from(VttError::invalid_params("Invalid session ID format")))?;
-
-            let sessions = self.sessions.lock().await;
-            let session = sessions.get(&session_id)
-                .ok_or_else(|| McpError::from(VttError::invalid_params("Session not found")))?;
-
-            let text = session.transcription.as_ref()
-                .map(|t| t.text.clone())
-                .unwrap_or_else(|| "No transcription yet".to_string());
-
-            let contents = vec![
-                ResourceContents::text(text, uri)
-            ];
-
-            Ok(ReadResourceResult { contents })
+            let uri = ResourceUri::parse(&request.uri).map_err(McpError::from)?;
+            let session_id = uri.session_id();
+            let mime_type = Some(uri.mime_type().to_string());
+
+            let contents = match uri {
+                ResourceUri::LiveTranscript(_) => {
+                    let sessions = self.sessions.lock().await;
+                    let session = sessions.get(&session_id)
+                        .ok_or_else(|| McpError::from(VttError::invalid_params("Session not found")))?;
+
+                    let text = match &session.transcription {
+                        Some(t) => t.text.clone(),
+                        None if !session.committed_text.is_empty() || !session.tentative_text.is_empty() => {
+                            join_text(&session.committed_text, &session.tentative_text)
+                        }
+                        None => "No transcription yet".to_string(),
+                    };
+                    ResourceContents::TextResourceContents { uri: request.uri, mime_type, text, meta: None }
+                }
+                ResourceUri::HistoryTranscript(_) => {
+                    let transcription = self.find_transcription(session_id).await
+                        .ok_or_else(|| McpError::from(VttError::invalid_params("Session has no transcription")))?;
+                    let text = export(&transcription.core_segments(), ExportFormat::Json, &ExportOptions::default())
+                        .map_err(|e| McpError::from(VttError::internal(e.to_string())))?;
+                    ResourceContents::TextResourceContents { uri: request.uri, mime_type, text, meta: None }
+                }
+                ResourceUri::Session(_) => {
+                    let sessions = self.sessions.lock().await;
+                    let session = sessions.get(&session_id)
+                        .ok_or_else(|| McpError::from(VttError::invalid_params("Session not found")))?;
+                    let text = serde_json::to_string_pretty(&SessionInfo::new(session_id, session))
+                        .map_err(|e| McpError::from(VttError::internal(e.to_string())))?;
+                    ResourceContents::TextResourceContents { uri: request.uri, mime_type, text, meta: None }
+                }
+                ResourceUri::SessionAudio(_) => {
+                    let sessions = self.sessions.lock().await;
+                    let session = sessions.get(&session_id)
+                        .ok_or_else(|| McpError::from(VttError::invalid_params("Session not found")))?;
+                    let wav = session.recording.clone()
+                        .ok_or_else(|| McpError::from(VttError::NoAudioData("Session has no recording yet".to_string())))?;
+                    drop(sessions);
+                    let blob = base64::engine::general_purpose::STANDARD.encode(&*wav);
+                    ResourceContents::BlobResourceContents { uri: request.uri, mime_type, blob, meta: None }
+                }
+            };
+
+            Ok(ReadResourceResult { contents: vec![contents] })
         }
     }
 
-    /// List resource templates (not used yet, returning empty)
+    /// List the URI templates of every resource type
     fn list_resource_templates(
         &self,
         _request: Option<PaginatedRequestParam>,
         _context: RequestContext<RoleServer>,
     ) -> impl std::future::Future<Output = Result<ListResourceTemplatesResult, McpError>> + Send + '_ {
         async move {
-            Ok(ListResourceTemplatesResult::default())
+            Ok(ListResourceTemplatesResult {
+                resource_templates: ResourceUri::templates(),
+                next_cursor: None,
+                meta: None,
+            })
         }
     }
 }
 
+/// Listing entry for a concrete resource
+fn resource(uri: ResourceUri, title: String) -> Resource {
+    let raw = RawResource {
+        uri: uri.to_string(),
+        name: uri.to_string(),
+        title: Some(title),
+        description: None,
+        mime_type: Some(uri.mime_type().to_string()),
+        size: None,
+        icons: None,
+        meta: None,
+    };
+    Annotated::new(raw, None)
+}
+
+/// Session id of a `transcript://live/` URI, the only subscribable resource
+fn live_session_id(uri: &str) -> Result<Uuid, McpError> {
+    match ResourceUri::parse(uri).map_err(McpError::from)? {
+        ResourceUri::LiveTranscript(session_id) => Ok(session_id),
+        _ => Err(McpError::from(VttError::invalid_params(
+            "Only transcript://live/{session_id} supports subscriptions"
+        ))),
+    }
+}
+
 /// Tool router implementation
 #[tool_router]
 impl VttMcpServer {
     /// List supported languages
-    #[tool(description = "List all supported languages for transcription")]
+    #[tool(description = "List all supported languages for transcription", output_schema = output_schema::<LanguagesListResult>())]
     async fn list_languages(
         &self,
         _params: Parameters<ListLanguagesParams>,
@@ -318,117 +785,154 @@ impl VttMcpServer {
         
         lines.push(String::new());
         lines.push(format!("Total: {} languages", SUPPORTED_LANGUAGES.len() + 1));
-        
-        Ok(CallToolResult::success(vec![
-            Content::text(lines.join("\n"))
-        ]))
+
+        let languages: Vec<LanguageInfo> = std::iter::once(LanguageInfo {
+            code: "auto".to_string(),
+            name: "Auto-detect language".to_string(),
+        })
+        .chain(SUPPORTED_LANGUAGES.iter().map(|lang| LanguageInfo {
+            code: lang.code.to_string(),
+            name: lang.name.to_string(),
+        }))
+        .collect();
+
+        structured_result(&LanguagesListResult { total: languages.len(), languages }, lines.join("\n"))
     }
 
     /// Transcribe an audio clip file
-    #[tool(description = "Transcribe an audio clip from a WAV file")]
+    #[tool(
+        description = "Transcribe an audio file (WAV, FLAC, OGG/Opus, MP3, M4A, or the audio track of MP4/MKV/WebM)",
+        output_schema = output_schema::<TranscribeClipResult>()
+    )]
     async fn transcribe_clip(
         &self,
         params: Parameters<TranscribeClipParams>,
+        context: RequestContext<RoleServer>,
     ) -> Result<CallToolResult, McpError> {
         let p = params.0;
-        
-        use hound::WavReader;
-        use std::path::Path;
-
-        let path = Path::new(&p.audio_file);
-        if !path.exists() {
-            return Err(McpError::from(VttError::invalid_params(format!("Audio file not found: {}", p.audio_file))));
-        }
-
-        let reader = WavReader::open(&path)
-            .map_err(|e| McpError::from(VttError::AudioFile(e)))?;
-
-        let samples: Vec<f32> = reader
-            .into_samples::<i16>()
-            .filter_map(|s| s.ok())
-            .map(|s| s as f32 / 32768.0)
-            .collect();
-
-        if samples.is_empty() {
-            return Err(McpError::from(VttError::NoAudioData("Audio file contains no samples".to_string())));
-        }
-
-        // Validate language if provided
-        if let Some(ref lang) = p.language {
-            if lang != "auto" && !Language::is_valid(lang) {
-                return Err(McpError::from(VttError::invalid_params(format!(
-                    "Unsupported language code: '{}'. Use list_languages tool to see supported languages.",
-                    lang
-                ))));
-            }
-        }
-
-        let model_path = p.model_path
-            .or_else(|| std::env::var("WHISPER_MODEL").ok())
-            .unwrap_or_else(|| "models/ggml-base.bin".to_string());
-
-        let threads = p.threads
-            .or_else(|| std::env::var("WHISPER_THREADS").ok().and_then(|t| t.parse().ok()))
-            .unwrap_or_else(|| num_cpus::get()) as i32;
-
-        let use_gpu = p.use_gpu
-            .or_else(|| std::env::var("WHISPER_USE_GPU").ok().and_then(|g| g.parse().ok()))
-            .unwrap_or(true);
-
-        // Convert language option for Whisper config (None means auto-detect)
-        let language = p.language.as_ref().and_then(|l| {
-            if l == "auto" { None } else { Some(l.clone()) }
-        });
 
-        let config = WhisperConfig {
-            model_path,
-            language,
-            use_gpu,
-            n_threads: threads,
-            ..Default::default()
-        };
+        let started_at = Utc::now();
+        let audio = decode_clip(&p.audio_file).await.map_err(McpError::from)?;
+
+        let config = self
+            .whisper_config(WhisperOverrides {
+                model_path: p.model_path,
+                language: p.language.clone(),
+                threads: p.threads,
+                use_gpu: p.use_gpu,
+                task: p.task,
+                initial_prompt: p.initial_prompt,
+                vocabulary: p.vocabulary,
+                decoding: p.decoding,
+            })
+            .map_err(McpError::from)?;
 
         let config_for_history = config.clone();
 
-        let ctx = WhisperContext::new(config)
-            .map_err(|e| McpError::from(VttError::Model(e.to_string())))?;
+        let model = self.acquire_model(config).await.map_err(McpError::from)?;
 
         let start_ms = 0u64;
-        let duration_ms = (samples.len() as f64 / 16000.0 * 1000.0) as u64;
+        let duration_ms = audio.duration_ms();
 
-        let transcription = ctx.transcribe(&samples, 16000)
-            .map_err(|e| McpError::from(VttError::Transcription(e)))?;
+        let (samples, chunks) = self.plan_clip_chunks(audio.to_whisper_input()).await.map_err(McpError::from)?;
+        let include_original = p.include_original.unwrap_or(false);
+        let progress = ProgressReporter::from_context(&context);
+        let transcription = transcribe_samples(&model, samples, WHISPER_SAMPLE_RATE, chunks, include_original, progress)
+            .await
+            .map_err(McpError::from)?;
 
-        let result = TranscribeClipResult {
-            text: transcription.text.clone(),
-            confidence: None,
+        let history_entry = TranscriptionResult {
             start_ms,
             end_ms: start_ms + duration_ms,
+            ..transcription
         };
 
-        let history_entry = TranscriptionResult {
-            text: result.text.clone(),
-            confidence: result.confidence,
-            start_ms: result.start_ms,
-            end_ms: result.end_ms,
+        let session_id = Uuid::new_v4();
+        let result = TranscribeClipResult {
+            session_id: session_id.to_string(),
+            text: history_entry.text.clone(),
+            confidence: history_entry.confidence,
+            start_ms: history_entry.start_ms,
+            end_ms: history_entry.end_ms,
+            // Whisper reports the language it used, detected or not
+            language: history_entry.language.clone().or(config_for_history.language.clone()),
+            segments: history_entry.segments.clone(),
+            task: config_for_history.task().to_string(),
+            original: history_entry.original.clone(),
         };
+        let segment_lines = history_entry.segment_lines();
+
+        let source = HistorySource::File { path: p.audio_file.clone() };
+        self.store_transcription_in_history(session_id, started_at, config_for_history, source, history_entry).await;
+
+        let mut text = format!(
+            "Transcription: {}\nConfidence: {:?}\nDuration: {}ms\nLanguage: {}",
+            result.text,
+            result.confidence,
+            result.end_ms - result.start_ms,
+            result.language.as_deref().map_or_else(|| "unknown".to_string(), display_name)
+        );
+        if !segment_lines.is_empty() {
+            text.push_str("\n\nSegments:\n");
+            text.push_str(&segment_lines.join("\n"));
+        }
 
-        let session_id = Uuid::new_v4();
-        self.store_transcription_in_history(session_id, config_for_history, history_entry).await;
-
-        Ok(CallToolResult::success(vec![
-            Content::text(format!(
-                "Transcription: {}\nConfidence: {:?}\nDuration: {}ms\nLanguage: {:?}",
-                result.text,
-                result.confidence,
-                result.end_ms - result.start_ms,
-                p.language.unwrap_or_else(|| "auto".to_string())
-            ))
-        ]))
+        structured_result(&result, text)
+    }
+
+    /// Detect the spoken language of an audio clip
+    #[tool(description = "Detect the spoken language of an audio or video file, with a probability per candidate language", output_schema = output_schema::<DetectLanguageResult>())]
+    async fn detect_language(
+        &self,
+        params: Parameters<DetectLanguageParams>,
+    ) -> Result<CallToolResult, McpError> {
+        let p = params.0;
+        let top_n = p.top_n.unwrap_or(DETECT_LANGUAGE_TOP_N);
+        if top_n == 0 {
+            return Err(McpError::from(VttError::invalid_params("top_n must be greater than 0")));
+        }
+
+        let audio = decode_clip(&p.audio_file).await.map_err(McpError::from)?;
+        let config = self
+            .whisper_config(WhisperOverrides {
+                model_path: p.model_path,
+                threads: p.threads,
+                use_gpu: p.use_gpu,
+                ..Default::default()
+            })
+            .map_err(McpError::from)?;
+        let model = self.acquire_model(config).await.map_err(McpError::from)?;
+
+        let detected = tokio::task::spawn_blocking(move || {
+            model.detect_language(&audio.to_whisper_input(), WHISPER_SAMPLE_RATE, top_n)
+        })
+        .await
+        .map_err(|e| McpError::from(VttError::internal(e.to_string())))?
+        .map_err(|e| match e {
+            WhisperError::InvalidParameter(_) => McpError::from(VttError::invalid_params(e.to_string())),
+            e => McpError::from(VttError::Transcription(e)),
+        })?;
+
+        let languages: Vec<DetectedLanguage> = detected
+            .iter()
+            .map(|d| DetectedLanguage {
+                code: d.language.code.to_string(),
+                name: d.language.name.to_string(),
+                probability: d.probability,
+            })
+            .collect();
+        let Some(best) = languages.first() else {
+            return Err(McpError::from(VttError::internal("Whisper returned no language probabilities")));
+        };
+
+        let mut lines = vec![format!("Detected language: {} ({})", best.name, best.code)];
+        lines.extend(languages.iter().map(|l| format!("  {} - {}: {:.1}%", l.code, l.name, l.probability * 100.0)));
+        let result = DetectLanguageResult { language: best.code.clone(), languages };
+        structured_result(&result, lines.join("\n"))
     }
 
     /// Start listening for audio
-    #[tool(description = "Start capturing audio from microphone")]
+    #[tool(description = "Start capturing audio from microphone", output_schema = output_schema::<StartListeningResult>())]
     async fn start_listening(
         &self,
         params: Parameters<StartListeningParams>,
@@ -438,71 +942,89 @@ impl VttMcpServer {
         let session_id = Uuid::new_v4();
         let start_time = Utc::now();
 
-        let model_path = p.model_path
-            .or_else(|| std::env::var("WHISPER_MODEL").ok())
-            .unwrap_or_else(|| "models/ggml-base.bin".to_string());
+        let config = self
+            .whisper_config(WhisperOverrides {
+                model_path: p.model_path,
+                language: p.language.clone(),
+                threads: p.threads,
+                use_gpu: p.use_gpu,
+                task: p.task,
+                initial_prompt: p.initial_prompt,
+                vocabulary: p.vocabulary,
+                decoding: p.decoding,
+            })
+            .map_err(McpError::from)?;
+        let model_path = config.model_path.clone();
+        let use_gpu = config.use_gpu;
+        let carry_prompt = p.carry_prompt.unwrap_or(true);
 
-        let threads = p.threads
-            .or_else(|| std::env::var("WHISPER_THREADS").ok().and_then(|t| t.parse().ok()))
-            .unwrap_or_else(|| num_cpus::get()) as i32;
+        // Refuse before loading a model or opening the device
+        let max_concurrent = self.config.sessions.max_concurrent;
+        check_listening_limit(&*self.sessions.lock().await, max_concurrent)?;
 
-        let use_gpu = p.use_gpu
-            .or_else(|| std::env::var("WHISPER_USE_GPU").ok().and_then(|g| g.parse().ok()))
-            .unwrap_or(true);
+        let segmenter = self
+            .vad_segmenter(self.config.vad.segmenter_config(WHISPER_SAMPLE_RATE))
+            .map_err(McpError::from)?;
 
-        // Validate language if provided
-        if let Some(ref lang) = p.language {
-            if lang != "auto" && !Language::is_valid(lang) {
-                return Err(McpError::from(VttError::invalid_params(format!(
-                    "Unsupported language code: '{}'. Use list_languages tool to see supported languages.",
-                    lang
-                ))));
-            }
-        }
+        // Fail up front if the model can't be loaded or is already fully booked
+        let model = self.acquire_model(config.clone()).await.map_err(McpError::from)?;
 
-        // Convert language option for Whisper config (None means auto-detect)
-        let language = p.language.as_ref().and_then(|l| {
-            if l == "auto" { None } else { Some(l.clone()) }
-        });
+        let (capture, device_name) = self.open_capture(p.device_name).await.map_err(McpError::from)?;
 
-        let config = WhisperConfig {
-            model_path: model_path.clone(),
-            language,
-            use_gpu,
-            n_threads: threads,
-            ..Default::default()
-        };
+        let (stop_tx, stop_rx) = oneshot::channel();
+        let task = tokio::spawn(
+            self.clone().run_live_transcription(session_id, capture, model, segmenter, carry_prompt, stop_rx)
+        );
 
-        let _audio_config = self.audio_config.lock().await;
-        let capture = AudioCapture::new().map_err(|e| McpError::from(VttError::Audio(e)))?;
+        let live = LiveSession { stop_tx, task };
 
-        let session = SessionState {
+        let mut sessions = self.sessions.lock().await;
+        // Another call may have started a session while we opened the device
+        if let Err(e) = check_listening_limit(&sessions, max_concurrent) {
+            drop(sessions);
+            if let Ok(LiveCapture { mut capture, .. }) = live.finish().await {
+                let _ = capture.stop();
+            }
+            return Err(e);
+        }
+        sessions.insert(session_id, SessionState {
             status: SessionStatus::Listening,
             start_time,
-            capture: Some(capture),
+            live: Some(live),
+            cancel: None,
             config,
+            device: device_name,
+            committed_text: String::new(),
+            tentative_text: String::new(),
             transcription: None,
             transcription_timestamp: None,
             error: None,
-        };
-
-        let mut sessions = self.sessions.lock().await;
-        sessions.insert(session_id, session);
+            recording: None,
+        });
+        drop(sessions);
 
         let language_display = p.language.as_ref()
             .map(|l| display_name(l))
             .unwrap_or_else(|| "Auto-detect".to_string());
 
-        Ok(CallToolResult::success(vec![
-            Content::text(format!(
-                "Started listening session: {}\nModel: {}\nLanguage: {}\nGPU: {}\nResource: transcript://live/{}",
-                session_id, model_path, language_display, use_gpu, session_id
-            ))
-        ]))
+        let result = StartListeningResult {
+            session_id: session_id.to_string(),
+            status: "listening".to_string(),
+            start_time,
+            model_path,
+            language: p.language,
+            use_gpu,
+            resource_uri: ResourceUri::LiveTranscript(session_id).to_string(),
+        };
+        let text = format!(
+            "Started listening session: {}\nModel: {}\nLanguage: {}\nGPU: {}\nResource: {}",
+            result.session_id, result.model_path, language_display, result.use_gpu, result.resource_uri
+        );
+        structured_result(&result, text)
     }
 
     /// Stop listening and transcribe
-    #[tool(description = "Stop capturing audio and optionally transcribe")]
+    #[tool(description = "Stop capturing audio and optionally transcribe", output_schema = output_schema::<StopListeningResult>())]
     async fn stop_listening(
         &self,
         params: Parameters<StopListeningParams>,
@@ -511,111 +1033,588 @@ impl VttMcpServer {
         let session_uuid = p.session_id.parse::<Uuid>()
             .map_err(|_| McpError::from(VttError::invalid_params("Invalid session_id format")))?;
 
-        let mut sessions = self.sessions.lock().await;
-        
-        let (config_clone, duration_ms) = {
+        let (live, config, start_time, device) = {
+            let mut sessions = self.sessions.lock().await;
             let session = sessions.get_mut(&session_uuid)
                 .ok_or_else(|| McpError::from(VttError::invalid_params("Session not found")))?;
 
             if session.status != SessionStatus::Listening {
                 return Err(McpError::from(VttError::invalid_params("Session is not listening")));
             }
+            if session.cancel.is_some() {
+                return Err(McpError::from(VttError::invalid_params(
+                    "Session belongs to a listen_once call; stop it with cancel_session",
+                )));
+            }
 
-            let duration_ms = (Utc::now() - session.start_time).num_milliseconds() as u64;
-            let config_clone = session.config.clone();
+            // Leave Listening right away so a concurrent stop can't race us
+            session.status = SessionStatus::Stopped;
+            (session.live.take(), session.config.clone(), session.start_time, session.device.clone())
+        };
 
-            (config_clone, duration_ms)
+        // Stop the live task, then drain what's left of the capture as mono
+        let (samples, mono_format, model) = match live {
+            Some(live) => {
+                let drained = live.finish().await
+                    .and_then(|capture| capture.drain().map_err(|e| McpError::from(VttError::Audio(e))));
+                match drained {
+                    Ok((samples, format, model)) => (samples, format, Some(model)),
+                    Err(e) => {
+                        // Don't leave the session looking like a clean stop
+                        if let Some(session) = self.sessions.lock().await.get_mut(&session_uuid) {
+                            session.status = SessionStatus::Error;
+                            session.error = Some(e.message.to_string());
+                            session.transcription_timestamp = Some(Utc::now());
+                        }
+                        tracing::warn!(session_id = %session_uuid, error = %e.message, "Failed to stop capture");
+                        self.notify_subscribers(session_uuid).await;
+                        self.cleanup_subscribers(session_uuid).await;
+                        return Err(e);
+                    }
+                }
+            }
+            None => (Vec::new(), AudioFormat::STT_DEFAULT, None),
         };
+        let samples_captured = samples.len();
+        let audio_ms = mono_format.duration_ms(samples_captured);
+        let duration_ms = (Utc::now() - start_time).num_milliseconds().max(0) as u64;
 
-        let session = sessions.get_mut(&session_uuid).unwrap();
-        let _samples_captured = 0;
+        // Kept for audio://session/{id}.wav
+        let recording = encode_recording(session_uuid, &samples, &mono_format);
 
-        let transcription = if p.transcribe.unwrap_or(true) {
-            Some(TranscriptionResult {
-                text: "Placeholder transcription".to_string(),
-                confidence: None,
-                start_ms: 0,
-                end_ms: duration_ms,
-            })
-        } else {
+        let mut error = None;
+        let transcription = if !p.transcribe.unwrap_or(true) {
             None
+        } else if samples.is_empty() {
+            error = Some("No audio captured".to_string());
+            None
+        } else {
+            // Reuse the live session's model rather than checking out another
+            let model = match model {
+                Some(model) => Ok(model),
+                None => self.acquire_model(config.clone()).await,
+            };
+            let sample_rate = mono_format.sample_rate;
+            let include_original = p.include_original.unwrap_or(false);
+            let outcome = match model {
+                Ok(model) => {
+                    let chunks = vec![AudioChunk::whole(samples.len())];
+                    transcribe_samples(&model, samples, sample_rate, chunks, include_original, None).await
+                }
+                Err(e) => Err(e),
+            };
+
+            match outcome {
+                Ok(tx) => Some(TranscriptionResult {
+                    start_ms: 0,
+                    end_ms: audio_ms,
+                    ..tx
+                }),
+                Err(e) => {
+                    tracing::warn!(session_id = %session_uuid, error = %e, "Transcription failed");
+                    error = Some(e.to_string());
+                    None
+                }
+            }
         };
 
-        session.status = if transcription.is_some() {
+        let status = if error.is_some() {
+            SessionStatus::Error
+        } else if transcription.is_some() {
             SessionStatus::Transcribed
         } else {
             SessionStatus::Stopped
         };
 
-        session.transcription = transcription.clone();
-        session.transcription_timestamp = Some(Utc::now());
+        let result = {
+            let mut sessions = self.sessions.lock().await;
+            let session = sessions.get_mut(&session_uuid)
+                .ok_or_else(|| McpError::from(VttError::invalid_params("Session not found")))?;
+
+            session.status = status;
+            session.transcription = transcription.clone();
+            session.transcription_timestamp = Some(Utc::now());
+            session.error = error.clone();
+            session.recording = recording;
+
+            StopListeningResult {
+                session_id: session_uuid.to_string(),
+                status: session.status_display().to_string(),
+                duration_ms,
+                samples_captured,
+                transcription,
+                error,
+            }
+        };
+
+        if let Some(tx) = &result.transcription {
+            self.broadcast_transcription(TranscriptionUpdate {
+                session_id: session_uuid,
+                text: tx.text.clone(),
+                committed_text: tx.text.clone(),
+                tentative_text: String::new(),
+                is_final: true,
+                timestamp: Utc::now(),
+                confidence: tx.confidence,
+            }).await;
+        }
 
         // Cleanup subscribers when session ends
-        drop(sessions);
         self.cleanup_subscribers(session_uuid).await;
 
-        if let Some(tx) = &transcription {
-            let tx_clone = tx.clone();
-            self.store_transcription_in_history(session_uuid, config_clone, tx_clone).await;
+        if let Some(tx) = &result.transcription {
+            let source = HistorySource::Microphone { device };
+            self.store_transcription_in_history(session_uuid, start_time, config, source, tx.clone()).await;
         }
 
-        Ok(CallToolResult::success(vec![
-            Content::text(format!(
-                "Session {} stopped. Status: {}. Duration: {}ms",
-                session_uuid,
-                if transcription.is_some() { "transcribed" } else { "stopped" },
-                duration_ms
-            ))
-        ]))
+        let mut lines = vec![format!(
+            "Session {} stopped. Status: {}. Duration: {}ms. Samples: {}",
+            result.session_id, result.status, result.duration_ms, result.samples_captured
+        )];
+        if let Some(tx) = &result.transcription {
+            lines.push(format!("Transcription: {}", tx.text));
+            lines.extend(tx.segment_lines());
+        }
+        if let Some(err) = &result.error {
+            lines.push(format!("Error: {}", err));
+        }
+
+        structured_result(&result, lines.join("\n"))
     }
 
-    /// Get last transcription
-    #[tool(description = "Get the most recent transcription")]
-    async fn get_last_transcription(
+    /// Listen for one utterance and transcribe it
+    #[tool(
+        description = "Listen until the user has said one thing and return the text. Waits for speech, then stops after trailing silence or a maximum duration",
+        output_schema = output_schema::<ListenOnceResult>()
+    )]
+    async fn listen_once(
         &self,
-        params: Parameters<GetLastTranscriptionParams>,
+        params: Parameters<ListenOnceParams>,
     ) -> Result<CallToolResult, McpError> {
         let p = params.0;
-        
-        if let Some(session_id_str) = p.session_id {
-            let session_uuid = session_id_str.parse::<Uuid>()
-                .map_err(|_| McpError::from(VttError::invalid_params("Invalid session_id format")))?;
-
-            let sessions = self.sessions.lock().await;
-            let session = sessions.get(&session_uuid)
-                .ok_or_else(|| McpError::from(VttError::invalid_params("Session not found")))?;
+        let started_at = Utc::now();
+
+        let silence_ms = p.silence_ms.unwrap_or(LISTEN_ONCE_SILENCE_MS);
+        let speech_timeout_secs = p.speech_timeout_secs.unwrap_or(LISTEN_ONCE_SPEECH_TIMEOUT_SECS);
+        let max_recording_secs = self.config.sessions.max_recording_secs;
+        let max_duration_secs = p.max_duration_secs.unwrap_or(match max_recording_secs {
+            0 => LISTEN_ONCE_MAX_DURATION_SECS,
+            limit => LISTEN_ONCE_MAX_DURATION_SECS.min(limit),
+        });
+        if silence_ms == 0 || speech_timeout_secs == 0 || max_duration_secs == 0 {
+            return Err(McpError::from(VttError::invalid_params(
+                "silence_ms, speech_timeout_secs and max_duration_secs must be greater than 0",
+            )));
+        }
+        if max_recording_secs > 0 && max_duration_secs > max_recording_secs {
+            return Err(McpError::from(VttError::invalid_params(format!(
+                "max_duration_secs can't exceed the server's maximum recording duration ({}s)",
+                max_recording_secs
+            ))));
+        }
 
-            let transcription = session.transcription.as_ref()
-                .ok_or_else(|| McpError::from(VttError::invalid_params("Session has no transcription".to_string())))?;
+        let config = self
+            .whisper_config(WhisperOverrides {
+                model_path: p.model_path,
+                language: p.language,
+                threads: p.threads,
+                use_gpu: p.use_gpu,
+                task: p.task,
+                initial_prompt: p.initial_prompt,
+                vocabulary: p.vocabulary,
+                decoding: p.decoding,
+            })
+            .map_err(McpError::from)?;
+
+        // Refuse before loading a model or opening the device
+        let max_concurrent = self.config.sessions.max_concurrent;
+        check_listening_limit(&*self.sessions.lock().await, max_concurrent)?;
+
+        // The loop below enforces the maximum duration, so the segmenter
+        // never splits the utterance itself
+        let segmenter = self
+            .vad_segmenter(SegmenterConfig {
+                min_silence_ms: silence_ms,
+                max_segment_ms: u32::MAX,
+                ..self.config.vad.segmenter_config(WHISPER_SAMPLE_RATE)
+            })
+            .map_err(McpError::from)?;
+        let model = self.acquire_model(config.clone()).await.map_err(McpError::from)?;
 
-            Ok(CallToolResult::success(vec![
-                Content::text(format!(
-                    "Session: {}\nText: {}\nConfidence: {:?}\nTime: {}ms",
-                    session_uuid,
-                    transcription.text,
-                    transcription.confidence,
-                    transcription.end_ms - transcription.start_ms
-                ))
-            ]))
+        // Listen as a session, so the call counts against the limit, shows
+        // up in list_sessions and can be stopped with cancel_session
+        let session_id = Uuid::new_v4();
+        let (cancel_tx, cancel_rx) = oneshot::channel();
+        {
+            let mut sessions = self.sessions.lock().await;
+            check_listening_limit(&sessions, max_concurrent)?;
+            sessions.insert(session_id, SessionState {
+                status: SessionStatus::Listening,
+                start_time: started_at,
+                live: None,
+                cancel: Some(cancel_tx),
+                config: config.clone(),
+                device: None,
+                committed_text: String::new(),
+                tentative_text: String::new(),
+                transcription: None,
+                transcription_timestamp: None,
+                error: None,
+                recording: None,
+            });
+        }
+
+        let captured = match self.open_capture(p.device_name).await {
+            Ok((capture, device)) => capture_utterance(
+                capture,
+                segmenter,
+                Duration::from_secs(speech_timeout_secs),
+                Duration::from_secs(max_duration_secs),
+                cancel_rx,
+            )
+            .await
+            .map(|captured| (captured, device)),
+            Err(e) => Err(e),
+        };
+        let ((recording, utterance, stop_reason), device) = match captured {
+            Ok(captured) => captured,
+            Err(e) => {
+                self.fail_session(session_id, e.to_string()).await;
+                return Err(McpError::from(e));
+            }
+        };
+        if stop_reason == "cancelled" {
+            // Either cancel_session removed the session or a limit failed it
+            let reason = self.sessions.lock().await.get(&session_id).and_then(|s| s.error.clone());
+            return Err(McpError::from(VttError::Session(format!(
+                "Session {} stopped: {}",
+                session_id,
+                reason.as_deref().unwrap_or("cancelled")
+            ))));
+        }
+        let listened_ms = AudioFormat::STT_DEFAULT.duration_ms(recording.len());
+
+        let transcription = match utterance {
+            Some(segment) => {
+                let samples = recording[segment.start_sample..segment.end_sample].to_vec();
+                let include_original = p.include_original.unwrap_or(false);
+                let chunks = vec![AudioChunk::whole(samples.len())];
+                match transcribe_samples(&model, samples, WHISPER_SAMPLE_RATE, chunks, include_original, None).await {
+                    Ok(tx) => Some(TranscriptionResult {
+                        start_ms: segment.start_ms(WHISPER_SAMPLE_RATE),
+                        end_ms: segment.end_ms(WHISPER_SAMPLE_RATE),
+                        ..tx
+                    }),
+                    Err(e) => {
+                        self.fail_session(session_id, e.to_string()).await;
+                        return Err(McpError::from(e));
+                    }
+                }
+            }
+            None => None,
+        };
+
+        if let Some(session) = self.sessions.lock().await.get_mut(&session_id) {
+            session.status = if transcription.is_some() { SessionStatus::Transcribed } else { SessionStatus::Stopped };
+            session.cancel = None;
+            session.device = device.clone();
+            session.transcription = transcription.clone();
+            session.transcription_timestamp = Some(Utc::now());
+            session.recording = encode_recording(session_id, &recording, &AudioFormat::STT_DEFAULT);
+        }
+
+        let session_id = match &transcription {
+            Some(tx) => {
+                let source = HistorySource::Microphone { device };
+                self.store_transcription_in_history(session_id, started_at, config, source, tx.clone()).await;
+                Some(session_id.to_string())
+            }
+            None => None,
+        };
+
+        let result = ListenOnceResult {
+            session_id,
+            text: transcription.as_ref().map(|tx| tx.text.trim().to_string()).unwrap_or_default(),
+            stop_reason: stop_reason.to_string(),
+            listened_ms,
+            transcription,
+        };
+        let text = match &result.transcription {
+            Some(_) => format!("Heard: {}\nStopped: {} after {}ms", result.text, result.stop_reason, result.listened_ms),
+            None => format!("No speech detected. Stopped: {} after {}ms", result.stop_reason, result.listened_ms),
+        };
+        structured_result(&result, text)
+    }
+
+    /// List known sessions
+    #[tool(
+        description = "List listening and finished sessions, oldest first, optionally filtered by status",
+        output_schema = output_schema::<ListSessionsResult>()
+    )]
+    async fn list_sessions(
+        &self,
+        params: Parameters<ListSessionsParams>,
+    ) -> Result<CallToolResult, McpError> {
+        let p = params.0;
+        if let Some(status) = p.status.as_deref() {
+            if !["listening", "stopped", "transcribed", "error"].contains(&status) {
+                return Err(McpError::from(VttError::invalid_params(format!(
+                    "Unknown status '{}': expected listening, stopped, transcribed or error",
+                    status
+                ))));
+            }
+        }
+
+        let mut sessions: Vec<SessionInfo> = self.sessions.lock().await
+            .iter()
+            .map(|(id, session)| SessionInfo::new(*id, session))
+            .filter(|info| p.status.as_ref().is_none_or(|status| &info.status == status))
+            .collect();
+        sessions.sort_by_key(|info| info.start_time);
+
+        let mut lines = vec![format!("{} sessions", sessions.len())];
+        lines.extend(sessions.iter().map(|info| format!(
+            "{} {} started {} ({}ms){}",
+            info.session_id,
+            info.status,
+            info.start_time.to_rfc3339_opts(chrono::SecondsFormat::Secs, true),
+            info.elapsed_ms,
+            info.error.as_ref().map(|e| format!(": {}", e)).unwrap_or_default()
+        )));
+
+        structured_result(&ListSessionsResult { sessions }, lines.join("\n"))
+    }
+
+    /// Get one session's status
+    #[tool(
+        description = "Get the status, configuration and timings of a session",
+        output_schema = output_schema::<SessionInfo>()
+    )]
+    async fn get_session_status(
+        &self,
+        params: Parameters<GetSessionStatusParams>,
+    ) -> Result<CallToolResult, McpError> {
+        let session_uuid = params.0.session_id.parse::<Uuid>()
+            .map_err(|_| McpError::from(VttError::invalid_params("Invalid session_id format")))?;
+
+        let info = self.sessions.lock().await
+            .get(&session_uuid)
+            .map(|session| SessionInfo::new(session_uuid, session))
+            .ok_or_else(|| McpError::from(VttError::invalid_params("Session not found")))?;
+
+        let mut text = format!(
+            "Session {}: {}. Elapsed: {}ms. Model: {}",
+            info.session_id, info.status, info.elapsed_ms, info.model_path
+        );
+        if let Some(err) = &info.error {
+            text.push_str(&format!("\nError: {}", err));
+        }
+        structured_result(&info, text)
+    }
+
+    /// Cancel a session
+    #[tool(
+        description = "Cancel a session: stop any capture without transcribing, discard its audio and forget it",
+        output_schema = output_schema::<CancelSessionResult>()
+    )]
+    async fn cancel_session(
+        &self,
+        params: Parameters<CancelSessionParams>,
+    ) -> Result<CallToolResult, McpError> {
+        let session_uuid = params.0.session_id.parse::<Uuid>()
+            .map_err(|_| McpError::from(VttError::invalid_params("Invalid session_id format")))?;
+
+        let session = self.sessions.lock().await
+            .remove(&session_uuid)
+            .ok_or_else(|| McpError::from(VttError::invalid_params("Session not found")))?;
+        let previous_status = session.status_display().to_string();
+
+        if let Some(live) = session.live {
+            let LiveCapture { mut capture, .. } = live.finish().await?;
+            if let Err(e) = capture.stop() {
+                tracing::warn!(session_id = %session_uuid, error = %e, "Failed to stop capture");
+            }
+        }
+        self.cleanup_subscribers(session_uuid).await;
+
+        let result = CancelSessionResult {
+            session_id: session_uuid.to_string(),
+            previous_status,
+        };
+        let text = format!("Cancelled session {} (was {})", result.session_id, result.previous_status);
+        structured_result(&result, text)
+    }
+
+    /// Get last transcription
+    #[tool(description = "Get the most recent transcription", output_schema = output_schema::<LastTranscriptionResult>())]
+    async fn get_last_transcription(
+        &self,
+        params: Parameters<GetLastTranscriptionParams>,
+    ) -> Result<CallToolResult, McpError> {
+        let p = params.0;
+        
+        let result = if let Some(session_id_str) = p.session_id {
+            let session_uuid = session_id_str.parse::<Uuid>()
+                .map_err(|_| McpError::from(VttError::invalid_params("Invalid session_id format")))?;
+
+            let sessions = self.sessions.lock().await;
+            let session = sessions.get(&session_uuid)
+                .ok_or_else(|| McpError::from(VttError::invalid_params("Session not found")))?;
+
+            let transcription = session.transcription.as_ref()
+                .ok_or_else(|| McpError::from(VttError::invalid_params("Session has no transcription".to_string())))?;
+
+            LastTranscriptionResult::new(
+                session_uuid,
+                session.transcription_timestamp.unwrap_or(session.start_time),
+                transcription,
+                session.config.model_path.clone(),
+                session.config.language.clone(),
+            )
         } else {
             let history = self.transcription_history.lock().await;
-            let entry = history.first()
+            let entry = history.latest()
                 .ok_or_else(|| McpError::from(VttError::internal("No transcriptions available")))?;
 
-            Ok(CallToolResult::success(vec![
-                Content::text(format!(
-                    "Session: {}\nText: {}\nConfidence: {:?}\nTime: {}ms",
-                    entry.session_id,
-                    entry.transcription.text,
-                    entry.transcription.confidence,
-                    entry.transcription.end_ms - entry.transcription.start_ms
-                ))
-            ]))
+            LastTranscriptionResult::new(
+                entry.session_id,
+                entry.finished_at,
+                &entry.transcription,
+                entry.model.clone(),
+                entry.language.clone(),
+            )
+        };
+
+        let mut lines = vec![format!(
+            "Session: {}\nText: {}\nConfidence: {:?}\nTime: {}ms",
+            result.session_id,
+            result.text,
+            result.confidence,
+            result.end_ms - result.start_ms
+        )];
+        lines.extend(result.segment_lines());
+
+        structured_result(&result, lines.join("\n"))
+    }
+
+    /// Export a transcription as subtitles or a document
+    #[tool(
+        description = "Export a transcription as SRT, WebVTT, JSON, Markdown or plain text",
+        output_schema = output_schema::<ExportTranscriptResult>()
+    )]
+    async fn export_transcript(
+        &self,
+        params: Parameters<ExportTranscriptParams>,
+    ) -> Result<CallToolResult, McpError> {
+        let p = params.0;
+
+        let format: ExportFormat = p.format.parse()
+            .map_err(|e: vtt_core::export::ExportError| McpError::from(VttError::invalid_params(e.to_string())))?;
+        let defaults = ExportOptions::default();
+        let options = ExportOptions {
+            max_line_chars: p.max_line_chars.unwrap_or(defaults.max_line_chars),
+            max_lines: p.max_lines.unwrap_or(defaults.max_lines),
+            max_caption_ms: p.max_caption_ms.unwrap_or(defaults.max_caption_ms),
+        };
+
+        let transcription = match p.session_id {
+            Some(session_id_str) => {
+                let session_uuid = session_id_str.parse::<Uuid>()
+                    .map_err(|_| McpError::from(VttError::invalid_params("Invalid session_id format")))?;
+                self.find_transcription(session_uuid).await
+                    .ok_or_else(|| McpError::from(VttError::invalid_params("Session has no transcription")))?
+            }
+            None => self.transcription_history.lock().await.latest()
+                .map(|entry| entry.transcription.clone())
+                .ok_or_else(|| McpError::from(VttError::internal("No transcriptions available")))?,
+        };
+
+        let content = export(&transcription.core_segments(), format, &options)
+            .map_err(|e| McpError::from(VttError::internal(e.to_string())))?;
+
+        let result = ExportTranscriptResult {
+            format: format.to_string(),
+            content,
+        };
+        structured_result(&result, result.content.clone())
+    }
+
+    /// List or search stored transcriptions
+    #[tool(
+        description = "List past transcriptions, newest first, with optional full-text search and date range",
+        output_schema = output_schema::<HistoryPage>()
+    )]
+    async fn list_history(
+        &self,
+        params: Parameters<ListHistoryParams>,
+    ) -> Result<CallToolResult, McpError> {
+        let p = params.0;
+
+        let query = HistoryQuery {
+            text: p.query.filter(|q| !q.trim().is_empty()),
+            since: p.since.as_deref().map(parse_timestamp).transpose()?,
+            until: p.until.as_deref().map(parse_timestamp).transpose()?,
+            offset: p.offset.unwrap_or(0),
+            limit: p.limit.unwrap_or(20).clamp(1, 100),
+        };
+        let page = self.transcription_history.lock().await.query(&query);
+
+        let mut lines = vec![format!(
+            "Showing {} of {} entries (offset {})",
+            page.entries.len(),
+            page.total,
+            page.offset
+        )];
+        for entry in &page.entries {
+            let source = match &entry.source {
+                HistorySource::Microphone { device } => format!("mic:{}", device.as_deref().unwrap_or("default")),
+                HistorySource::File { path } => format!("file:{}", path),
+            };
+            lines.push(format!(
+                "{} {} [{}] {}",
+                entry.session_id,
+                entry.finished_at.to_rfc3339_opts(chrono::SecondsFormat::Secs, true),
+                source,
+                entry.transcription.text
+            ));
+        }
+
+        structured_result(&page, lines.join("\n"))
+    }
+
+    /// Delete stored transcriptions
+    #[tool(
+        description = "Delete transcriptions from history by session id, or all of them",
+        output_schema = output_schema::<DeleteHistoryResult>()
+    )]
+    async fn delete_history(
+        &self,
+        params: Parameters<DeleteHistoryParams>,
+    ) -> Result<CallToolResult, McpError> {
+        let p = params.0;
+
+        let mut history = self.transcription_history.lock().await;
+        let deleted = if p.all.unwrap_or(false) {
+            history.clear()
+        } else {
+            if p.session_ids.is_empty() {
+                return Err(McpError::from(VttError::invalid_params("Pass session_ids or all = true")));
+            }
+            let ids = p.session_ids.iter()
+                .map(|id| id.parse::<Uuid>())
+                .collect::<Result<Vec<_>, _>>()
+                .map_err(|_| McpError::from(VttError::invalid_params("Invalid session_id format")))?;
+            history.delete(&ids)
         }
+        .map_err(|e| McpError::from(VttError::internal(format!("Failed to update history: {}", e))))?;
+
+        let result = DeleteHistoryResult { deleted, remaining: history.len() };
+        let text = format!("Deleted {} history entries, {} remaining", result.deleted, result.remaining);
+        structured_result(&result, text)
     }
 
     /// List audio devices
-    #[tool(description = "List available audio capture devices")]
+    #[tool(description = "List available audio capture devices", output_schema = output_schema::<AudioDevicesListResult>())]
     async fn list_audio_devices(
         &self,
         _params: Parameters<ListAudioDevicesParams>,
@@ -626,7 +1625,9 @@ impl VttMcpServer {
         let default_device = audio_config.default_device
             .clone()
             .unwrap_or_else(|| {
-                devices.first()
+                devices.iter()
+                    .find(|d| d.is_default)
+                    .or_else(|| devices.first())
                     .map(|d| d.name.clone())
                     .unwrap_or_else(|| "default".to_string())
             });
@@ -639,17 +1640,20 @@ impl VttMcpServer {
             ))
             .collect();
 
-        Ok(CallToolResult::success(vec![
-            Content::text(format!(
-                "Audio Devices:\nDefault: {}\n\n{}",
-                default_device,
-                device_list.join("\n")
-            ))
-        ]))
+        let text = format!(
+            "Audio Devices:\nDefault: {}\n\n{}",
+            default_device,
+            device_list.join("\n")
+        );
+        let result = AudioDevicesListResult {
+            devices: devices.iter().map(|d| AudioDeviceInfo::new(d, &default_device)).collect(),
+            default_device,
+        };
+        structured_result(&result, text)
     }
 
     /// Configure audio settings
-    #[tool(description = "Configure audio capture settings")]
+    #[tool(description = "Configure audio capture settings", output_schema = output_schema::<AudioConfigurationResult>())]
     async fn configure_audio(
         &self,
         params: Parameters<ConfigureAudioParams>,
@@ -659,12 +1663,9 @@ impl VttMcpServer {
         let mut config = self.audio_config.lock().await;
 
         if let Some(ref device_name) = p.device_name {
-            let devices = list_devices().map_err(|e| McpError::from(VttError::internal(e.to_string())))?;
-            let device_exists = devices.iter().any(|d| d.name == *device_name);
-            if !device_exists {
-                return Err(McpError::from(VttError::device_not_found(device_name)));
-            }
-            config.default_device = Some(device_name.clone());
+            let device = find_device(device_name)
+                .map_err(|_| McpError::from(VttError::device_not_found(device_name)))?;
+            config.default_device = Some(device.name);
         }
 
         if let Some(sensitivity) = p.vad_sensitivity {
@@ -686,28 +1687,46 @@ impl VttMcpServer {
             ))
             .collect();
 
-        Ok(CallToolResult::success(vec![
-            Content::text(format!(
-                "Audio configuration updated:\nDefault device: {}\nVAD threshold: {:.2}\n\nDevices:\n{}",
-                default_device.unwrap_or_else(|| "default".to_string()),
-                vad_config.energy_threshold,
-                device_list.join("\n")
-            ))
-        ]))
+        let text = format!(
+            "Audio configuration updated:\nDefault device: {}\nVAD threshold: {:.2}\n\nDevices:\n{}",
+            default_device.as_deref().unwrap_or("default"),
+            vad_config.energy_threshold,
+            device_list.join("\n")
+        );
+        let selected = default_device.clone()
+            .or_else(|| devices.iter().find(|d| d.is_default).map(|d| d.name.clone()))
+            .unwrap_or_default();
+        let result = AudioConfigurationResult {
+            available_devices: devices.iter().map(|d| AudioDeviceInfo::new(d, &selected)).collect(),
+            default_device,
+            vad_config,
+        };
+        structured_result(&result, text)
     }
 }
 
 // Internal types
 
-#[derive(Debug, Clone)]
+#[derive(Debug)]
 struct SessionState {
     status: SessionStatus,
     start_time: DateTime<Utc>,
-    capture: Option<AudioCapture>,
+    live: Option<LiveSession>,
+    /// Held while a `listen_once` call captures; dropping it stops the call
+    cancel: Option<oneshot::Sender<()>>,
     config: WhisperConfig,
+    /// Input device requested for the session; `None` for the default
+    device: Option<String>,
+    /// Text of the latest update published while the session is listening
+    /// that won't change any more
+    committed_text: String,
+    /// Rest of the latest update, which may still be revised
+    tentative_text: String,
     transcription: Option<TranscriptionResult>,
     transcription_timestamp: Option<DateTime<Utc>>,
     error: Option<String>,
+    /// Captured audio as a mono WAV file, once the session has stopped
+    recording: Option<Arc<Vec<u8>>>,
 }
 
 impl SessionState {
@@ -721,6 +1740,64 @@ impl SessionState {
     }
 }
 
+/// Whisper settings a tool call may override; `None` keeps the server's
+#[derive(Debug, Default)]
+struct WhisperOverrides {
+    model_path: Option<String>,
+    language: Option<String>,
+    threads: Option<usize>,
+    use_gpu: Option<bool>,
+    task: Option<Task>,
+    /// An empty prompt clears the configured one
+    initial_prompt: Option<String>,
+    /// Replaces the configured vocabulary; an empty list clears it
+    vocabulary: Option<Vec<String>>,
+    /// Applied over the `[whisper.decoding]` settings
+    decoding: Option<DecodingOverrides>,
+}
+
+/// Handle on a session's background live-transcription task
+#[derive(Debug)]
+struct LiveSession {
+    stop_tx: oneshot::Sender<()>,
+    task: JoinHandle<LiveCapture>,
+}
+
+impl LiveSession {
+    /// Stop the task and take back the capture and what it recorded so far
+    async fn finish(self) -> Result<LiveCapture, McpError> {
+        let _ = self.stop_tx.send(());
+        self.task.await
+            .map_err(|e| McpError::from(VttError::internal(format!("Live transcription task failed: {}", e))))
+    }
+}
+
+/// What a live-transcription task hands back when it stops
+#[derive(Debug)]
+struct LiveCapture {
+    capture: AudioCapture,
+    /// Converts the capture's mono downmix to Whisper's sample rate
+    resampler: Resampler,
+    /// Mono samples at [`WHISPER_SAMPLE_RATE`]
+    recording: Vec<f32>,
+    /// Model lease held for the whole session
+    model: ModelLease,
+}
+
+impl LiveCapture {
+    /// Stop the device and append whatever is still buffered
+    ///
+    /// Returns the whole mono recording, its format and the model lease.
+    fn drain(self) -> vtt_core::audio::AudioResult<(Vec<f32>, AudioFormat, ModelLease)> {
+        let LiveCapture { mut capture, mut resampler, mut recording, model } = self;
+        capture.stop()?;
+        let format = capture.format();
+        recording.extend(resampler.process(&format.to_mono(&capture.take_buffer())));
+        recording.extend(resampler.flush());
+        Ok((recording, AudioFormat { sample_rate: WHISPER_SAMPLE_RATE, channels: 1, ..format }, model))
+    }
+}
+
 #[derive(Debug, Clone, PartialEq)]
 enum SessionStatus {
     Listening,
@@ -729,14 +1806,6 @@ enum SessionStatus {
     Error,
 }
 
-#[derive(Clone)]
-struct HistoryEntry {
-    session_id: Uuid,
-    timestamp: DateTime<Utc>,
-    config: WhisperConfig,
-    transcription: TranscriptionResult,
-}
-
 #[derive(Debug, Clone)]
 struct AudioRuntimeConfig {
     default_device: Option<String>,
@@ -752,6 +1821,342 @@ impl Default for AudioRuntimeConfig {
     }
 }
 
+/// Refuse a new listening session once `max` are already listening
+fn check_listening_limit(sessions: &HashMap<Uuid, SessionState>, max: usize) -> Result<(), McpError> {
+    let listening = sessions.values().filter(|s| s.status == SessionStatus::Listening).count();
+    if max > 0 && listening >= max {
+        return Err(McpError::from(VttError::Session(format!(
+            "{} sessions are already listening (limit {}); stop or cancel one first",
+            listening, max
+        ))));
+    }
+    Ok(())
+}
+
+/// Encode a session's mono recording as WAV for `audio://session/{id}.wav`
+fn encode_recording(session_id: Uuid, samples: &[f32], format: &AudioFormat) -> Option<Arc<Vec<u8>>> {
+    if samples.is_empty() {
+        return None;
+    }
+    encode_wav(samples, format)
+        .map_err(|e| tracing::warn!(session_id = %session_id, error = %e, "Failed to encode recording"))
+        .ok()
+        .map(Arc::new)
+}
+
+/// Decode an audio or video file given to a tool
+async fn decode_clip(audio_file: &str) -> VttResult<DecodedAudio> {
+    let path = std::path::PathBuf::from(audio_file);
+    if !path.exists() {
+        return Err(VttError::invalid_params(format!("Audio file not found: {}", audio_file)));
+    }
+
+    let audio = tokio::task::spawn_blocking(move || decode_file(path))
+        .await
+        .map_err(|e| VttError::internal(e.to_string()))??;
+
+    if audio.samples.is_empty() {
+        return Err(VttError::NoAudioData("Audio file contains no samples".to_string()));
+    }
+    Ok(audio)
+}
+
+/// Sends `notifications/progress` to a caller that asked for them
+#[derive(Clone)]
+struct ProgressReporter {
+    peer: Peer<RoleServer>,
+    token: ProgressToken,
+}
+
+impl ProgressReporter {
+    /// Reporter for a request, if the caller sent a progress token
+    fn from_context(context: &RequestContext<RoleServer>) -> Option<Self> {
+        let token = context.meta.get_progress_token()?;
+        Some(Self { peer: context.peer.clone(), token })
+    }
+
+    async fn report(&self, progress: usize, total: usize, message: String) {
+        let param = ProgressNotificationParam {
+            progress_token: self.token.clone(),
+            progress: progress as f64,
+            total: Some(total as f64),
+            message: Some(message),
+        };
+        if let Err(e) = self.peer.notify_progress(param).await {
+            tracing::debug!(error = %e, "Failed to send progress notification");
+        }
+    }
+}
+
+/// Transcribe or translate `samples` chunk by chunk with the lease's settings
+///
+/// With `include_original`, a translation also carries the transcript in
+/// the spoken language, from a second pass over the same audio. `progress`
+/// hears about every chunk finished, over both passes.
+async fn transcribe_samples(
+    model: &ModelLease,
+    samples: Vec<f32>,
+    sample_rate: u32,
+    chunks: Vec<AudioChunk>,
+    include_original: bool,
+    progress: Option<ProgressReporter>,
+) -> VttResult<TranscriptionResult> {
+    let context = model.context().clone();
+    let include_original = include_original && context.config().task() == Task::Translate;
+    let total = chunks.len() * if include_original { 2 } else { 1 };
+
+    let (progress_tx, mut progress_rx) = mpsc::unbounded_channel();
+    let task = tokio::task::spawn_blocking(move || {
+        let tx = context.transcribe_chunks(&samples, sample_rate, &chunks, |done, _| {
+            let _ = progress_tx.send(done);
+        })?;
+        let original = if include_original {
+            let mut config = context.config().clone().with_task(Task::Transcribe);
+            // Reuse the language found while translating instead of detecting it again
+            config.language = tx.language.clone().or(config.language);
+            let original = context.with_config(config).transcribe_chunks(&samples, sample_rate, &chunks, |done, _| {
+                let _ = progress_tx.send(chunks.len() + done);
+            })?;
+            Some(OriginalText::from(original))
+        } else {
+            None
+        };
+        Ok(TranscriptionResult { original, ..TranscriptionResult::from(tx) })
+    });
+
+    // Ends when the task finishes and drops the sender
+    while let Some(done) = progress_rx.recv().await {
+        if let Some(ref progress) = progress {
+            progress.report(done, total, format!("Transcribed chunk {} of {}", done, total)).await;
+        }
+    }
+
+    task.await
+        .map_err(|e| VttError::internal(e.to_string()))?
+        .map_err(VttError::Transcription)
+}
+
+/// Text of a live session: its finished utterances and the hypotheses for
+/// the one in progress
+///
+/// Hypotheses are stabilized with [`LocalAgreement`], timed from the start
+/// of the recording. The audio of committed words is left out of the window
+/// [`window_start`](Self::window_start) hands out, so each tick only
+/// re-transcribes speech no two hypotheses have agreed on yet.
+#[derive(Debug, Default)]
+struct LiveTranscript {
+    /// Finished utterances, joined
+    finished: String,
+    /// Hypotheses for the utterance in progress
+    agreement: LocalAgreement,
+    /// Committed and tentative text of the last partial update, to skip
+    /// repeats
+    partial: (String, String),
+}
+
+impl LiveTranscript {
+    /// First sample to re-transcribe of the open utterance starting at
+    /// `utterance_start`, with `end` samples recorded
+    ///
+    /// Committed audio is skipped, and so is audio more than
+    /// [`LIVE_WINDOW_MS`] old: tentative words in it can't be confirmed any
+    /// more and are committed as they are.
+    fn window_start(&mut self, utterance_start: usize, end: usize) -> usize {
+        let start = utterance_start.max(end.saturating_sub(ms_to_samples(LIVE_WINDOW_MS as i64)));
+        self.agreement.commit_until(samples_to_ms(start));
+        start.max(ms_to_samples(self.agreement.committed_end_ms())).min(end)
+    }
+
+    /// Prompt for the window: everything committed before it
+    fn prompt(&self) -> String {
+        join_text(&self.finished, &self.agreement.committed_text())
+    }
+
+    /// Feed a transcription of the window starting at `window_start`
+    ///
+    /// Returns the partial update to publish, or `None` if neither its
+    /// committed nor its tentative text changed.
+    fn hypothesis(&mut self, session_id: Uuid, tx: &Transcription, window_start: usize) -> Option<TranscriptionUpdate> {
+        self.agreement.insert(TimedWord::from_transcription(tx, samples_to_ms(window_start)));
+        if self.agreement.text().is_empty() {
+            return None;
+        }
+        let update = self.update(session_id, false);
+        let partial = (update.committed_text.clone(), update.tentative_text.clone());
+        if partial == self.partial {
+            return None;
+        }
+        self.partial = partial;
+        Some(update)
+    }
+
+    /// Close the utterance in progress with its final text
+    ///
+    /// Returns the final update to publish, or `None` if nothing was said.
+    fn finish_utterance(&mut self, session_id: Uuid, text: &str) -> Option<TranscriptionUpdate> {
+        self.agreement.reset();
+        self.partial = Default::default();
+        if text.is_empty() {
+            return None;
+        }
+        self.finished = join_text(&self.finished, text);
+        Some(self.update(session_id, true))
+    }
+
+    fn update(&self, session_id: Uuid, is_final: bool) -> TranscriptionUpdate {
+        let committed_text = join_text(&self.finished, &self.agreement.committed_text());
+        let tentative_text = self.agreement.tentative_text();
+        TranscriptionUpdate {
+            session_id,
+            text: join_text(&committed_text, &tentative_text),
+            committed_text,
+            tentative_text,
+            is_final,
+            timestamp: Utc::now(),
+            confidence: None,
+        }
+    }
+}
+
+fn samples_to_ms(samples: usize) -> i64 {
+    (samples as u64 * 1000 / WHISPER_SAMPLE_RATE as u64) as i64
+}
+
+fn ms_to_samples(ms: i64) -> usize {
+    (ms.max(0) as u64 * WHISPER_SAMPLE_RATE as u64 / 1000) as usize
+}
+
+/// Transcribe one utterance of 16 kHz mono audio, logging failures
+///
+/// `previous_text`, the transcript before the utterance, is added to the
+/// prompt.
+async fn transcribe_utterance(
+    session_id: Uuid,
+    model: &ModelLease,
+    samples: &[f32],
+    previous_text: Option<&str>,
+) -> Option<Transcription> {
+    let context = match previous_text.filter(|text| !text.is_empty()) {
+        Some(text) => {
+            let config = model.context().config().clone().with_previous_text(Some(text.to_string()));
+            model.context().with_config(config)
+        }
+        None => model.context().clone(),
+    };
+    let samples = samples.to_vec();
+    let outcome = tokio::task::spawn_blocking(move || context.transcribe(&samples, WHISPER_SAMPLE_RATE)).await;
+    match outcome {
+        Ok(Ok(tx)) => Some(tx).filter(|tx| !tx.text.trim().is_empty()),
+        Ok(Err(e)) => {
+            tracing::warn!(session_id = %session_id, error = %e, "Utterance transcription failed");
+            None
+        }
+        Err(e) => {
+            tracing::warn!(session_id = %session_id, error = %e, "Utterance transcription panicked");
+            None
+        }
+    }
+}
+
+/// Capture until the first complete utterance for `listen_once`
+///
+/// Returns the 16 kHz mono recording, the utterance within it, if anyone
+/// spoke, and why listening stopped: "silence", "max_duration",
+/// "no_speech", or "cancelled" once `cancel` fires or its sender is dropped.
+async fn capture_utterance(
+    mut capture: AudioCapture,
+    mut segmenter: VadSegmenter,
+    speech_timeout: Duration,
+    max_duration: Duration,
+    mut cancel: oneshot::Receiver<()>,
+) -> VttResult<(Vec<f32>, Option<SpeechSegment>, &'static str)> {
+    let mut resampler = Resampler::new(capture.format().sample_rate, WHISPER_SAMPLE_RATE, 1);
+    let mut recording = Vec::new();
+    let started = tokio::time::Instant::now();
+    let mut ticker = tokio::time::interval(LISTEN_ONCE_POLL);
+
+    let (utterance, reason) = loop {
+        ticker.tick().await;
+        if !matches!(cancel.try_recv(), Err(oneshot::error::TryRecvError::Empty)) {
+            break (None, "cancelled");
+        }
+        let chunk = resampler.process(&capture.format().to_mono(&capture.take_buffer()));
+        recording.extend_from_slice(&chunk);
+
+        // Segment positions index into `recording`
+        let (returned, pushed) = with_segmenter(segmenter, move |segmenter| segmenter.push(&chunk)).await?;
+        segmenter = returned;
+        if let Some(segment) = pushed?.into_iter().next() {
+            break (Some(segment), "silence");
+        }
+        let elapsed = started.elapsed();
+        if elapsed >= max_duration {
+            let (_, flushed) = with_segmenter(segmenter, VadSegmenter::flush).await?;
+            break (flushed?, "max_duration");
+        }
+        if elapsed >= speech_timeout && segmenter.open_segment_start().is_none() {
+            break (None, "no_speech");
+        }
+    };
+
+    capture.stop()?;
+    Ok((recording, utterance, reason))
+}
+
+/// Run `f` on the segmenter on a blocking thread, handing it back after
+///
+/// Backends such as Silero run a model on every frame, which would stall
+/// the async worker threads; the clip path moves the same work off them.
+async fn with_segmenter<T: Send + 'static>(
+    mut segmenter: VadSegmenter,
+    f: impl FnOnce(&mut VadSegmenter) -> T + Send + 'static,
+) -> VttResult<(VadSegmenter, T)> {
+    tokio::task::spawn_blocking(move || {
+        let out = f(&mut segmenter);
+        (segmenter, out)
+    })
+    .await
+    .map_err(|e| VttError::internal(format!("Voice activity detection failed: {}", e)))
+}
+
+/// Append an utterance to the text before it, space-separated
+fn join_text(before: &str, text: &str) -> String {
+    if before.is_empty() || text.is_empty() {
+        format!("{}{}", before, text)
+    } else {
+        format!("{} {}", before, text)
+    }
+}
+
+/// Output schema for a tool returning `T` as structured content
+fn output_schema<T: JsonSchema + 'static>() -> Arc<JsonObject> {
+    rmcp::handler::server::tool::schema_for_output::<T>()
+        .unwrap_or_else(|e| panic!("{} is not a valid output schema: {}", std::any::type_name::<T>(), e))
+}
+
+/// Tool result with `value` as structured content and `text` as the
+/// human-readable fallback for clients that ignore `structuredContent`
+fn structured_result<T: Serialize>(value: &T, text: String) -> Result<CallToolResult, McpError> {
+    let value = serde_json::to_value(value)
+        .map_err(|e| McpError::from(VttError::internal(format!("Failed to serialize result: {}", e))))?;
+    let mut result = CallToolResult::success(vec![Content::text(text)]);
+    result.structured_content = Some(value);
+    Ok(result)
+}
+
+/// Parse an RFC 3339 time or a `YYYY-MM-DD` date (midnight UTC)
+fn parse_timestamp(value: &str) -> Result<DateTime<Utc>, McpError> {
+    if let Ok(time) = DateTime::parse_from_rfc3339(value) {
+        return Ok(time.with_timezone(&Utc));
+    }
+    chrono::NaiveDate::parse_from_str(value, "%Y-%m-%d")
+        .map(|date| date.and_time(chrono::NaiveTime::MIN).and_utc())
+        .map_err(|_| McpError::from(VttError::invalid_params(format!(
+            "Invalid time '{}': expected RFC 3339 or YYYY-MM-DD",
+            value
+        ))))
+}
+
 // Tool parameter types
 
 #[derive(Debug, Clone, Deserialize, JsonSchema)]
@@ -764,10 +2169,29 @@ pub struct TranscribeClipParams {
     pub model_path: Option<String>,
     #[serde(default)]
     pub language: Option<String>,
+    /// "transcribe" (default) for text in the spoken language, or
+    /// "translate" for English text
+    #[serde(default)]
+    pub task: Option<Task>,
+    /// With task "translate", also return the transcript in the spoken
+    /// language (a second pass over the audio)
+    #[serde(default)]
+    pub include_original: Option<bool>,
     #[serde(default)]
     pub use_gpu: Option<bool>,
     #[serde(default)]
     pub threads: Option<usize>,
+    /// Text to prime Whisper with, e.g. the topic or expected spelling;
+    /// defaults to the configured prompt, an empty string clears it
+    #[serde(default)]
+    pub initial_prompt: Option<String>,
+    /// Names and jargon Whisper should spell this way; replaces the
+    /// configured vocabulary
+    #[serde(default)]
+    pub vocabulary: Option<Vec<String>>,
+    /// Decoding preset and settings, applied over the server's
+    #[serde(default)]
+    pub decoding: Option<DecodingOverrides>,
 }
 
 #[derive(Debug, Clone, Deserialize, JsonSchema)]
@@ -776,40 +2200,203 @@ pub struct StartListeningParams {
     pub model_path: Option<String>,
     #[serde(default)]
     pub language: Option<String>,
+    /// "transcribe" (default) for text in the spoken language, or
+    /// "translate" for English text
+    #[serde(default)]
+    pub task: Option<Task>,
     #[serde(default)]
     pub use_gpu: Option<bool>,
     #[serde(default)]
     pub threads: Option<usize>,
     #[serde(default)]
     pub device_name: Option<String>,
-}
-
-#[derive(Debug, Clone, Deserialize, JsonSchema)]
-pub struct StopListeningParams {
-    pub session_id: String,
+    /// Text to prime Whisper with, e.g. the topic or expected spelling;
+    /// defaults to the configured prompt, an empty string clears it
     #[serde(default)]
-    pub transcribe: Option<bool>,
+    pub initial_prompt: Option<String>,
+    /// Names and jargon Whisper should spell this way; replaces the
+    /// configured vocabulary
+    #[serde(default)]
+    pub vocabulary: Option<Vec<String>>,
+    /// Decoding preset and settings, applied over the server's
+    #[serde(default)]
+    pub decoding: Option<DecodingOverrides>,
+    /// Prompt each utterance with the text transcribed before it
+    /// (default true)
+    #[serde(default)]
+    pub carry_prompt: Option<bool>,
 }
 
+/// Parameters for `detect_language`
 #[derive(Debug, Clone, Deserialize, JsonSchema)]
-pub struct GetLastTranscriptionParams {
+pub struct DetectLanguageParams {
+    /// Audio or video file; only the first 30 seconds are listened to
+    pub audio_file: String,
+    /// Candidate languages to return, most likely first (default 5)
     #[serde(default)]
-    pub session_id: Option<String>,
+    pub top_n: Option<usize>,
+    /// Whisper model file; must be multilingual (not a `.en` model)
+    #[serde(default)]
+    pub model_path: Option<String>,
+    /// Run Whisper on the GPU
+    #[serde(default)]
+    pub use_gpu: Option<bool>,
+    /// Whisper threads
+    #[serde(default)]
+    pub threads: Option<usize>,
 }
 
+/// Parameters for `listen_once`
 #[derive(Debug, Clone, Deserialize, JsonSchema)]
-pub struct ListAudioDevicesParams {}
-
-#[derive(Debug, Clone, Deserialize, JsonSchema)]
-pub struct ConfigureAudioParams {
+pub struct ListenOnceParams {
+    /// Whisper model file; defaults to the configured model
+    #[serde(default)]
+    pub model_path: Option<String>,
+    /// Language code, or "auto" to detect it
+    #[serde(default)]
+    pub language: Option<String>,
+    /// "transcribe" (default) for text in the spoken language, or
+    /// "translate" for English text
+    #[serde(default)]
+    pub task: Option<Task>,
+    /// With task "translate", also return the transcript in the spoken
+    /// language (a second pass over the audio)
+    #[serde(default)]
+    pub include_original: Option<bool>,
+    /// Run Whisper on the GPU
+    #[serde(default)]
+    pub use_gpu: Option<bool>,
+    /// Whisper threads
+    #[serde(default)]
+    pub threads: Option<usize>,
+    /// Capture device; defaults to the one picked with configure_audio
     #[serde(default)]
     pub device_name: Option<String>,
+    /// Silence that ends the utterance, in ms (default 1500)
     #[serde(default)]
-    pub vad_sensitivity: Option<f32>,
-}
-
-// Tool result types
-
+    pub silence_ms: Option<u32>,
+    /// Give up if nobody starts speaking within this many seconds (default 10)
+    #[serde(default)]
+    pub speech_timeout_secs: Option<u64>,
+    /// Stop listening after this many seconds even mid-sentence (default 60,
+    /// or the server's maximum recording duration if lower)
+    #[serde(default)]
+    pub max_duration_secs: Option<u64>,
+    /// Text to prime Whisper with, e.g. the topic or expected spelling;
+    /// defaults to the configured prompt, an empty string clears it
+    #[serde(default)]
+    pub initial_prompt: Option<String>,
+    /// Names and jargon Whisper should spell this way; replaces the
+    /// configured vocabulary
+    #[serde(default)]
+    pub vocabulary: Option<Vec<String>>,
+    /// Decoding preset and settings, applied over the server's
+    #[serde(default)]
+    pub decoding: Option<DecodingOverrides>,
+}
+
+#[derive(Debug, Clone, Deserialize, JsonSchema)]
+pub struct StopListeningParams {
+    pub session_id: String,
+    #[serde(default)]
+    pub transcribe: Option<bool>,
+    /// With a session started with task "translate", also return the
+    /// transcript in the spoken language
+    #[serde(default)]
+    pub include_original: Option<bool>,
+}
+
+/// Parameters for `export_transcript`
+#[derive(Debug, Clone, Deserialize, JsonSchema)]
+pub struct ExportTranscriptParams {
+    /// Session to export; defaults to the most recent transcription
+    #[serde(default)]
+    pub session_id: Option<String>,
+    /// Output format: txt, srt, vtt, json or md
+    pub format: String,
+    /// Maximum characters per caption line (SRT/WebVTT, default 42)
+    #[serde(default)]
+    pub max_line_chars: Option<usize>,
+    /// Maximum lines per caption (SRT/WebVTT, default 2)
+    #[serde(default)]
+    pub max_lines: Option<usize>,
+    /// Maximum caption duration in milliseconds (SRT/WebVTT, default 7000)
+    #[serde(default)]
+    pub max_caption_ms: Option<u64>,
+}
+
+/// Parameters for `list_history`
+#[derive(Debug, Clone, Deserialize, JsonSchema)]
+pub struct ListHistoryParams {
+    /// Words that must all appear in the transcript (case-insensitive)
+    #[serde(default)]
+    pub query: Option<String>,
+    /// Only entries finished at or after this time (RFC 3339 or YYYY-MM-DD)
+    #[serde(default)]
+    pub since: Option<String>,
+    /// Only entries finished before this time (RFC 3339 or YYYY-MM-DD)
+    #[serde(default)]
+    pub until: Option<String>,
+    /// Matching entries to skip (default 0)
+    #[serde(default)]
+    pub offset: Option<usize>,
+    /// Entries per page (default 20, at most 100)
+    #[serde(default)]
+    pub limit: Option<usize>,
+}
+
+/// Parameters for `delete_history`
+#[derive(Debug, Clone, Deserialize, JsonSchema)]
+pub struct DeleteHistoryParams {
+    /// Sessions to delete
+    #[serde(default)]
+    pub session_ids: Vec<String>,
+    /// Delete the whole history
+    #[serde(default)]
+    pub all: Option<bool>,
+}
+
+/// Parameters for `list_sessions`
+#[derive(Debug, Clone, Deserialize, JsonSchema)]
+pub struct ListSessionsParams {
+    /// Only sessions with this status: listening, stopped, transcribed or error
+    #[serde(default)]
+    pub status: Option<String>,
+}
+
+/// Parameters for `get_session_status`
+#[derive(Debug, Clone, Deserialize, JsonSchema)]
+pub struct GetSessionStatusParams {
+    /// Session UUID
+    pub session_id: String,
+}
+
+/// Parameters for `cancel_session`
+#[derive(Debug, Clone, Deserialize, JsonSchema)]
+pub struct CancelSessionParams {
+    /// Session UUID
+    pub session_id: String,
+}
+
+#[derive(Debug, Clone, Deserialize, JsonSchema)]
+pub struct GetLastTranscriptionParams {
+    #[serde(default)]
+    pub session_id: Option<String>,
+}
+
+#[derive(Debug, Clone, Deserialize, JsonSchema)]
+pub struct ListAudioDevicesParams {}
+
+#[derive(Debug, Clone, Deserialize, JsonSchema)]
+pub struct ConfigureAudioParams {
+    #[serde(default)]
+    pub device_name: Option<String>,
+    #[serde(default)]
+    pub vad_sensitivity: Option<f32>,
+}
+
+// Tool result types
+
 #[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct VadConfigInfo {
     pub energy_threshold: f32,
@@ -835,25 +2422,173 @@ pub struct TranscriptionResult {
     pub confidence: Option<f32>,
     pub start_ms: u64,
     pub end_ms: u64,
+    /// Timed segments, for jumping to or highlighting part of the recording
+    #[serde(default)]
+    pub segments: Vec<SegmentInfo>,
+    /// Language Whisper transcribed in; the detected one under auto-detect
+    #[serde(default)]
+    pub language: Option<String>,
+    /// Transcript in the spoken language, when `text` is a translation and
+    /// `include_original` was set
+    #[serde(default)]
+    pub original: Option<OriginalText>,
 }
 
-impl From<Transcription> for TranscriptionResult {
+/// Text in the spoken language, next to an English translation
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct OriginalText {
+    /// Full text in the spoken language
+    pub text: String,
+    /// Timed segments of the original text
+    pub segments: Vec<SegmentInfo>,
+}
+
+impl From<Transcription> for OriginalText {
     fn from(tx: Transcription) -> Self {
         Self {
+            segments: tx.segments.into_iter().map(SegmentInfo::from).collect(),
             text: tx.text,
-            confidence: None,
+        }
+    }
+}
+
+impl TranscriptionResult {
+    /// One "[start - end] text" line per non-empty segment
+    fn segment_lines(&self) -> Vec<String> {
+        segment_lines(&self.segments)
+    }
+
+    /// Segments for export; text-only results become a single segment
+    fn core_segments(&self) -> Vec<Segment> {
+        if self.segments.is_empty() {
+            return vec![Segment {
+                start_ms: self.start_ms as i64,
+                end_ms: self.end_ms as i64,
+                text: self.text.clone(),
+                no_speech_probability: 0.0,
+                tokens: Vec::new(),
+            }];
+        }
+        self.segments.iter().cloned().map(Segment::from).collect()
+    }
+}
+
+impl From<SegmentInfo> for Segment {
+    fn from(segment: SegmentInfo) -> Self {
+        Self {
+            start_ms: segment.start_ms as i64,
+            end_ms: segment.end_ms as i64,
+            text: segment.text,
+            no_speech_probability: segment.no_speech_probability,
+            tokens: segment.tokens.into_iter().map(|t| Token {
+                id: t.id,
+                text: t.text,
+                start_ms: t.start_ms as i64,
+                end_ms: t.end_ms as i64,
+                probability: t.probability,
+            }).collect(),
+        }
+    }
+}
+
+impl From<Transcription> for TranscriptionResult {
+    fn from(tx: Transcription) -> Self {
+        Self {
+            confidence: tx.confidence(),
             start_ms: tx.start_timestamp.max(0) as u64,
             end_ms: tx.end_timestamp.max(0) as u64,
+            segments: tx.segments.into_iter().map(SegmentInfo::from).collect(),
+            text: tx.text,
+            language: tx.language,
+            original: None,
+        }
+    }
+}
+
+fn segment_lines(segments: &[SegmentInfo]) -> Vec<String> {
+    segments
+        .iter()
+        .filter(|s| !s.text.is_empty())
+        .map(|s| format!(
+            "[{:.2}s - {:.2}s] {}",
+            s.start_ms as f64 / 1000.0,
+            s.end_ms as f64 / 1000.0,
+            s.text
+        ))
+        .collect()
+}
+
+/// A timed span of a transcription
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct SegmentInfo {
+    /// Start of the segment in the recording, in milliseconds
+    pub start_ms: u64,
+    /// End of the segment in the recording, in milliseconds
+    pub end_ms: u64,
+    /// Segment text
+    pub text: String,
+    /// Probability that the segment holds no speech
+    pub no_speech_probability: f32,
+    /// Text tokens with per-token timing
+    pub tokens: Vec<TokenInfo>,
+}
+
+impl From<Segment> for SegmentInfo {
+    fn from(segment: Segment) -> Self {
+        Self {
+            start_ms: segment.start_ms.max(0) as u64,
+            end_ms: segment.end_ms.max(0) as u64,
+            text: segment.text,
+            no_speech_probability: segment.no_speech_probability,
+            tokens: segment.tokens.into_iter().map(TokenInfo::from).collect(),
+        }
+    }
+}
+
+/// A decoded token with its timing and probability
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct TokenInfo {
+    /// Vocabulary id
+    pub id: i32,
+    /// Token text, including any leading space
+    pub text: String,
+    /// Start of the token in the recording, in milliseconds
+    pub start_ms: u64,
+    /// End of the token in the recording, in milliseconds
+    pub end_ms: u64,
+    /// Probability the model assigned to the token
+    pub probability: f32,
+}
+
+impl From<Token> for TokenInfo {
+    fn from(token: Token) -> Self {
+        Self {
+            id: token.id,
+            text: token.text,
+            start_ms: token.start_ms.max(0) as u64,
+            end_ms: token.end_ms.max(0) as u64,
+            probability: token.probability,
         }
     }
 }
 
 #[derive(Debug, Serialize, Deserialize, JsonSchema)]
 pub struct TranscribeClipResult {
+    /// History id of the transcription, usable with `export_transcript`
+    pub session_id: String,
     pub text: String,
     pub confidence: Option<f32>,
     pub start_ms: u64,
     pub end_ms: u64,
+    /// Language Whisper transcribed in: the detected one under
+    /// auto-detect, else the requested one
+    pub language: Option<String>,
+    /// Timed segments of the clip
+    pub segments: Vec<SegmentInfo>,
+    /// "transcribe", or "translate" if `text` is an English translation
+    pub task: String,
+    /// Transcript in the spoken language, with `include_original`
+    pub original: Option<OriginalText>,
 }
 
 #[derive(Debug, Serialize, Deserialize, JsonSchema)]
@@ -864,6 +2599,8 @@ pub struct StartListeningResult {
     pub model_path: String,
     pub language: Option<String>,
     pub use_gpu: bool,
+    /// Resource carrying the live transcript, for `resources/subscribe`
+    pub resource_uri: String,
 }
 
 #[derive(Debug, Serialize, Deserialize, JsonSchema)]
@@ -876,6 +2613,21 @@ pub struct StopListeningResult {
     pub error: Option<String>,
 }
 
+/// Result of `listen_once`
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct ListenOnceResult {
+    /// History id of the transcript; absent if nobody spoke
+    pub session_id: Option<String>,
+    /// What the user said; empty if nobody spoke
+    pub text: String,
+    /// Why listening stopped: "silence", "max_duration" or "no_speech"
+    pub stop_reason: String,
+    /// How long the microphone was open, in ms
+    pub listened_ms: u64,
+    /// The utterance; `start_ms` and `end_ms` count from when listening began
+    pub transcription: Option<TranscriptionResult>,
+}
+
 #[derive(Debug, Serialize, Deserialize, JsonSchema)]
 pub struct LastTranscriptionResult {
     pub session_id: String,
@@ -886,14 +2638,55 @@ pub struct LastTranscriptionResult {
     pub end_ms: u64,
     pub model_path: String,
     pub language: Option<String>,
+    /// Timed segments of the transcription
+    #[serde(default)]
+    pub segments: Vec<SegmentInfo>,
+}
+
+impl LastTranscriptionResult {
+    fn new(
+        session_id: Uuid,
+        timestamp: DateTime<Utc>,
+        transcription: &TranscriptionResult,
+        model_path: String,
+        language: Option<String>,
+    ) -> Self {
+        Self {
+            session_id: session_id.to_string(),
+            timestamp,
+            text: transcription.text.clone(),
+            confidence: transcription.confidence,
+            start_ms: transcription.start_ms,
+            end_ms: transcription.end_ms,
+            model_path,
+            language,
+            segments: transcription.segments.clone(),
+        }
+    }
+
+    fn segment_lines(&self) -> Vec<String> {
+        segment_lines(&self.segments)
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct AudioDeviceInfo {
     pub name: String,
+    /// Stable identifier (PipeWire node name on Linux)
+    pub id: String,
     pub is_default: bool,
 }
 
+impl AudioDeviceInfo {
+    fn new(device: &vtt_core::audio::AudioDevice, default_device: &str) -> Self {
+        Self {
+            name: device.name.clone(),
+            id: device.id.clone(),
+            is_default: device.name == default_device || device.id == default_device,
+        }
+    }
+}
+
 #[derive(Debug, Serialize, Deserialize, JsonSchema)]
 pub struct AudioDevicesListResult {
     pub devices: Vec<AudioDeviceInfo>,
@@ -907,9 +2700,140 @@ pub struct AudioConfigurationResult {
     pub available_devices: Vec<AudioDeviceInfo>,
 }
 
+/// Contents of `session://{session_id}`, also returned by `get_session_status`
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct SessionInfo {
+    /// Session UUID
+    pub session_id: String,
+    /// "listening", "stopped", "transcribed" or "error"
+    pub status: String,
+    /// When capture started
+    pub start_time: DateTime<Utc>,
+    /// When the session was stopped, if it has been
+    pub stopped_at: Option<DateTime<Utc>>,
+    /// Time from start to stop (or to now while listening), in milliseconds
+    pub elapsed_ms: u64,
+    /// Model file used
+    pub model_path: String,
+    /// Requested language; `None` means auto-detect
+    pub language: Option<String>,
+    /// Whether GPU inference was requested
+    pub use_gpu: bool,
+    /// Inference threads
+    pub threads: i32,
+    /// Input device requested; `None` for the default
+    pub device: Option<String>,
+    /// Whether `audio://session/{session_id}.wav` is available
+    pub has_recording: bool,
+    /// Transcript so far that won't change any more; the final text once
+    /// the session is transcribed
+    pub committed_text: String,
+    /// Words after `committed_text` that later updates may still revise
+    pub tentative_text: String,
+    /// Why the session failed, if it did
+    pub error: Option<String>,
+}
+
+impl SessionInfo {
+    fn new(session_id: Uuid, session: &SessionState) -> Self {
+        let end = session.transcription_timestamp.unwrap_or_else(Utc::now);
+        Self {
+            session_id: session_id.to_string(),
+            status: session.status_display().to_string(),
+            start_time: session.start_time,
+            stopped_at: session.transcription_timestamp,
+            elapsed_ms: (end - session.start_time).num_milliseconds().max(0) as u64,
+            model_path: session.config.model_path.clone(),
+            language: session.config.language.clone(),
+            use_gpu: session.config.use_gpu,
+            threads: session.config.n_threads,
+            device: session.device.clone(),
+            has_recording: session.recording.is_some(),
+            committed_text: session.transcription.as_ref()
+                .map_or_else(|| session.committed_text.clone(), |t| t.text.clone()),
+            tentative_text: if session.transcription.is_some() { String::new() } else { session.tentative_text.clone() },
+            error: session.error.clone(),
+        }
+    }
+}
+
+/// Result of `list_sessions`
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct ListSessionsResult {
+    /// Matching sessions, oldest first
+    pub sessions: Vec<SessionInfo>,
+}
+
+/// Result of `cancel_session`
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct CancelSessionResult {
+    /// Session UUID
+    pub session_id: String,
+    /// Status the session had when it was cancelled
+    pub previous_status: String,
+}
+
+/// Result of `list_languages`
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct LanguagesListResult {
+    /// Accepted language codes, starting with "auto"
+    pub languages: Vec<LanguageInfo>,
+    /// Number of entries in `languages`
+    pub total: usize,
+}
+
+/// A language accepted by the `language` parameters
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct LanguageInfo {
+    /// ISO 639-1 code, or "auto"
+    pub code: String,
+    /// English name
+    pub name: String,
+}
+
+/// Result of `detect_language`
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct DetectLanguageResult {
+    /// Code of the most likely language
+    pub language: String,
+    /// Candidate languages, most likely first
+    pub languages: Vec<DetectedLanguage>,
+}
+
+/// A candidate language from `detect_language`
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct DetectedLanguage {
+    /// ISO 639-1 code (or Whisper's own code for a few languages)
+    pub code: String,
+    /// English name
+    pub name: String,
+    /// Probability between 0 and 1
+    pub probability: f32,
+}
+
+/// Result of `export_transcript`
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct ExportTranscriptResult {
+    /// Format of `content`: txt, srt, vtt, json or md
+    pub format: String,
+    /// The exported document
+    pub content: String,
+}
+
+/// Result of `delete_history`
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct DeleteHistoryResult {
+    /// Entries removed
+    pub deleted: usize,
+    /// Entries left in the history
+    pub remaining: usize,
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use vtt_core::config::schema::SessionConfig;
+    use vtt_core::whisper::DecodingPreset;
 
     #[tokio::test]
     async fn test_server_creation() {
@@ -917,13 +2841,80 @@ mod tests {
         assert!(server.sessions.lock().await.is_empty());
     }
 
+    /// Client that records the URIs of `resources/updated` notifications
+    #[derive(Clone)]
+    struct RecordingClient {
+        updates: tokio::sync::mpsc::UnboundedSender<String>,
+    }
+
+    impl rmcp::ClientHandler for RecordingClient {
+        async fn on_resource_updated(
+            &self,
+            params: ResourceUpdatedNotificationParam,
+            _context: rmcp::service::NotificationContext<rmcp::RoleClient>,
+        ) {
+            let _ = self.updates.send(params.uri);
+        }
+    }
+
+    /// Connect an in-process client to `server` over a duplex pipe
+    async fn connect(
+        server: VttMcpServer,
+    ) -> (
+        rmcp::service::RunningService<RoleServer, VttMcpServer>,
+        rmcp::service::RunningService<rmcp::RoleClient, RecordingClient>,
+        tokio::sync::mpsc::UnboundedReceiver<String>,
+    ) {
+        use rmcp::ServiceExt;
+
+        let (server_io, client_io) = tokio::io::duplex(64 * 1024);
+        let (updates, updates_rx) = tokio::sync::mpsc::unbounded_channel();
+        let (server_service, client_service) = tokio::join!(
+            server.serve(server_io),
+            RecordingClient { updates }.serve(client_io),
+        );
+        (server_service.unwrap(), client_service.unwrap(), updates_rx)
+    }
+
+    fn listening_session() -> SessionState {
+        SessionState {
+            status: SessionStatus::Listening,
+            start_time: Utc::now(),
+            live: None,
+            cancel: None,
+            config: WhisperConfig::default(),
+            device: None,
+            committed_text: String::new(),
+            tentative_text: String::new(),
+            transcription: None,
+            transcription_timestamp: None,
+            error: None,
+            recording: None,
+        }
+    }
+
+    fn update(session_id: Uuid, text: &str) -> TranscriptionUpdate {
+        TranscriptionUpdate {
+            session_id,
+            text: text.to_string(),
+            committed_text: String::new(),
+            tentative_text: text.to_string(),
+            is_final: false,
+            timestamp: Utc::now(),
+            confidence: None,
+        }
+    }
+
     #[tokio::test]
     async fn test_subscriber_management() {
         let server = VttMcpServer::new();
+        let (server_service, _client_service, _updates) = connect(server.clone()).await;
         let session_id = Uuid::new_v4();
         let client_id = "test-client".to_string();
+        let peer = server_service.peer().clone();
 
-        server.add_subscriber(session_id, client_id.clone()).await.unwrap();
+        server.add_subscriber(session_id, client_id.clone(), peer.clone()).await.unwrap();
+        server.add_subscriber(session_id, client_id.clone(), peer).await.unwrap();
         let subscribers = server.get_subscribers(session_id).await;
         assert_eq!(subscribers.len(), 1);
         assert_eq!(subscribers[0].client_id, client_id);
@@ -933,6 +2924,100 @@ mod tests {
         assert_eq!(subscribers.len(), 0);
     }
 
+    #[tokio::test]
+    async fn test_subscribers_receive_resource_updates() {
+        let server = VttMcpServer::new();
+        let session_id = Uuid::new_v4();
+        server.sessions.lock().await.insert(session_id, listening_session());
+
+        let (server_service, client_service, mut updates) = connect(server.clone()).await;
+        let uri = format!("transcript://live/{}", session_id);
+        client_service
+            .subscribe(SubscribeRequestParam { uri: uri.clone() })
+            .await
+            .unwrap();
+        assert_eq!(server.get_subscribers(session_id).await.len(), 1);
+
+        server.broadcast_transcription(update(session_id, "hello")).await;
+        let received = tokio::time::timeout(Duration::from_secs(5), updates.recv())
+            .await
+            .unwrap();
+        assert_eq!(received, Some(uri));
+
+        // Updates for other sessions don't reach this client
+        server.broadcast_transcription(update(Uuid::new_v4(), "elsewhere")).await;
+        assert!(updates.try_recv().is_err());
+
+        // Once the client goes away the next update prunes the subscription
+        client_service.cancel().await.unwrap();
+        server_service.waiting().await.unwrap();
+        server.broadcast_transcription(update(session_id, "anyone there?")).await;
+        assert!(server.get_subscribers(session_id).await.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_resources() {
+        let server = VttMcpServer::new();
+        let session_id = Uuid::new_v4();
+        let mut session = listening_session();
+        session.status = SessionStatus::Transcribed;
+        session.transcription_timestamp = Some(Utc::now());
+        session.recording = Some(Arc::new(encode_wav(&[0.0; 160], &AudioFormat::STT_DEFAULT).unwrap()));
+        server.sessions.lock().await.insert(session_id, session);
+        let transcription = TranscriptionResult {
+            text: "saved".to_string(),
+            confidence: None,
+            start_ms: 0,
+            end_ms: 10,
+            segments: Vec::new(),
+            language: None,
+            original: None,
+        };
+        let source = HistorySource::Microphone { device: None };
+        server.store_transcription_in_history(session_id, Utc::now(), WhisperConfig::default(), source, transcription).await;
+
+        let (_server_service, client, _updates) = connect(server.clone()).await;
+
+        let templates = client.list_resource_templates(None).await.unwrap().resource_templates;
+        assert_eq!(templates.len(), 4);
+
+        let uris: Vec<String> = client.list_resources(None).await.unwrap()
+            .resources.into_iter().map(|r| r.raw.uri).collect();
+        assert!(uris.contains(&format!("session://{}", session_id)));
+        assert!(uris.contains(&format!("audio://session/{}.wav", session_id)));
+        assert!(uris.contains(&format!("transcript://history/{}", session_id)));
+        assert!(!uris.contains(&format!("transcript://live/{}", session_id)));
+
+        let read = |uri: String| {
+            let client = &client;
+            async move { client.read_resource(ReadResourceRequestParam { uri }).await }
+        };
+
+        let contents = read(format!("session://{}", session_id)).await.unwrap().contents;
+        let ResourceContents::TextResourceContents { text, .. } = &contents[0] else { panic!("expected text") };
+        let info: SessionInfo = serde_json::from_str(text).unwrap();
+        assert_eq!(info.status, "transcribed");
+        assert!(info.has_recording);
+
+        let contents = read(format!("transcript://history/{}", session_id)).await.unwrap().contents;
+        let ResourceContents::TextResourceContents { text, .. } = &contents[0] else { panic!("expected text") };
+        let json: serde_json::Value = serde_json::from_str(text).unwrap();
+        assert_eq!(json["text"], "saved");
+
+        let contents = read(format!("audio://session/{}.wav", session_id)).await.unwrap().contents;
+        let ResourceContents::BlobResourceContents { blob, mime_type, .. } = &contents[0] else { panic!("expected blob") };
+        assert_eq!(mime_type.as_deref(), Some("audio/wav"));
+        let wav = base64::engine::general_purpose::STANDARD.decode(blob).unwrap();
+        assert_eq!(&wav[..4], b"RIFF");
+
+        assert!(read(format!("audio://session/{}.wav", Uuid::new_v4())).await.is_err());
+        assert!(read("transcript://nope".to_string()).await.is_err());
+        assert!(client
+            .subscribe(SubscribeRequestParam { uri: format!("session://{}", session_id) })
+            .await
+            .is_err());
+    }
+
     #[tokio::test]
     async fn test_broadcast_transcription() {
         let server = VttMcpServer::new();
@@ -941,6 +3026,8 @@ mod tests {
         let update = TranscriptionUpdate {
             session_id: Uuid::new_v4(),
             text: "Hello world".to_string(),
+            committed_text: "Hello".to_string(),
+            tentative_text: "world".to_string(),
             is_final: false,
             timestamp: Utc::now(),
             confidence: Some(0.95),
@@ -953,17 +3040,556 @@ mod tests {
         assert_eq!(received.session_id, update.session_id);
     }
 
+    #[test]
+    fn test_join_text() {
+        assert_eq!(join_text("", "Hello."), "Hello.");
+        assert_eq!(join_text("Hello.", "How are you?"), "Hello. How are you?");
+        assert_eq!(join_text("Hello.", ""), "Hello.");
+    }
+
+    #[tokio::test]
+    async fn test_broadcast_updates_live_text() {
+        let server = VttMcpServer::new();
+        let session_id = Uuid::new_v4();
+        server.sessions.lock().await.insert(session_id, listening_session());
+
+        server.broadcast_transcription(TranscriptionUpdate {
+            committed_text: "still".to_string(),
+            tentative_text: "talking".to_string(),
+            ..update(session_id, "still talking")
+        }).await;
+
+        let sessions = server.sessions.lock().await;
+        let info = SessionInfo::new(session_id, &sessions[&session_id]);
+        assert_eq!((info.committed_text.as_str(), info.tentative_text.as_str()), ("still", "talking"));
+    }
+
+    /// Transcription of a window with words 500 ms apart
+    fn hypothesis(text: &str) -> Transcription {
+        let words = text.split_whitespace().count() as i64;
+        Transcription::from_segments(vec![Segment {
+            start_ms: 0,
+            end_ms: 500 * words,
+            text: text.to_string(),
+            no_speech_probability: 0.0,
+            tokens: Vec::new(),
+        }])
+    }
+
+    #[test]
+    fn test_live_transcript_trims_committed_audio() {
+        let session_id = Uuid::new_v4();
+        let second = WHISPER_SAMPLE_RATE as usize;
+        let mut live = LiveTranscript::default();
+
+        // An utterance starting 1 s in, re-transcribed from its start until
+        // two hypotheses agree
+        assert_eq!(live.window_start(second, 3 * second), second);
+        let update = live.hypothesis(session_id, &hypothesis("hello there gen"), second).unwrap();
+        assert_eq!((update.committed_text.as_str(), update.tentative_text.as_str()), ("", "hello there gen"));
+        assert_eq!(live.window_start(second, 4 * second), second);
+        let update = live.hypothesis(session_id, &hypothesis("hello there general kenobi"), second).unwrap();
+        assert_eq!((update.committed_text.as_str(), update.tentative_text.as_str()), ("hello there", "general kenobi"));
+        assert!(!update.is_final);
+
+        // The committed words' audio is dropped from the window, and the
+        // next hypothesis is timed from where the window starts
+        let start = live.window_start(second, 5 * second);
+        assert_eq!(start, 2 * second);
+        assert_eq!(live.prompt(), "hello there");
+        let update = live.hypothesis(session_id, &hypothesis("general kenobi"), start).unwrap();
+        assert_eq!(update.committed_text, "hello there general kenobi");
+        assert_eq!(update.tentative_text, "");
+        assert!(live.hypothesis(session_id, &hypothesis("general kenobi"), start).is_none());
+
+        let update = live.finish_utterance(session_id, "Hello there, General Kenobi.").unwrap();
+        assert!(update.is_final);
+        assert_eq!(update.committed_text, "Hello there, General Kenobi.");
+        assert_eq!(update.text, update.committed_text);
+        assert!(live.finish_utterance(session_id, "").is_none());
+
+        // A long utterance nobody agrees on is cut to Whisper's window, and
+        // the words that fell out of it are committed
+        let start = live.window_start(0, 20 * second);
+        live.hypothesis(session_id, &hypothesis("one two three"), start).unwrap();
+        assert_eq!(live.window_start(0, 40 * second), 10 * second);
+        assert_eq!(live.prompt(), "Hello there, General Kenobi. one two three");
+    }
+
     #[test]
     fn test_session_status_display() {
         let session = SessionState {
             status: SessionStatus::Listening,
             start_time: Utc::now(),
-            capture: None,
+            live: None,
+            cancel: None,
             config: WhisperConfig::default(),
+            device: None,
+            committed_text: String::new(),
+            tentative_text: String::new(),
             transcription: None,
             transcription_timestamp: None,
             error: None,
+            recording: None,
         };
         assert_eq!(session.status_display(), "listening");
     }
+
+    #[test]
+    fn test_transcription_result_segments() {
+        let tx = Transcription::from_segments(vec![Segment {
+            start_ms: 500,
+            end_ms: 1750,
+            text: "hello there".to_string(),
+            no_speech_probability: 0.05,
+            tokens: vec![Token {
+                id: 7,
+                text: " hello".to_string(),
+                start_ms: 500,
+                end_ms: 900,
+                probability: 0.75,
+            }],
+        }]);
+
+        let result = TranscriptionResult::from(tx);
+        assert_eq!(result.start_ms, 500);
+        assert_eq!(result.end_ms, 1750);
+        assert_eq!(result.confidence, Some(0.75));
+        assert_eq!(result.segments[0].tokens[0].end_ms, 900);
+        assert_eq!(result.segment_lines(), vec!["[0.50s - 1.75s] hello there"]);
+    }
+
+    #[tokio::test]
+    async fn test_export_transcript() {
+        let server = VttMcpServer::new();
+        let session_id = Uuid::new_v4();
+        let transcription = TranscriptionResult {
+            text: "hello world".to_string(),
+            confidence: None,
+            start_ms: 0,
+            end_ms: 2000,
+            segments: Vec::new(),
+            language: None,
+            original: None,
+        };
+        let source = HistorySource::File { path: "clip.wav".to_string() };
+        server.store_transcription_in_history(session_id, Utc::now(), WhisperConfig::default(), source, transcription).await;
+
+        let params = ExportTranscriptParams {
+            session_id: Some(session_id.to_string()),
+            format: "srt".to_string(),
+            max_line_chars: None,
+            max_lines: None,
+            max_caption_ms: None,
+        };
+        let result = server.export_transcript(Parameters(params.clone())).await.unwrap();
+        let text = result.content[0].as_text().unwrap().text.clone();
+        assert_eq!(text, "1\n00:00:00,000 --> 00:00:02,000\nhello world\n\n");
+
+        let bad = ExportTranscriptParams { format: "docx".to_string(), ..params };
+        assert!(server.export_transcript(Parameters(bad)).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_history_tools() {
+        let server = VttMcpServer::new();
+        let mut ids = Vec::new();
+        for text in ["buy milk", "call the bank", "bank holiday plans"] {
+            let session_id = Uuid::new_v4();
+            let transcription = TranscriptionResult {
+                text: text.to_string(),
+                confidence: None,
+                start_ms: 0,
+                end_ms: 1000,
+                segments: Vec::new(),
+                language: None,
+                original: None,
+            };
+            let source = HistorySource::Microphone { device: None };
+            server.store_transcription_in_history(session_id, Utc::now(), WhisperConfig::default(), source, transcription).await;
+            ids.push(session_id);
+        }
+
+        let params = ListHistoryParams { query: Some("Bank".to_string()), since: None, until: None, offset: None, limit: None };
+        let result = server.list_history(Parameters(params.clone())).await.unwrap();
+        let text = result.content[0].as_text().unwrap().text.clone();
+        assert!(text.starts_with("Showing 2 of 2 entries"));
+        assert!(text.contains("[mic:default] bank holiday plans"));
+        assert!(!text.contains("buy milk"));
+
+        let bad = ListHistoryParams { since: Some("yesterday".to_string()), ..params };
+        assert!(server.list_history(Parameters(bad)).await.is_err());
+
+        let params = DeleteHistoryParams { session_ids: vec![ids[0].to_string()], all: None };
+        server.delete_history(Parameters(params)).await.unwrap();
+        assert!(server.find_transcription(ids[0]).await.is_none());
+        assert!(server.find_transcription(ids[1]).await.is_some());
+
+        let params = DeleteHistoryParams { session_ids: Vec::new(), all: None };
+        assert!(server.delete_history(Parameters(params)).await.is_err());
+    }
+
+    #[test]
+    fn test_every_tool_declares_output_schema() {
+        let tools = VttMcpServer::tool_router().list_all();
+        assert!(!tools.is_empty());
+        for tool in tools {
+            let schema = tool.output_schema.unwrap_or_else(|| panic!("{} has no output schema", tool.name));
+            assert_eq!(schema.get("type").and_then(|t| t.as_str()), Some("object"), "{}", tool.name);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_structured_results() {
+        let server = VttMcpServer::new();
+        let result = server.list_languages(Parameters(ListLanguagesParams {})).await.unwrap();
+        let languages: LanguagesListResult = result.clone().into_typed().unwrap();
+        assert_eq!(languages.languages[0].code, "auto");
+        assert_eq!(languages.total, SUPPORTED_LANGUAGES.len() + 1);
+        assert!(result.content[0].as_text().unwrap().text.starts_with("Supported Languages:"));
+
+        let session_id = Uuid::new_v4();
+        let transcription = TranscriptionResult {
+            text: "structured".to_string(),
+            confidence: Some(0.9),
+            start_ms: 0,
+            end_ms: 1500,
+            segments: Vec::new(),
+            language: None,
+            original: None,
+        };
+        let source = HistorySource::File { path: "clip.wav".to_string() };
+        server.store_transcription_in_history(session_id, Utc::now(), WhisperConfig::default(), source, transcription).await;
+
+        let result = server.get_last_transcription(Parameters(GetLastTranscriptionParams { session_id: None })).await.unwrap();
+        let last: LastTranscriptionResult = result.into_typed().unwrap();
+        assert_eq!(last.session_id, session_id.to_string());
+        assert_eq!(last.text, "structured");
+        assert_eq!(last.end_ms, 1500);
+    }
+
+    #[test]
+    fn test_parse_timestamp() {
+        assert_eq!(parse_timestamp("2026-03-01").unwrap().to_rfc3339(), "2026-03-01T00:00:00+00:00");
+        assert_eq!(parse_timestamp("2026-03-01T12:00:00+02:00").unwrap().to_rfc3339(), "2026-03-01T10:00:00+00:00");
+        assert!(parse_timestamp("March 1st").is_err());
+    }
+
+    fn server_with_limits(limits: SessionConfig) -> VttMcpServer {
+        let mut config = Config::default();
+        config.history.persist = false;
+        config.sessions = limits;
+        VttMcpServer::with_config(config)
+    }
+
+    #[tokio::test]
+    async fn test_detect_language_validates_params() {
+        let server = VttMcpServer::new();
+        let params = |audio_file: &str, top_n| DetectLanguageParams {
+            audio_file: audio_file.to_string(),
+            top_n,
+            model_path: None,
+            use_gpu: None,
+            threads: None,
+        };
+
+        let err = server.detect_language(Parameters(params("clip.wav", Some(0)))).await.unwrap_err();
+        assert!(err.message.contains("top_n"));
+        let err = server.detect_language(Parameters(params("/nonexistent/clip.wav", None))).await.unwrap_err();
+        assert!(err.message.contains("not found"));
+    }
+
+    #[tokio::test]
+    async fn test_listen_once_validates_limits() {
+        let server = server_with_limits(SessionConfig { max_recording_secs: 30, ..Default::default() });
+        let params = |silence_ms, max_duration_secs| ListenOnceParams {
+            model_path: None,
+            language: None,
+            task: None,
+            include_original: None,
+            use_gpu: None,
+            threads: None,
+            device_name: None,
+            silence_ms,
+            speech_timeout_secs: None,
+            max_duration_secs,
+            initial_prompt: None,
+            vocabulary: None,
+            decoding: None,
+        };
+
+        let err = server.listen_once(Parameters(params(Some(0), None))).await.unwrap_err();
+        assert!(err.message.contains("greater than 0"));
+        let err = server.listen_once(Parameters(params(None, Some(120)))).await.unwrap_err();
+        assert!(err.message.contains("maximum recording duration"));
+        let mut bad_language = params(None, Some(10));
+        bad_language.language = Some("xx".to_string());
+        let err = server.listen_once(Parameters(bad_language)).await.unwrap_err();
+        assert!(err.message.contains("Unsupported language"));
+    }
+
+    #[test]
+    fn test_whisper_config_prompt_overrides() {
+        let mut config = Config::default();
+        config.whisper.initial_prompt = Some("Standup meeting.".to_string());
+        config.whisper.vocabulary = vec!["PipeWire".to_string()];
+        let server = VttMcpServer::with_config(config);
+
+        let cfg = server.whisper_config(WhisperOverrides::default()).unwrap();
+        assert_eq!(cfg.prompt().as_deref(), Some("Glossary: PipeWire. Standup meeting."));
+
+        let cfg = server
+            .whisper_config(WhisperOverrides {
+                initial_prompt: Some(String::new()),
+                vocabulary: Some(vec!["Kubernetes".to_string(), "Helm".to_string()]),
+                ..Default::default()
+            })
+            .unwrap();
+        assert_eq!(cfg.initial_prompt, None);
+        assert_eq!(cfg.prompt().as_deref(), Some("Glossary: Kubernetes, Helm."));
+
+        let bad = WhisperOverrides { language: Some("xx".to_string()), ..Default::default() };
+        assert!(server.whisper_config(bad).is_err());
+    }
+
+    #[test]
+    fn test_whisper_config_decoding() {
+        let mut config = Config::default();
+        config.whisper.decoding.preset = Some(DecodingPreset::Accurate);
+        config.whisper.decoding.beam_size = Some(8);
+        let server = VttMcpServer::with_config(config);
+
+        let cfg = server.whisper_config(WhisperOverrides::default()).unwrap();
+        assert_eq!(cfg.decoding.beam_size, 8);
+
+        let decoding = DecodingOverrides { temperature_increment: Some(0.0), ..Default::default() };
+        let cfg = server.whisper_config(WhisperOverrides { decoding: Some(decoding), ..Default::default() }).unwrap();
+        assert_eq!(cfg.decoding.beam_size, 8);
+        assert_eq!(cfg.decoding.temperature_increment, 0.0);
+
+        let decoding = DecodingOverrides { preset: Some(DecodingPreset::Fast), ..Default::default() };
+        let cfg = server.whisper_config(WhisperOverrides { decoding: Some(decoding), ..Default::default() }).unwrap();
+        assert!(!cfg.decoding.is_beam_search());
+
+        for decoding in [
+            DecodingOverrides { beam_size: Some(0), ..Default::default() },
+            DecodingOverrides { beam_size: Some(9), ..Default::default() },
+        ] {
+            let err = server.whisper_config(WhisperOverrides { decoding: Some(decoding), ..Default::default() }).unwrap_err();
+            assert!(matches!(err, VttError::InvalidParams(_)), "{}", err);
+        }
+
+        // Unknown presets are rejected with the rest of the parameters
+        let params = serde_json::json!({ "decoding": { "preset": "slow" } });
+        assert!(serde_json::from_value::<StartListeningParams>(params).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_plan_clip_chunks() {
+        let server = VttMcpServer::new();
+        let rate = WHISPER_SAMPLE_RATE as usize;
+        let (samples, chunks) = server.plan_clip_chunks(vec![0.0; 10 * rate]).await.unwrap();
+        assert_eq!(samples.len(), 10 * rate);
+        assert_eq!(chunks, [AudioChunk::whole(10 * rate)]);
+
+        let (samples, chunks) = server.plan_clip_chunks(vec![0.0; 95 * rate]).await.unwrap();
+        assert_eq!(samples.len(), 95 * rate);
+        assert!(chunks.len() > 3);
+        assert_eq!(chunks[0].keep_start, 0);
+        assert_eq!(chunks.last().unwrap().keep_end, 95 * rate);
+        assert!(chunks.iter().all(|c| c.len() <= 30 * rate), "{:?}", chunks);
+    }
+
+    #[test]
+    fn test_whisper_config_task() {
+        let server = VttMcpServer::new();
+        let cfg = server.whisper_config(WhisperOverrides::default()).unwrap();
+        assert_eq!(cfg.task(), Task::Transcribe);
+
+        let task = Some(Task::Translate);
+        let cfg = server.whisper_config(WhisperOverrides { task, ..Default::default() }).unwrap();
+        assert_eq!(cfg.task(), Task::Translate);
+
+        // Unknown tasks are rejected with the rest of the parameters
+        let params = serde_json::json!({ "audio_file": "clip.wav", "task": "translate" });
+        let params: TranscribeClipParams = serde_json::from_value(params).unwrap();
+        assert_eq!(params.task, Some(Task::Translate));
+        let params = serde_json::json!({ "audio_file": "clip.wav", "task": "summarize" });
+        assert!(serde_json::from_value::<TranscribeClipParams>(params).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_concurrent_session_limit() {
+        let server = server_with_limits(SessionConfig { max_concurrent: 1, ..Default::default() });
+        server.sessions.lock().await.insert(Uuid::new_v4(), listening_session());
+
+        let params = StartListeningParams {
+            model_path: None,
+            language: None,
+            task: None,
+            use_gpu: None,
+            threads: None,
+            device_name: None,
+            initial_prompt: None,
+            vocabulary: None,
+            decoding: None,
+            carry_prompt: None,
+        };
+        let err = server.start_listening(Parameters(params)).await.unwrap_err();
+        assert!(err.message.contains("limit 1"), "{}", err.message);
+    }
+
+    #[tokio::test]
+    async fn test_reap_sessions() {
+        let server = server_with_limits(SessionConfig {
+            max_concurrent: 0,
+            max_recording_secs: 60,
+            finished_ttl_secs: 60,
+        });
+        let long_ago = Utc::now() - chrono::Duration::minutes(5);
+        let overdue = Uuid::new_v4();
+        let recent = Uuid::new_v4();
+        let finished = Uuid::new_v4();
+        {
+            let mut sessions = server.sessions.lock().await;
+            sessions.insert(overdue, SessionState { start_time: long_ago, ..listening_session() });
+            sessions.insert(recent, listening_session());
+            sessions.insert(finished, SessionState {
+                status: SessionStatus::Stopped,
+                start_time: long_ago,
+                transcription_timestamp: Some(long_ago),
+                ..listening_session()
+            });
+        }
+
+        server.reap_sessions().await;
+
+        let sessions = server.sessions.lock().await;
+        assert!(!sessions.contains_key(&finished));
+        assert_eq!(sessions[&recent].status, SessionStatus::Listening);
+        let session = &sessions[&overdue];
+        assert_eq!(session.status, SessionStatus::Error);
+        assert!(session.error.as_deref().unwrap().contains("maximum recording duration (60s)"));
+        assert!(session.transcription_timestamp.is_some());
+    }
+
+    #[tokio::test]
+    async fn test_list_status_and_cancel_sessions() {
+        let server = VttMcpServer::new();
+        let listening = Uuid::new_v4();
+        let failed = Uuid::new_v4();
+        {
+            let mut sessions = server.sessions.lock().await;
+            sessions.insert(listening, listening_session());
+            sessions.insert(failed, SessionState {
+                status: SessionStatus::Error,
+                error: Some("No audio captured".to_string()),
+                transcription_timestamp: Some(Utc::now()),
+                ..listening_session()
+            });
+        }
+
+        let result = server.list_sessions(Parameters(ListSessionsParams { status: None })).await.unwrap();
+        let all: ListSessionsResult = result.into_typed().unwrap();
+        assert_eq!(all.sessions.len(), 2);
+
+        let params = ListSessionsParams { status: Some("error".to_string()) };
+        let errors: ListSessionsResult = server.list_sessions(Parameters(params)).await.unwrap().into_typed().unwrap();
+        assert_eq!(errors.sessions.len(), 1);
+        assert_eq!(errors.sessions[0].session_id, failed.to_string());
+        assert!(server.list_sessions(Parameters(ListSessionsParams { status: Some("done".to_string()) })).await.is_err());
+
+        let params = GetSessionStatusParams { session_id: failed.to_string() };
+        let info: SessionInfo = server.get_session_status(Parameters(params)).await.unwrap().into_typed().unwrap();
+        assert_eq!(info.status, "error");
+        assert_eq!(info.error.as_deref(), Some("No audio captured"));
+
+        let params = CancelSessionParams { session_id: listening.to_string() };
+        let cancelled: CancelSessionResult = server.cancel_session(Parameters(params.clone())).await.unwrap().into_typed().unwrap();
+        assert_eq!(cancelled.previous_status, "listening");
+        assert!(!server.sessions.lock().await.contains_key(&listening));
+        assert!(server.cancel_session(Parameters(params)).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_stop_listening_without_audio() {
+        let server = VttMcpServer::new();
+        let session_id = Uuid::new_v4();
+        server.sessions.lock().await.insert(session_id, listening_session());
+
+        let params = StopListeningParams {
+            session_id: session_id.to_string(),
+            transcribe: None,
+            include_original: None,
+        };
+        server.stop_listening(Parameters(params.clone())).await.unwrap();
+
+        let sessions = server.sessions.lock().await;
+        let session = &sessions[&session_id];
+        assert_eq!(session.status, SessionStatus::Error);
+        assert_eq!(session.error.as_deref(), Some("No audio captured"));
+        drop(sessions);
+
+        // A stopped session can't be stopped again
+        assert!(server.stop_listening(Parameters(params)).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_listen_once_is_a_session() {
+        let server = server_with_limits(SessionConfig { max_concurrent: 1, ..Default::default() });
+        let session_id = Uuid::new_v4();
+        let (cancel_tx, mut cancel_rx) = oneshot::channel();
+        server.sessions.lock().await.insert(session_id, SessionState { cancel: Some(cancel_tx), ..listening_session() });
+
+        // A capturing listen_once takes up a listening slot
+        let params = ListenOnceParams {
+            model_path: None,
+            language: None,
+            task: None,
+            include_original: None,
+            use_gpu: None,
+            threads: None,
+            device_name: None,
+            silence_ms: None,
+            speech_timeout_secs: None,
+            max_duration_secs: None,
+            initial_prompt: None,
+            vocabulary: None,
+            decoding: None,
+        };
+        let err = server.listen_once(Parameters(params)).await.unwrap_err();
+        assert!(err.message.contains("already listening"), "{}", err.message);
+
+        // Only cancel_session stops it
+        let stop = StopListeningParams { session_id: session_id.to_string(), transcribe: None, include_original: None };
+        let err = server.stop_listening(Parameters(stop)).await.unwrap_err();
+        assert!(err.message.contains("cancel_session"));
+        assert_eq!(cancel_rx.try_recv(), Err(oneshot::error::TryRecvError::Empty));
+
+        let cancel = CancelSessionParams { session_id: session_id.to_string() };
+        server.cancel_session(Parameters(cancel)).await.unwrap();
+        assert_eq!(cancel_rx.try_recv(), Err(oneshot::error::TryRecvError::Closed));
+    }
+
+    #[tokio::test]
+    async fn test_stop_listening_marks_failed_stop() {
+        let server = VttMcpServer::new();
+        let session_id = Uuid::new_v4();
+        let (stop_tx, stop_rx) = oneshot::channel();
+        let task = tokio::spawn(async move {
+            let _ = stop_rx.await;
+            panic!("live transcription crashed")
+        });
+        let live = LiveSession { stop_tx, task };
+        server.sessions.lock().await.insert(session_id, SessionState { live: Some(live), ..listening_session() });
+
+        let params = StopListeningParams { session_id: session_id.to_string(), transcribe: None, include_original: None };
+        assert!(server.stop_listening(Parameters(params)).await.is_err());
+
+        let sessions = server.sessions.lock().await;
+        let session = &sessions[&session_id];
+        assert_eq!(session.status, SessionStatus::Error);
+        assert!(session.error.as_deref().is_some_and(|e| e.contains("Live transcription task failed")));
+        assert!(session.transcription_timestamp.is_some());
+    }
 }
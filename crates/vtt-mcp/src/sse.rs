@@ -0,0 +1,197 @@
+//! Legacy HTTP+SSE transport
+//!
+//! The HTTP transport of MCP 2024-11-05, for clients that predate Streamable
+//! HTTP. A client opens an event stream with `GET /sse`; its first `endpoint`
+//! event names the URL the client `POST`s its messages to, and the server's
+//! messages come back as `message` events on the stream. rmcp only serves
+//! Streamable HTTP, so these endpoints are implemented here on top of its
+//! [`Transport`] trait.
+
+use crate::VttMcpServer;
+use axum::extract::{Query, State};
+use axum::http::StatusCode;
+use axum::response::sse::{Event, KeepAlive, Sse};
+use axum::routing::{get, post};
+use axum::{Json, Router};
+use rmcp::model::{ClientJsonRpcMessage, ServerJsonRpcMessage};
+use rmcp::transport::Transport;
+use rmcp::{RoleServer, ServiceExt};
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::future::Future;
+use std::io;
+use std::sync::{Arc, Mutex};
+use tokio::sync::mpsc;
+use tokio_stream::wrappers::UnboundedReceiverStream;
+use tokio_stream::{Stream, StreamExt};
+use uuid::Uuid;
+
+/// Routes for the legacy transport: `GET /sse` and `POST /message`
+///
+/// Every event stream gets its own handler, but all of them share the
+/// server's sessions, history and model pool.
+pub fn router(server: VttMcpServer) -> Router {
+    Router::new()
+        .route("/sse", get(open_stream))
+        .route("/message", post(post_message))
+        .with_state(SseState { server, streams: Arc::default() })
+}
+
+#[derive(Clone)]
+struct SseState {
+    server: VttMcpServer,
+    /// Where to deliver the messages posted for each open event stream
+    streams: Arc<Mutex<HashMap<Uuid, mpsc::UnboundedSender<ClientJsonRpcMessage>>>>,
+}
+
+/// Query of `POST /message`
+#[derive(Deserialize)]
+struct MessageQuery {
+    #[serde(rename = "sessionId")]
+    session_id: Uuid,
+}
+
+/// One client's event stream and the messages it posts, as an rmcp transport
+struct SseTransport {
+    incoming: mpsc::UnboundedReceiver<ClientJsonRpcMessage>,
+    outgoing: mpsc::UnboundedSender<ServerJsonRpcMessage>,
+}
+
+impl Transport<RoleServer> for SseTransport {
+    type Error = io::Error;
+
+    fn send(&mut self, item: ServerJsonRpcMessage) -> impl Future<Output = Result<(), Self::Error>> + Send + 'static {
+        let sent = self
+            .outgoing
+            .send(item)
+            .map_err(|_| io::Error::new(io::ErrorKind::BrokenPipe, "SSE stream closed"));
+        std::future::ready(sent)
+    }
+
+    fn receive(&mut self) -> impl Future<Output = Option<ClientJsonRpcMessage>> + Send {
+        self.incoming.recv()
+    }
+
+    async fn close(&mut self) -> Result<(), Self::Error> {
+        self.incoming.close();
+        Ok(())
+    }
+}
+
+/// Forgets an event stream's session once the stream is dropped
+struct StreamGuard {
+    streams: Arc<Mutex<HashMap<Uuid, mpsc::UnboundedSender<ClientJsonRpcMessage>>>>,
+    session_id: Uuid,
+}
+
+impl Drop for StreamGuard {
+    fn drop(&mut self) {
+        self.streams.lock().unwrap().remove(&self.session_id);
+    }
+}
+
+/// `GET /sse`: open an event stream and serve a new client over it
+async fn open_stream(State(state): State<SseState>) -> Sse<impl Stream<Item = Result<Event, axum::Error>>> {
+    let session_id = Uuid::new_v4();
+    let (deliver, incoming) = mpsc::unbounded_channel();
+    let (outgoing, messages) = mpsc::unbounded_channel();
+    state.streams.lock().unwrap().insert(session_id, deliver);
+
+    // Ends once the stream is gone: dropping it drops the guard, which closes
+    // `incoming`
+    let handler = state.server.for_connection();
+    tokio::spawn(async move {
+        match handler.serve(SseTransport { incoming, outgoing }).await {
+            Ok(service) => {
+                let _ = service.waiting().await;
+            }
+            Err(e) => tracing::warn!(session_id = %session_id, error = %e, "SSE client failed to initialize"),
+        }
+        tracing::debug!(session_id = %session_id, "SSE client disconnected");
+    });
+
+    tracing::debug!(session_id = %session_id, "SSE client connected");
+    let endpoint = Event::default().event("endpoint").data(format!("/message?sessionId={}", session_id));
+    let guard = StreamGuard { streams: state.streams, session_id };
+    let messages = UnboundedReceiverStream::new(messages).map(move |message| {
+        // Owned by the stream so the session goes with it
+        let _ = &guard;
+        Event::default().event("message").json_data(message)
+    });
+    Sse::new(tokio_stream::once(Ok(endpoint)).chain(messages)).keep_alive(KeepAlive::default())
+}
+
+/// `POST /message?sessionId=…`: hand a client message to its stream's handler
+async fn post_message(
+    State(state): State<SseState>,
+    Query(query): Query<MessageQuery>,
+    Json(message): Json<ClientJsonRpcMessage>,
+) -> StatusCode {
+    let streams = state.streams.lock().unwrap();
+    match streams.get(&query.session_id) {
+        Some(deliver) if deliver.send(message).is_ok() => StatusCode::ACCEPTED,
+        _ => StatusCode::NOT_FOUND,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::io::{AsyncBufReadExt, AsyncReadExt, AsyncWriteExt, BufReader};
+    use tokio::net::{TcpListener, TcpStream};
+
+    /// Data of the next event called `name` on an open event stream
+    async fn next_event(stream: &mut BufReader<TcpStream>, name: &str) -> String {
+        let mut event = String::new();
+        loop {
+            let mut line = String::new();
+            assert!(stream.read_line(&mut line).await.unwrap() > 0, "event stream closed");
+            let line = line.trim_end();
+            if let Some(e) = line.strip_prefix("event: ") {
+                event = e.to_string();
+            } else if let Some(data) = line.strip_prefix("data: ") {
+                if event == name {
+                    return data.to_string();
+                }
+            }
+        }
+    }
+
+    /// POST `body` to `path` and return the response's status line
+    async fn post(addr: std::net::SocketAddr, path: &str, body: &str) -> String {
+        let mut conn = TcpStream::connect(addr).await.unwrap();
+        let request = format!(
+            "POST {} HTTP/1.1\r\nHost: {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+            path,
+            addr,
+            body.len(),
+            body
+        );
+        conn.write_all(request.as_bytes()).await.unwrap();
+        let mut response = String::new();
+        conn.read_to_string(&mut response).await.unwrap();
+        response.lines().next().unwrap_or_default().to_string()
+    }
+
+    #[tokio::test]
+    async fn test_sse_initialize() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move { axum::serve(listener, router(VttMcpServer::new())).await });
+
+        let mut events = BufReader::new(TcpStream::connect(addr).await.unwrap());
+        let request = format!("GET /sse HTTP/1.1\r\nHost: {}\r\nAccept: text/event-stream\r\n\r\n", addr);
+        events.get_mut().write_all(request.as_bytes()).await.unwrap();
+        let endpoint = next_event(&mut events, "endpoint").await;
+        assert!(endpoint.starts_with("/message?sessionId="));
+
+        let initialize = r#"{"jsonrpc":"2.0","id":1,"method":"initialize","params":{"protocolVersion":"2024-11-05","capabilities":{},"clientInfo":{"name":"test","version":"0.1.0"}}}"#;
+        assert!(post(addr, &endpoint, initialize).await.contains("202"));
+        let response = next_event(&mut events, "message").await;
+        assert!(response.contains(r#""id":1"#));
+        assert!(response.contains("serverInfo"));
+
+        let unknown = format!("/message?sessionId={}", Uuid::new_v4());
+        assert!(post(addr, &unknown, initialize).await.contains("404"));
+    }
+}